@@ -0,0 +1,17 @@
+//! Sets the `tz_subset` cfg when the `tz-subset` feature is enabled and
+//! `CHRONO_TZ_TIMEZONE_FILTER` selects a subset of the timezones, which leaves out
+//! the `Tz` constants of the zones that may not exist.
+use std::env;
+
+const FILTER_ENV_VAR_NAME: &str = "CHRONO_TZ_TIMEZONE_FILTER";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={FILTER_ENV_VAR_NAME}");
+    println!("cargo:rustc-check-cfg=cfg(tz_subset)");
+
+    let filtered = env::var(FILTER_ENV_VAR_NAME).is_ok_and(|filter| !filter.is_empty());
+    if env::var_os("CARGO_FEATURE_TZ_SUBSET").is_some() && filtered {
+        println!("cargo:rustc-cfg=tz_subset");
+    }
+}
@@ -1,4 +1,4 @@
-const { getAllRecurrencesBetween } = require('../../../pkg/nodejs/rrule.js');
+const { getAllRecurrencesBetween, getRecurrencesBetween } = require('../../../pkg/nodejs/rrule.js');
 
 const rule_set = [
   // 'DTSTART:20120201T093000Z',
@@ -14,4 +14,13 @@ const data = getAllRecurrencesBetween(
   10,
 );
 
-console.log(data);
\ No newline at end of file
+console.log(data);
+
+const { dates, limited, reason } = getRecurrencesBetween(
+  rule_set,
+  new Date(2021, 0, 1).toISOString(),
+  new Date(2022, 0, 1).toISOString(),
+  { maxResults: 5 },
+);
+
+console.log(dates, limited ? `more occurrences available (${reason})` : 'all occurrences');
@@ -1,4 +1,4 @@
-import init, { getAllRecurrencesBetween } from '../../../pkg/web/rrule.js';
+import init, { getAllRecurrencesBetween, getAllTimestampsBetween } from '../../../pkg/web/rrule.js';
 import { tryParseEventRecurrenceRules, createValidDateTimeFromISO, getInstanceStartAt } from './rrule_utils.js';
 
 function executeRRulePerformanceTest(ruleSet, after, before, limit) {
@@ -11,6 +11,11 @@ function executeRustRRulePerformanceTest(ruleSet, after, before, limit) {
     return getAllRecurrencesBetween(ruleSet, after, before, limit);
   }, "rust-rrule");
 }
+function executeRustRRuleTimestampsPerformanceTest(ruleSet, after, before, limit) {
+  return executeWork(() => {
+    return getAllTimestampsBetween(ruleSet, after, before, limit);
+  }, "rust-rrule (timestamps)");
+}
 
 const performance = window.performance;
 
@@ -55,6 +60,9 @@ function executePerformanceTests() {
   rustRRuleResultDiv.innerHTML = "Executing ...";
   rustRRuleResultDiv.innerHTML = executeRustRRulePerformanceTest(ruleSet, afterDateString, beforeDateString, limit);
 
+  const rustRRuleTimestampsResultDiv = document.querySelector("#rustRRuleTimestampsResult");
+  rustRRuleTimestampsResultDiv.innerHTML = executeRustRRuleTimestampsPerformanceTest(ruleSet, afterDateString, beforeDateString, limit);
+
   setTimeout(() => {
     const rruleResultDiv = document.querySelector("#rruleResult");
     rruleResultDiv.innerHTML = "Executing ...";
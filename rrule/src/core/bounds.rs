@@ -0,0 +1,223 @@
+use super::{rrule::RRule, rruleset::RRuleSet, timezone::Tz};
+use crate::{RRuleError, ValidatorLimits};
+use chrono::DateTime;
+
+impl RRule {
+    /// Returns the equivalent rule with an `UNTIL` instead of a `COUNT`, for systems
+    /// which only support the former. `UNTIL` is set to the last occurrence, in UTC,
+    /// or in the timezone of `dt_start` if it is local or floating, as required by
+    /// RFC 5545. A rule without `COUNT` is returned as is.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{Frequency, RRule, Tz};
+    ///
+    /// let dt_start = Tz::Europe__Berlin.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule = RRule::new(Frequency::Weekly).count(3).validate(dt_start).unwrap();
+    /// let rrule = rrule.count_to_until(dt_start).unwrap();
+    /// assert_eq!(
+    ///     rrule.to_string(),
+    ///     "FREQ=WEEKLY;UNTIL=20210115T080000Z;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=FR"
+    /// );
+    /// assert_eq!(rrule.until_to_count(dt_start).unwrap().get_count(), Some(3));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError::IterError`] if the occurrences can't be found within
+    /// [`ValidatorLimits::DEFAULT`].
+    pub fn count_to_until(&self, dt_start: DateTime<Tz>) -> Result<Self, RRuleError> {
+        if self.count.is_none() {
+            return Ok(self.clone());
+        }
+        let (last, _) = last_occurrence(&RRuleSet::new(dt_start).rrule(self.clone()), None)?;
+        let mut rrule = self.clone().into_builder();
+        rrule.count = None;
+        rrule.until = Some(until_at(last, &dt_start));
+        rrule.validate(dt_start)
+    }
+
+    /// Returns the equivalent rule with a `COUNT` instead of an `UNTIL`, set to the
+    /// number of occurrences up to `UNTIL`. A rule without `UNTIL` is returned as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError::IterError`] if the rule has no occurrences, or if they
+    /// can't be counted within [`ValidatorLimits::DEFAULT`].
+    pub fn until_to_count(&self, dt_start: DateTime<Tz>) -> Result<Self, RRuleError> {
+        if self.until.is_none() {
+            return Ok(self.clone());
+        }
+        let (_, count) = last_occurrence(&RRuleSet::new(dt_start).rrule(self.clone()), None)?;
+        let mut rrule = self.clone().into_builder();
+        rrule.until = None;
+        rrule.count = Some(count);
+        rrule.validate(dt_start)
+    }
+}
+
+impl RRuleSet {
+    /// Replaces the `COUNT` of every rrule by an `UNTIL`, like
+    /// [`RRule::count_to_until`], but takes `COUNT` to be the number of visible
+    /// occurrences: those excluded by an exdate or exrule are skipped, so `UNTIL`
+    /// is set to the `COUNT`th occurrence that remains.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet =
+    ///     "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3\nEXDATE:20210102T090000Z"
+    ///         .parse()
+    ///         .unwrap();
+    /// let rrule_set = rrule_set.visible_count_to_until().unwrap();
+    /// assert_eq!(
+    ///     rrule_set.get_rrule()[0].to_string(),
+    ///     "FREQ=DAILY;UNTIL=20210104T090000Z;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+    /// );
+    /// assert_eq!(rrule_set.all(10).dates.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError::IterError`] if a rule has no visible occurrences, or if
+    /// they can't be found within the validation limits of the set.
+    pub fn visible_count_to_until(self) -> Result<Self, RRuleError> {
+        let limits = if self.limited {
+            self.limits
+        } else {
+            ValidatorLimits::UNLIMITED
+        };
+        let mut rrules = Vec::with_capacity(self.rrule.len());
+        for rrule in &self.rrule {
+            let Some(count) = rrule.count else {
+                rrules.push(rrule.clone());
+                continue;
+            };
+            let mut unbounded = rrule.clone().into_builder();
+            unbounded.count = None;
+            let unbounded = Self {
+                rrule: vec![unbounded.validate_inner(self.dt_start, &limits, false)?],
+                rdate: vec![],
+                ..self.clone()
+            };
+            let (last, _) = last_occurrence(&unbounded, Some(count))?;
+
+            let mut bounded = rrule.clone().into_builder();
+            bounded.count = None;
+            bounded.until = Some(until_at(last, &self.dt_start));
+            rrules.push(bounded.validate_inner(
+                self.dt_start,
+                &limits,
+                self.reject_never_occurring,
+            )?);
+        }
+        Ok(Self {
+            rrule: rrules,
+            ..self
+        })
+    }
+}
+
+/// Returns the last of the first `count` occurrences of `rrule_set`, or of all of
+/// them if `count` is `None`, together with the number of occurrences.
+fn last_occurrence(
+    rrule_set: &RRuleSet,
+    count: Option<u32>,
+) -> Result<(DateTime<Tz>, u32), RRuleError> {
+    let rrule_set = if rrule_set.limited {
+        rrule_set.clone()
+    } else {
+        rrule_set.clone().limit()
+    };
+    let mut iter = rrule_set.into_iter();
+    let (mut last, mut found) = (None, 0);
+    while count.map_or(true, |count| found < count) {
+        let Some(date) = iter.next() else {
+            break;
+        };
+        last = Some(date);
+        found += 1;
+    }
+    if let Some(reason) = iter.limit_reason() {
+        return Err(RRuleError::new_iter_err(format!(
+            "The occurrences could not be counted, as {reason}."
+        )));
+    }
+    last.map(|last| (last, found))
+        .ok_or_else(|| RRuleError::new_iter_err("The rule has no occurrences."))
+}
+
+/// Returns `last` in the timezone that `UNTIL` must have for `dt_start`.
+fn until_at(last: DateTime<Tz>, dt_start: &DateTime<Tz>) -> DateTime<Tz> {
+    match dt_start.timezone() {
+        // The occurrences are in the local or floating timezone of the start date.
+        Tz::Local(_) | Tz::Floating => last,
+        Tz::Tz(_) | Tz::FixedOffset(_) => last.with_timezone(&Tz::UTC),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Frequency, Unvalidated};
+    use chrono::TimeZone;
+
+    fn validated(rrule: RRule<Unvalidated>, dt_start: DateTime<Tz>) -> RRule {
+        rrule.validate(dt_start).unwrap()
+    }
+
+    #[test]
+    fn converts_floating_rules_without_utc() {
+        let dt_start = Tz::FLOATING.with_ymd_and_hms(2021, 3, 1, 9, 0, 0).unwrap();
+        let rrule = validated(RRule::new(Frequency::Monthly).count(2), dt_start);
+        let rrule = rrule.count_to_until(dt_start).unwrap();
+        assert_eq!(
+            rrule.get_until(),
+            Some(&Tz::FLOATING.with_ymd_and_hms(2021, 4, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(rrule.get_count(), None);
+    }
+
+    #[test]
+    fn keeps_rules_without_the_bound() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        let rrule = validated(RRule::new(Frequency::Daily), dt_start);
+        assert_eq!(rrule.count_to_until(dt_start), Ok(rrule.clone()));
+        assert_eq!(rrule.until_to_count(dt_start), Ok(rrule));
+    }
+
+    #[test]
+    fn skips_excluded_occurrences() {
+        let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2\nRRULE:FREQ=WEEKLY;UNTIL=20210201T000000Z\nEXDATE:20210101T090000Z,20210103T090000Z"
+            .parse()
+            .unwrap();
+        let converted = rrule_set.clone().visible_count_to_until().unwrap();
+        assert_eq!(
+            converted.get_rrule()[0].get_until(),
+            Some(&Tz::UTC.with_ymd_and_hms(2021, 1, 4, 9, 0, 0).unwrap())
+        );
+        assert_eq!(converted.get_rrule()[1], rrule_set.get_rrule()[1]);
+    }
+
+    #[test]
+    fn reports_why_the_occurrences_could_not_be_counted() {
+        let rrule_set: RRuleSet =
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30;COUNT=1"
+                .parse()
+                .unwrap();
+        let err = rrule_set
+            .limits(ValidatorLimits::DEFAULT.max_iterations(100))
+            .visible_count_to_until()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            RRuleError::new_iter_err(
+                "The occurrences could not be counted, as no occurrence was found within the max number of iterations."
+            )
+        );
+    }
+}
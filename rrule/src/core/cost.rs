@@ -0,0 +1,235 @@
+use super::{rrule::Frequency, rruleset::RRuleSet, timezone::Tz};
+use crate::iter::matching_days;
+use crate::RRule;
+use chrono::{DateTime, Datelike, Duration, NaiveDate};
+
+/// Weekdays and leap years repeat every 28 years (between 1901 and 2099),
+/// which is enough to sample how many days pass the filters of a rule.
+const SAMPLE_YEARS: i32 = 28;
+
+const SECONDS_IN_A_DAY: f64 = 86_400.0;
+
+/// Estimated cost of expanding an [`RRuleSet`], see [`RRuleSet::estimate_cost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CostEstimate {
+    /// Number of periods (years, months, weeks, days, hours, minutes or seconds,
+    /// depending on the frequency) the iterators visit.
+    pub periods: u64,
+    /// Number of occurrences produced.
+    pub occurrences: u64,
+}
+
+impl RRuleSet {
+    /// Estimates the cost of expanding the set over `window`, starting at `DTSTART`.
+    ///
+    /// The estimate is based on the frequency, interval and timeset of the rules and
+    /// on the share of days that pass their `BYxxx` filters, without iterating them.
+    /// This allows rejecting expensive rules from untrusted input before calling
+    /// [`RRuleSet::all`]. Note that the iteration always starts at `DTSTART`, so the
+    /// window of a set with [`RRuleSet::before`] is the time between the two.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet =
+    ///     "DTSTART:20210101T090000Z\nRRULE:FREQ=SECONDLY;BYMONTH=2;BYHOUR=9,10;BYMINUTE=0,30"
+    ///         .parse()
+    ///         .unwrap();
+    /// let cost = rrule_set.estimate_cost(Duration::days(365));
+    /// // 28 days in February, with 2 * 2 minutes of 60 seconds each.
+    /// assert!((6_000..7_000).contains(&cost.occurrences));
+    /// // Every second of February is visited, and a single one for the other days.
+    /// assert!((2_000_000..3_000_000).contains(&cost.periods));
+    /// ```
+    #[must_use]
+    pub fn estimate_cost(&self, window: Duration) -> CostEstimate {
+        let (mut periods, mut occurrences) = (0.0, 0.0);
+        for rrule in &self.rrule {
+            let (rule_periods, rule_occurrences) = rule_cost(rrule, &self.dt_start, window);
+            periods += rule_periods;
+            occurrences += rule_occurrences;
+        }
+        for exrule in &self.exrule {
+            periods += rule_cost(exrule, &self.dt_start, window).0;
+        }
+        let window_end = self.dt_start.checked_add_signed(window);
+        occurrences += self
+            .rdate
+            .iter()
+            .filter(|rdate| {
+                **rdate >= self.dt_start && window_end.map_or(true, |end| **rdate <= end)
+            })
+            .count() as f64;
+
+        CostEstimate {
+            periods: periods.ceil() as u64,
+            occurrences: occurrences.ceil() as u64,
+        }
+    }
+}
+
+/// Returns the estimated number of periods visited and occurrences produced by `rrule`.
+fn rule_cost(rrule: &RRule, dt_start: &DateTime<Tz>, window: Duration) -> (f64, f64) {
+    let window = match rrule.until {
+        Some(until) => window.min(until - *dt_start),
+        None => window,
+    };
+    let window_seconds = window.num_seconds().max(0) as f64;
+    let window_days = window_seconds / SECONDS_IN_A_DAY;
+    let interval = f64::from(rrule.interval.max(1));
+    let day_share = matching_day_share(rrule, dt_start);
+    let len = |values: usize| values as f64;
+
+    let (periods, mut occurrences) = match rrule.freq {
+        Frequency::Yearly | Frequency::Monthly | Frequency::Weekly | Frequency::Daily => {
+            let period_days = match rrule.freq {
+                Frequency::Yearly => 365.2425,
+                Frequency::Monthly => 30.436_875,
+                Frequency::Weekly => 7.0,
+                _ => 1.0,
+            };
+            let timeset = len(rrule.by_hour.len() * rrule.by_minute.len() * rrule.by_second.len());
+            (
+                window_days / (period_days * interval),
+                window_days / interval * day_share * timeset,
+            )
+        }
+        Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+            let (period_seconds, expansion) = match rrule.freq {
+                Frequency::Hourly => (3600.0, len(rrule.by_minute.len() * rrule.by_second.len())),
+                Frequency::Minutely => (60.0, len(rrule.by_second.len())),
+                _ => (1.0, 1.0),
+            };
+            // Share of the periods of a day which pass the time filters.
+            let share = |values: &[u8], of: f64, applies: bool| {
+                if applies && !values.is_empty() {
+                    len(values.len()) / of
+                } else {
+                    1.0
+                }
+            };
+            let time_share = share(&rrule.by_hour, 24.0, true)
+                * share(&rrule.by_minute, 60.0, rrule.freq >= Frequency::Minutely)
+                * share(&rrule.by_second, 60.0, rrule.freq >= Frequency::Secondly);
+            let day_periods = window_seconds / (period_seconds * interval) * day_share;
+            (
+                // Days that don't pass the filters are skipped at once.
+                day_periods + window_days * (1.0 - day_share),
+                day_periods * time_share * expansion,
+            )
+        }
+    };
+
+    if !rrule.by_set_pos.is_empty() {
+        occurrences = occurrences.min(periods * len(rrule.by_set_pos.len()));
+    }
+    match rrule.count {
+        // The iteration stops once `COUNT` occurrences are found.
+        Some(count) if f64::from(count) < occurrences => {
+            let share = f64::from(count) / occurrences;
+            (periods * share, f64::from(count))
+        }
+        _ => (periods, occurrences),
+    }
+}
+
+/// Returns the share of the days in a sample of years which pass the filters of `rrule`.
+fn matching_day_share(rrule: &RRule, dt_start: &DateTime<Tz>) -> f64 {
+    let last_year = dt_start.year().saturating_add(SAMPLE_YEARS - 1);
+    let total_days = NaiveDate::from_ymd_opt(last_year + 1, 1, 1)
+        .map_or(0, |end| (end - dt_start.date_naive()).num_days());
+    if total_days <= 0 {
+        return 1.0;
+    }
+    let matching_days = matching_days(rrule, dt_start, last_year).count();
+    matching_days as f64 / total_days as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(rrule_set: &str, window: Duration) -> CostEstimate {
+        rrule_set.parse::<RRuleSet>().unwrap().estimate_cost(window)
+    }
+
+    #[test]
+    fn estimates_daily_rules() {
+        let cost = estimate(
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=9,12,15",
+            Duration::days(100),
+        );
+        assert_eq!(
+            cost,
+            CostEstimate {
+                periods: 100,
+                occurrences: 300
+            }
+        );
+
+        let cost = estimate(
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TU",
+            Duration::weeks(10),
+        );
+        assert_eq!(
+            cost,
+            CostEstimate {
+                periods: 5,
+                occurrences: 10
+            }
+        );
+    }
+
+    #[test]
+    fn estimates_sparse_secondly_rules() {
+        let dense = estimate(
+            "DTSTART:20210101T000000Z\nRRULE:FREQ=SECONDLY",
+            Duration::days(1),
+        );
+        assert_eq!(
+            dense,
+            CostEstimate {
+                periods: 86_400,
+                occurrences: 86_400
+            }
+        );
+
+        let sparse = estimate(
+            "DTSTART:20210101T000000Z\nRRULE:FREQ=SECONDLY;BYMONTHDAY=1;BYHOUR=1",
+            Duration::days(365),
+        );
+        // About 12 days are visited second by second, the others at once.
+        assert!((11 * 86_400..13 * 86_400).contains(&sparse.periods));
+        assert!((11 * 3_600..13 * 3_600).contains(&sparse.occurrences));
+    }
+
+    #[test]
+    fn estimates_are_bounded_by_count_and_until() {
+        let cost = estimate(
+            "DTSTART:20210101T000000Z\nRRULE:FREQ=MINUTELY;COUNT=30",
+            Duration::days(1),
+        );
+        assert_eq!(
+            cost,
+            CostEstimate {
+                periods: 30,
+                occurrences: 30
+            }
+        );
+
+        let cost = estimate(
+            "DTSTART:20210101T000000Z\nRRULE:FREQ=HOURLY;UNTIL=20210101T100000Z\nRDATE:20210105T000000Z",
+            Duration::days(1),
+        );
+        assert_eq!(
+            cost,
+            CostEstimate {
+                periods: 10,
+                occurrences: 10
+            }
+        );
+    }
+}
@@ -1,11 +1,5 @@
 use super::timezone::Tz;
-use chrono::{Datelike, Duration, NaiveTime, Timelike};
-
-pub(crate) fn duration_from_midnight(time: NaiveTime) -> Duration {
-    Duration::hours(i64::from(time.hour()))
-        + Duration::minutes(i64::from(time.minute()))
-        + Duration::seconds(i64::from(time.second()))
-}
+use chrono::{Datelike, Timelike};
 
 pub(crate) fn get_month(dt: &chrono::DateTime<Tz>) -> u8 {
     u8::try_from(dt.month()).expect("month is between 1-12 which is covered by u8")
@@ -27,15 +21,15 @@ pub(crate) fn get_second(dt: &chrono::DateTime<Tz>) -> u8 {
     u8::try_from(dt.second()).expect("second is between 0-59 which is covered by u8")
 }
 
-/// Generates an iCalendar date-time string format with the prefix symbols.
-/// Like: `:19970714T173000Z` or `;TZID=America/New_York:19970714T133000`
+/// Splits an iCalendar date-time into its property parameters and value.
+/// Like: `("", "19970714T173000Z")` or `(";TZID=America/New_York", "19970714T133000")`
 /// ref: <https://tools.ietf.org/html/rfc5545#section-3.3.5>
-pub(crate) fn datetime_to_ical_format(dt: &chrono::DateTime<Tz>) -> String {
+pub(crate) fn datetime_to_ical_parts(dt: &chrono::DateTime<Tz>) -> (String, String) {
     let mut tz_prefix = String::new();
     let mut tz_postfix = String::new();
     let tz = dt.timezone();
     match tz {
-        Tz::Local(_) => {}
+        Tz::Local(_) | Tz::Floating => {}
         Tz::Tz(tz) => match tz {
             chrono_tz::UTC | chrono_tz::GMT => {
                 tz_postfix = "Z".to_string();
@@ -55,5 +49,5 @@ pub(crate) fn datetime_to_ical_format(dt: &chrono::DateTime<Tz>) -> String {
     }
 
     let dt = dt.format("%Y%m%dT%H%M%S");
-    format!("{}:{}{}", tz_prefix, dt, tz_postfix)
+    (tz_prefix, format!("{}{}", dt, tz_postfix))
 }
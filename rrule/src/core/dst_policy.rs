@@ -0,0 +1,234 @@
+use super::timezone::Tz;
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone};
+
+/// How a local time that falls into a daylight saving time gap is resolved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// Parsing such a date fails with [`crate::ParseError::InvalidDateTimeInLocalTimezone`].
+    /// Occurrences in a gap are shifted forward like with [`GapPolicy::ShiftForward`],
+    /// as the iteration can't fail for a single occurrence.
+    #[default]
+    Reject,
+    /// Interpret the time with the UTC offset in effect before the gap, as RFC 5545 specifies.
+    /// This moves it forward by the length of the gap, e.g. `02:30` becomes `03:30`.
+    ShiftForward,
+    /// Move the time to the end of the gap, e.g. `02:30` becomes `03:00`.
+    NextValidTime,
+    /// Keep the wall-clock time with the UTC offset in effect before the gap, e.g.
+    /// `02:30` stays `02:30+01:00` in `Europe/Berlin`. This is the same instant as
+    /// with [`GapPolicy::ShiftForward`], only labelled with the time of the input.
+    KeepWallClock,
+    /// Drop the occurrence. Parsing such a date fails.
+    Skip,
+}
+
+/// How a local time that occurs twice in a daylight saving time overlap is resolved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Use the first occurrence of the time, as RFC 5545 specifies.
+    #[default]
+    Earlier,
+    /// Use the second occurrence of the time.
+    Later,
+    /// Drop the occurrence. Parsing such a date fails.
+    Skip,
+}
+
+/// Policy for local times which don't exist, or exist twice, because of a daylight
+/// saving time transition.
+///
+/// The policy is applied to the dates parsed by [`crate::RRuleSet::set_from_string`]
+/// and to the occurrences generated by the rules of the set.
+///
+/// # Usage
+///
+/// ```
+/// use chrono::TimeZone;
+/// use rrule::{DstPolicy, GapPolicy, OverlapPolicy, RRuleSet, Tz};
+///
+/// let policy = DstPolicy::new(GapPolicy::Skip, OverlapPolicy::Later);
+/// let dates = RRuleSet::new(Tz::UTC.timestamp_nanos(0))
+///     .dst_policy(policy)
+///     .set_from_string("DTSTART;TZID=Europe/Berlin:20210327T023000\nRRULE:FREQ=DAILY;UNTIL=20210329T000000Z")
+///     .unwrap()
+///     .all(10)
+///     .dates;
+/// // 2021-03-28T02:30 doesn't exist in Berlin, so it was skipped.
+/// assert_eq!(dates.len(), 1);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DstPolicy {
+    /// Resolution of times in a gap.
+    pub gap: GapPolicy,
+    /// Resolution of times in an overlap.
+    pub overlap: OverlapPolicy,
+}
+
+impl DstPolicy {
+    /// The resolution specified by RFC 5545.
+    pub const RFC5545: Self = Self::new(GapPolicy::ShiftForward, OverlapPolicy::Earlier);
+    /// Drop every time in a gap or overlap.
+    pub const SKIP: Self = Self::new(GapPolicy::Skip, OverlapPolicy::Skip);
+
+    /// Creates a policy from the resolution of gaps and overlaps.
+    #[must_use]
+    pub const fn new(gap: GapPolicy, overlap: OverlapPolicy) -> Self {
+        Self { gap, overlap }
+    }
+
+    /// Converts a generated occurrence to a `DateTime` in `tz`, or `None` if the policy
+    /// skips it.
+    pub(crate) fn resolve_occurrence(&self, tz: Tz, local: &NaiveDateTime) -> Option<DateTime<Tz>> {
+        let gap = match self.gap {
+            GapPolicy::Reject => GapPolicy::ShiftForward,
+            gap => gap,
+        };
+        Self { gap, ..*self }.resolve(tz, local)
+    }
+
+    /// Converts a local time to a `DateTime` in `tz`, or `None` if the policy skips
+    /// or rejects it.
+    pub(crate) fn resolve(&self, tz: Tz, local: &NaiveDateTime) -> Option<DateTime<Tz>> {
+        match tz.from_local_datetime(local) {
+            LocalResult::Single(dt) => Some(dt),
+            LocalResult::Ambiguous(dt1, dt2) => match self.overlap {
+                OverlapPolicy::Earlier => Some(dt1.min(dt2)),
+                OverlapPolicy::Later => Some(dt1.max(dt2)),
+                OverlapPolicy::Skip => None,
+            },
+            LocalResult::None => match self.gap {
+                GapPolicy::Reject | GapPolicy::Skip => None,
+                GapPolicy::ShiftForward | GapPolicy::KeepWallClock => {
+                    let (before, _) = gap_transition(tz, local)?;
+                    let offset_before = tz.offset_from_utc_datetime(&before);
+                    let shifted = local.checked_sub_signed(Duration::seconds(
+                        offset_before.fix().local_minus_utc().into(),
+                    ))?;
+                    Some(if self.gap == GapPolicy::KeepWallClock {
+                        DateTime::from_naive_utc_and_offset(shifted, offset_before)
+                    } else {
+                        tz.from_utc_datetime(&shifted)
+                    })
+                }
+                GapPolicy::NextValidTime => {
+                    let (_, transition) = gap_transition(tz, local)?;
+                    Some(tz.from_utc_datetime(&transition))
+                }
+            },
+        }
+    }
+}
+
+/// Returns the UTC instant just before the transition that causes the gap `local`
+/// falls in, and the instant of the transition.
+fn gap_transition(tz: Tz, local: &NaiveDateTime) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let wall_clock = |timestamp: i64| {
+        let utc = DateTime::from_timestamp(timestamp, 0)?.naive_utc();
+        let offset = tz.offset_from_utc_datetime(&utc).fix().local_minus_utc();
+        utc.checked_add_signed(Duration::seconds(offset.into()))
+    };
+    // Offsets are less than a day, so the wall-clock time is before `local` a day
+    // earlier and after it a day later. Search the last second before it.
+    let timestamp = local.and_utc().timestamp();
+    let mut before = timestamp.checked_sub(86_400)?;
+    let mut after = timestamp.checked_add(86_400)?;
+    while after - before > 1 {
+        let middle = before + (after - before) / 2;
+        if wall_clock(middle)? < *local {
+            before = middle;
+        } else {
+            after = middle;
+        }
+    }
+    Some((
+        DateTime::from_timestamp(before, 0)?.naive_utc(),
+        DateTime::from_timestamp(after, 0)?.naive_utc(),
+    ))
+}
+
+#[cfg(test)]
+#[cfg(not(tz_subset))]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn resolves_gap() {
+        let tz = Tz::Europe__Berlin;
+        let gap = local(2021, 3, 28, 2, 30);
+        let resolve = |gap_policy| {
+            DstPolicy::new(gap_policy, OverlapPolicy::Earlier)
+                .resolve(tz, &gap)
+                .map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            resolve(GapPolicy::ShiftForward).as_deref(),
+            Some("2021-03-28T03:30:00+02:00")
+        );
+        assert_eq!(
+            resolve(GapPolicy::NextValidTime).as_deref(),
+            Some("2021-03-28T03:00:00+02:00")
+        );
+        assert_eq!(
+            resolve(GapPolicy::KeepWallClock).as_deref(),
+            Some("2021-03-28T02:30:00+01:00")
+        );
+        assert_eq!(resolve(GapPolicy::Skip), None);
+        assert_eq!(resolve(GapPolicy::Reject), None);
+    }
+
+    #[test]
+    fn resolves_day_long_gap() {
+        // Samoa skipped 2011-12-30 entirely, moving from -10:00 to +14:00.
+        let tz = Tz::Pacific__Apia;
+        let gap = local(2011, 12, 30, 9, 0);
+        let resolve = |policy: DstPolicy| policy.resolve(tz, &gap).map(|dt| dt.to_rfc3339());
+        assert_eq!(
+            resolve(DstPolicy::RFC5545).as_deref(),
+            Some("2011-12-31T09:00:00+14:00")
+        );
+        assert_eq!(
+            resolve(DstPolicy::new(
+                GapPolicy::NextValidTime,
+                OverlapPolicy::Earlier
+            ))
+            .as_deref(),
+            Some("2011-12-31T00:00:00+14:00")
+        );
+    }
+
+    #[test]
+    fn resolves_overlap() {
+        let tz = Tz::Europe__Berlin;
+        let overlap = local(2021, 10, 31, 2, 30);
+        let resolve = |overlap_policy| {
+            DstPolicy::new(GapPolicy::ShiftForward, overlap_policy)
+                .resolve(tz, &overlap)
+                .map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(
+            resolve(OverlapPolicy::Earlier).as_deref(),
+            Some("2021-10-31T02:30:00+02:00")
+        );
+        assert_eq!(
+            resolve(OverlapPolicy::Later).as_deref(),
+            Some("2021-10-31T02:30:00+01:00")
+        );
+        assert_eq!(resolve(OverlapPolicy::Skip), None);
+    }
+
+    #[test]
+    fn keeps_unambiguous_times() {
+        let dt = DstPolicy::SKIP
+            .resolve(Tz::Europe__Berlin, &local(2021, 3, 28, 9, 0))
+            .unwrap();
+        assert_eq!(dt.to_rfc3339(), "2021-03-28T09:00:00+02:00");
+    }
+}
@@ -1,15 +1,20 @@
+mod bounds;
+mod cost;
 mod datetime;
+mod dst_policy;
 mod rrule;
 mod rruleset;
 mod timezone;
 mod timezone_impl;
 pub(crate) mod utils;
+mod vtimezone;
 
+pub use self::cost::CostEstimate;
+pub use self::dst_policy::{DstPolicy, GapPolicy, OverlapPolicy};
+pub(crate) use self::rrule::FilledIn;
 pub use self::rrule::{Frequency, NWeekday, RRule};
 pub use self::rruleset::{RRuleResult, RRuleSet};
-pub(crate) use datetime::{
-    duration_from_midnight, get_day, get_hour, get_minute, get_month, get_second,
-};
+pub(crate) use datetime::{get_day, get_hour, get_minute, get_month, get_second};
 pub use timezone::Tz;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
@@ -3,12 +3,16 @@ use crate::core::get_hour;
 use crate::core::get_minute;
 use crate::core::get_month;
 use crate::core::get_second;
-use crate::iter::RRuleIter;
+use crate::core::DstPolicy;
+use crate::iter::{Interrupt, RRuleIter};
 use crate::parser::str_to_weekday;
 use crate::parser::ContentLineCaptures;
 use crate::parser::ParseError;
+use crate::parser::Span;
+use crate::validator::validate_not_empty;
 use crate::validator::validate_rrule;
 use crate::validator::ValidationError;
+use crate::validator::ValidatorLimits;
 use crate::Tz;
 use crate::{RRuleError, RRuleSet, Unvalidated, Validated};
 use chrono::DateTime;
@@ -214,7 +218,7 @@ fn weekday_to_str(d: Weekday) -> String {
 /// It has two stages, based on the attached type, `Validated` or `Unvalidated`.
 /// - `Unvalidated`, which is the raw string representation of the RRULE
 /// - `Validated`, which is when the `RRule` has been parsed and validated, based on the start date
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", serde_as)]
 #[cfg_attr(feature = "serde", derive(DeserializeFromStr, SerializeDisplay))]
 pub struct RRule<Stage = Validated> {
@@ -274,11 +278,78 @@ pub struct RRule<Stage = Validated> {
     /// Can be a value from -366 to 366.
     /// Note: Only used when `by-easter` feature flag is set. Otherwise, it is ignored.
     pub(crate) by_easter: Option<i16>,
+    /// The experimental rule parts, like `X-NAME=value`, which are kept as is
+    /// and written back as they were parsed.
+    pub(crate) extensions: Vec<(String, String)>,
+    /// The rule parts which validation filled in from the start date. They are
+    /// written out like given rule parts, so this is neither compared nor serialized.
+    #[cfg_attr(feature = "serde", serde_as(as = "ignore"))]
+    pub(crate) filled_in: FilledIn,
     /// A phantom data to have the stage (unvalidated or validated).
     #[cfg_attr(feature = "serde", serde_as(as = "ignore"))]
     pub(crate) stage: PhantomData<Stage>,
 }
 
+/// The rule parts which were not given, and which validation filled in from the
+/// start date, so that they can be filled in again from another start date.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct FilledIn {
+    pub(crate) by_month: bool,
+    pub(crate) by_month_day: bool,
+    pub(crate) by_weekday: bool,
+    pub(crate) by_hour: bool,
+    pub(crate) by_minute: bool,
+    pub(crate) by_second: bool,
+}
+
+/// Compares every rule part, but not which of them validation filled in from the
+/// start date. Filled in rule parts are formatted and serialized like given ones,
+/// so a rule is still equal to itself after a round trip through a string.
+impl<S> PartialEq for RRule<S> {
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            freq,
+            interval,
+            count,
+            until,
+            week_start,
+            by_set_pos,
+            by_month,
+            by_month_day,
+            by_n_month_day,
+            by_year_day,
+            by_week_no,
+            by_weekday,
+            by_hour,
+            by_minute,
+            by_second,
+            by_easter,
+            extensions,
+            filled_in: _,
+            stage: _,
+        } = self;
+        *freq == other.freq
+            && *interval == other.interval
+            && *count == other.count
+            && *until == other.until
+            && *week_start == other.week_start
+            && *by_set_pos == other.by_set_pos
+            && *by_month == other.by_month
+            && *by_month_day == other.by_month_day
+            && *by_n_month_day == other.by_n_month_day
+            && *by_year_day == other.by_year_day
+            && *by_week_no == other.by_week_no
+            && *by_weekday == other.by_weekday
+            && *by_hour == other.by_hour
+            && *by_minute == other.by_minute
+            && *by_second == other.by_second
+            && *by_easter == other.by_easter
+            && *extensions == other.extensions
+    }
+}
+
+impl<S> Eq for RRule<S> {}
+
 impl Default for RRule<Unvalidated> {
     /// Creates a new unvalidated `RRule` with default values and Yearly frequency.
     fn default() -> Self {
@@ -299,6 +370,8 @@ impl Default for RRule<Unvalidated> {
             by_minute: Vec::new(),
             by_second: Vec::new(),
             by_easter: None,
+            extensions: Vec::new(),
+            filled_in: FilledIn::default(),
             stage: PhantomData,
         }
     }
@@ -363,6 +436,10 @@ impl RRule<Unvalidated> {
     }
 
     /// When given, these variables will define the months to apply the recurrence to.
+    ///
+    /// # Panics
+    ///
+    /// Will not panic, `Month::number_from_month` is always within `1..=12`.
     #[must_use]
     pub fn by_month(mut self, by_month: &[Month]) -> Self {
         self.by_month = by_month
@@ -371,6 +448,7 @@ impl RRule<Unvalidated> {
                 u8::try_from(month.number_from_month()).expect("1-12 is within range of u8")
             })
             .collect();
+        self.filled_in.by_month = false;
         self
     }
 
@@ -379,6 +457,7 @@ impl RRule<Unvalidated> {
     #[must_use]
     pub fn by_month_day(mut self, by_month_day: Vec<i8>) -> Self {
         self.by_month_day = by_month_day;
+        self.filled_in.by_month_day = false;
         self
     }
 
@@ -405,6 +484,7 @@ impl RRule<Unvalidated> {
     #[must_use]
     pub fn by_weekday(mut self, by_weekday: Vec<NWeekday>) -> Self {
         self.by_weekday = by_weekday;
+        self.filled_in.by_weekday = false;
         self
     }
 
@@ -413,6 +493,7 @@ impl RRule<Unvalidated> {
     #[must_use]
     pub fn by_hour(mut self, by_hour: Vec<u8>) -> Self {
         self.by_hour = by_hour;
+        self.filled_in.by_hour = false;
         self
     }
 
@@ -421,6 +502,7 @@ impl RRule<Unvalidated> {
     #[must_use]
     pub fn by_minute(mut self, by_minute: Vec<u8>) -> Self {
         self.by_minute = by_minute;
+        self.filled_in.by_minute = false;
         self
     }
 
@@ -429,6 +511,7 @@ impl RRule<Unvalidated> {
     #[must_use]
     pub fn by_second(mut self, by_second: Vec<u8>) -> Self {
         self.by_second = by_second;
+        self.filled_in.by_second = false;
         self
     }
 
@@ -442,6 +525,15 @@ impl RRule<Unvalidated> {
         self
     }
 
+    /// Adds an experimental rule part, like `X-NAME=value`. It doesn't change the
+    /// recurrences, but is kept as is when the rule is formatted. The `name` is
+    /// expected to start with `X-`.
+    #[must_use]
+    pub fn extension(mut self, name: &str, value: &str) -> Self {
+        self.extensions.push((name.into(), value.into()));
+        self
+    }
+
     /// Fills in some additional fields in order to make iter work correctly.
     pub(crate) fn finalize_parsed_rrule(mut self, dt_start: &DateTime<Tz>) -> Self {
         // TEMP: move negative months to another list
@@ -456,6 +548,8 @@ impl RRule<Unvalidated> {
         }
         self.by_month_day = by_month_day;
         self.by_n_month_day = by_n_month_day;
+        // The parts filled in before stay filled in, unless they were changed since.
+        let mut filled_in = self.filled_in;
 
         // Can only be set to true if the feature flag is set.
         let by_easter_is_some = if cfg!(feature = "by-easter") {
@@ -477,16 +571,20 @@ impl RRule<Unvalidated> {
                     if self.by_month.is_empty() {
                         let month = get_month(dt_start);
                         self.by_month = vec![month];
+                        filled_in.by_month = true;
                     }
                     let day = get_day(dt_start);
                     self.by_month_day = vec![day];
+                    filled_in.by_month_day = true;
                 }
                 Frequency::Monthly => {
                     let day = get_day(dt_start);
                     self.by_month_day = vec![day];
+                    filled_in.by_month_day = true;
                 }
                 Frequency::Weekly => {
                     self.by_weekday = vec![NWeekday::Every(dt_start.weekday())];
+                    filled_in.by_weekday = true;
                 }
                 _ => (),
             };
@@ -496,18 +594,21 @@ impl RRule<Unvalidated> {
         if self.by_hour.is_empty() && self.freq < Frequency::Hourly {
             let hour = get_hour(dt_start);
             self.by_hour = vec![hour];
+            filled_in.by_hour = true;
         }
 
         // by_minute
         if self.by_minute.is_empty() && self.freq < Frequency::Minutely {
             let minute = get_minute(dt_start);
             self.by_minute = vec![minute];
+            filled_in.by_minute = true;
         }
 
         // by_second
         if self.by_second.is_empty() && self.freq < Frequency::Secondly {
             let second = get_second(dt_start);
             self.by_second = vec![second];
+            filled_in.by_second = true;
         }
 
         //  BYMONTHDAY must not be specified when the FREQ rule part is set to WEEKLY.
@@ -530,6 +631,7 @@ impl RRule<Unvalidated> {
             if self.by_month.is_empty() && !self.by_month_day.is_empty() {
                 let month = get_month(dt_start);
                 self.by_month = vec![month];
+                filled_in.by_month = true;
             } else if self.by_month_day.is_empty()
                 && self.by_n_month_day.is_empty()
                 && self.by_weekday.is_empty()
@@ -538,8 +640,10 @@ impl RRule<Unvalidated> {
             {
                 let day = get_day(dt_start);
                 self.by_month_day = vec![day];
+                filled_in.by_month_day = true;
             }
         }
+        self.filled_in = filled_in;
 
         // make sure all BYXXX are unique and sorted
         self.by_hour.sort_unstable();
@@ -572,16 +676,19 @@ impl RRule<Unvalidated> {
         self.by_weekday.sort_unstable();
         self.by_weekday.dedup();
 
-        match (self.get_until(), dt_start.timezone()) {
-            (Some(until), Tz::Tz(_)) => {
-                // https://icalendar.org/iCalendar-RFC-5545/3-3-10-recurrence-rule.html
-                // If the "DTSTART" property is specified as a date with UTC time or a date with local time and 
-                // time zone reference, then the UNTIL rule part MUST be specified as a date with UTC time
-                if until.timezone() != Tz::UTC {
-                    self.until = Some(until.with_timezone(&Tz::UTC));
-                }
+        if let (Some(until), Tz::Tz(_)) = (self.get_until(), dt_start.timezone()) {
+            // https://icalendar.org/iCalendar-RFC-5545/3-3-10-recurrence-rule.html
+            // If the "DTSTART" property is specified as a date with UTC time or a date with local time and
+            // time zone reference, then the UNTIL rule part MUST be specified as a date with UTC time
+            if until.timezone() != Tz::UTC {
+                // A floating UNTIL is read as a wall-clock time of DTSTART's timezone.
+                let until = dt_start
+                    .timezone()
+                    .resolve_floating(until)
+                    .earliest()
+                    .unwrap_or(*until);
+                self.until = Some(until.with_timezone(&Tz::UTC));
             }
-            _ => {}
         }
 
         self
@@ -589,39 +696,99 @@ impl RRule<Unvalidated> {
 
     /// Validates the [`RRule`] with the given `dt_start`.
     ///
+    /// `DTSTART` has to be within the years `-10_000..=10_000`, use
+    /// [`RRule::validate_with_limits`] to enforce other limits.
+    ///
     /// # Errors
     ///
     /// If the properties aren't valid, it will return [`RRuleError`].
     pub fn validate(self, dt_start: DateTime<Tz>) -> Result<RRule<Validated>, RRuleError> {
+        self.validate_with_limits(dt_start, &ValidatorLimits::UNLIMITED)
+    }
+
+    /// Validates the [`RRule`] with the given `dt_start`, and checks that the
+    /// `INTERVAL` and the year of `dt_start` are within `limits`.
+    ///
+    /// # Errors
+    ///
+    /// If the properties aren't valid, it will return [`RRuleError`].
+    pub fn validate_with_limits(
+        self,
+        dt_start: DateTime<Tz>,
+        limits: &ValidatorLimits,
+    ) -> Result<RRule<Validated>, RRuleError> {
+        self.validate_inner(dt_start, limits, false)
+    }
+
+    /// Validates the [`RRule`] with the given `dt_start`, and returns every error
+    /// found instead of stopping at the first one, e.g. to highlight all invalid
+    /// rule parts in an editor. See [`ValidationError::rule_part`].
+    ///
+    /// # Errors
+    ///
+    /// If the properties aren't valid, it will return all [`ValidationError`]s found.
+    pub fn validate_all(
+        self,
+        dt_start: DateTime<Tz>,
+    ) -> Result<RRule<Validated>, Vec<ValidationError>> {
+        self.validate_collect(dt_start, &ValidatorLimits::UNLIMITED, false)
+            .map(|(rrule, _)| rrule)
+    }
+
+    /// Validates the [`RRule`]. A rule that never occurs is only logged as a
+    /// warning, unless `reject_never_occurring` is set.
+    pub(crate) fn validate_inner(
+        self,
+        dt_start: DateTime<Tz>,
+        limits: &ValidatorLimits,
+        reject_never_occurring: bool,
+    ) -> Result<RRule<Validated>, RRuleError> {
+        let (rrule, warning) = self
+            .validate_collect(dt_start, limits, reject_never_occurring)
+            .map_err(|mut errors| errors.swap_remove(0))?;
+        if let Some(warning) = warning {
+            log::warn!("{warning}");
+        }
+        Ok(rrule)
+    }
+
+    /// Same as [`RRule::validate_inner`], but returns every error found, and the
+    /// error the rule was accepted with instead of logging it.
+    /// The returned list of errors is never empty.
+    pub(crate) fn validate_collect(
+        self,
+        dt_start: DateTime<Tz>,
+        limits: &ValidatorLimits,
+        reject_never_occurring: bool,
+    ) -> Result<(RRule<Validated>, Option<ValidationError>), Vec<ValidationError>> {
         let rrule = self.finalize_parsed_rrule(&dt_start);
 
         // Validate required checks (defined by RFC 5545)
-        validate_rrule::validate_rrule_forced(&rrule, &dt_start)?;
+        let mut errors = validate_rrule::validate_rrule_forced_all(&rrule, &dt_start);
+        if let Err(err) = validate_rrule::validate_rrule_limits(&rrule, &dt_start, limits) {
+            errors.push(err);
+        }
 
         // Check if it is possible to generate a timeset
-        match rrule.freq {
-            Frequency::Hourly => {
-                if rrule.by_minute.is_empty() && rrule.by_second.is_empty() {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
-            }
-            Frequency::Minutely => {
-                if rrule.by_second.is_empty() {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
-            }
-            Frequency::Secondly => {}
+        let has_timeset = match rrule.freq {
+            Frequency::Hourly => !rrule.by_minute.is_empty() || !rrule.by_second.is_empty(),
+            Frequency::Minutely => !rrule.by_second.is_empty(),
+            Frequency::Secondly => true,
             _ => {
-                if rrule.by_hour.is_empty()
-                    && rrule.by_minute.is_empty()
-                    && rrule.by_second.is_empty()
-                {
-                    return Err(ValidationError::UnableToGenerateTimeset.into());
-                }
+                !rrule.by_hour.is_empty()
+                    || !rrule.by_minute.is_empty()
+                    || !rrule.by_second.is_empty()
             }
+        };
+        if !has_timeset {
+            errors.push(ValidationError::UnableToGenerateTimeset);
         }
 
-        Ok(RRule {
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let rrule = RRule {
             freq: rrule.freq,
             interval: rrule.interval,
             count: rrule.count,
@@ -638,8 +805,16 @@ impl RRule<Unvalidated> {
             by_minute: rrule.by_minute,
             by_second: rrule.by_second,
             by_easter: rrule.by_easter,
+            extensions: rrule.extensions,
+            filled_in: rrule.filled_in,
             stage: PhantomData,
-        })
+        };
+
+        match validate_not_empty(&rrule, &dt_start) {
+            Ok(()) => Ok((rrule, None)),
+            Err(err) if reject_never_occurring => Err(vec![err]),
+            Err(err) => Ok((rrule, Some(err))),
+        }
     }
 
     /// Validates the [`RRule`] with the given `dt_start` and creates an [`RRuleSet`] struct.
@@ -655,8 +830,86 @@ impl RRule<Unvalidated> {
 }
 
 impl RRule {
-    pub(crate) fn iter_with_ctx(&self, dt_start: DateTime<Tz>, limited: bool) -> RRuleIter {
-        RRuleIter::new(self, &dt_start, limited)
+    /// Converts the validated rule back to an [`RRule<Unvalidated>`], so that it
+    /// can be changed with the builder methods and validated again.
+    ///
+    /// Note that validation fills in the rule parts which default to the start
+    /// date, like `BYHOUR`, so those stay the same if the rule is validated with
+    /// another start date. Use [`RRuleSet::set_dt_start`] to move them along.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{Frequency, RRule, Tz};
+    ///
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+    /// let rrule = RRule::new(Frequency::Daily).count(3).validate(dt_start).unwrap();
+    /// let rrule = rrule.into_builder().count(5).validate(dt_start).unwrap();
+    /// assert_eq!(rrule.to_string(), "FREQ=DAILY;COUNT=5;BYHOUR=9;BYMINUTE=0;BYSECOND=0");
+    /// ```
+    #[must_use]
+    pub fn into_builder(self) -> RRule<Unvalidated> {
+        let mut by_month_day = self.by_month_day;
+        by_month_day.extend(self.by_n_month_day);
+        RRule {
+            freq: self.freq,
+            interval: self.interval,
+            count: self.count,
+            until: self.until,
+            week_start: self.week_start,
+            by_set_pos: self.by_set_pos,
+            by_month: self.by_month,
+            by_month_day,
+            by_n_month_day: vec![],
+            by_year_day: self.by_year_day,
+            by_week_no: self.by_week_no,
+            by_weekday: self.by_weekday,
+            by_hour: self.by_hour,
+            by_minute: self.by_minute,
+            by_second: self.by_second,
+            by_easter: self.by_easter,
+            extensions: self.extensions,
+            filled_in: self.filled_in,
+            stage: PhantomData,
+        }
+    }
+
+    /// Same as [`RRule::into_builder`], but clears the rule parts which validation
+    /// filled in from the start date, so that they are filled in from the new start
+    /// date when the rule is validated again. Rule parts that were given are kept.
+    pub(crate) fn into_builder_without_defaults(self) -> RRule<Unvalidated> {
+        let filled_in = self.filled_in;
+        let mut rrule = self.into_builder();
+        if filled_in.by_month {
+            rrule.by_month.clear();
+        }
+        if filled_in.by_month_day {
+            rrule.by_month_day.clear();
+        }
+        if filled_in.by_weekday {
+            rrule.by_weekday.clear();
+        }
+        if filled_in.by_hour {
+            rrule.by_hour.clear();
+        }
+        if filled_in.by_minute {
+            rrule.by_minute.clear();
+        }
+        if filled_in.by_second {
+            rrule.by_second.clear();
+        }
+        rrule
+    }
+
+    pub(crate) fn iter_with_ctx(
+        &self,
+        dt_start: DateTime<Tz>,
+        limits: Option<ValidatorLimits>,
+        dst_policy: DstPolicy,
+        interrupt: Interrupt,
+    ) -> RRuleIter {
+        RRuleIter::new(self, &dt_start, limits, dst_policy, interrupt)
     }
 }
 
@@ -664,8 +917,12 @@ impl FromStr for RRule<Unvalidated> {
     type Err = RRuleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = ContentLineCaptures::new(s)?;
-        Self::try_from(parts).map_err(From::from)
+        let locate = |err: ParseError| {
+            let span = Span::locate(s, 1, err.rule_part(), err.offending_text());
+            RRuleError::ParserError(err, Some(span))
+        };
+        let parts = ContentLineCaptures::new(s).map_err(locate)?;
+        Self::try_from(parts).map_err(locate)
     }
 }
 
@@ -681,7 +938,12 @@ impl<S> Display for RRule<S> {
         res.push(format!("FREQ={}", &self.freq));
 
         if let Some(until) = &self.until {
-            let maybe_zulu = if until.timezone().is_local() { "" } else { "Z" };
+            let tz = until.timezone();
+            let maybe_zulu = if tz.is_local() || tz.is_floating() {
+                ""
+            } else {
+                "Z"
+            };
             res.push(format!(
                 "UNTIL={}{}",
                 until.format("%Y%m%dT%H%M%S"),
@@ -725,11 +987,13 @@ impl<S> Display for RRule<S> {
             ));
         }
 
-        if !self.by_month_day.is_empty() {
+        // Validation moves the negative month days to `by_n_month_day`.
+        if !self.by_month_day.is_empty() || !self.by_n_month_day.is_empty() {
             res.push(format!(
                 "BYMONTHDAY={}",
                 self.by_month_day
                     .iter()
+                    .chain(&self.by_n_month_day)
                     .map(ToString::to_string)
                     .collect::<Vec<_>>()
                     .join(",")
@@ -807,6 +1071,10 @@ impl<S> Display for RRule<S> {
             res.push(format!("BYEASTER={}", by_easter));
         }
 
+        for (name, value) in &self.extensions {
+            res.push(format!("{name}={value}"));
+        }
+
         write!(f, "{}", res.join(";"))
     }
 }
@@ -902,4 +1170,10 @@ impl<S> RRule<S> {
     pub fn get_by_easter(&self) -> Option<&i16> {
         self.by_easter.as_ref()
     }
+
+    /// Get the experimental rule parts, like `X-NAME=value`, of the recurrence.
+    #[must_use]
+    pub fn get_extensions(&self) -> &[(String, String)] {
+        &self.extensions
+    }
 }
@@ -1,8 +1,12 @@
-use crate::core::datetime::datetime_to_ical_format;
+use crate::core::datetime::datetime_to_ical_parts;
 use crate::core::utils::collect_with_error;
-use crate::parser::{ContentLine, Grammar};
-use crate::{ParseError, RRule, RRuleError, Tz};
-use chrono::DateTime;
+use crate::iter::Interrupt;
+use crate::parser::{ContentLine, DateContentLine, Grammar, ParseMode, ParseOptions};
+use crate::{
+    CancellationToken, Deadline, Diagnostic, DstPolicy, LimitReason, LocatedError, ParseError,
+    RRule, RRuleError, Severity, Span, Tz, ValidationError, ValidatorLimits,
+};
+use chrono::{DateTime, NaiveTime, TimeZone};
 #[cfg(feature = "serde")]
 use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
 use std::fmt::Display;
@@ -29,6 +33,39 @@ pub struct RRuleSet {
     pub(crate) after: Option<DateTime<Tz>>,
     /// If validation limits are enabled
     pub(crate) limited: bool,
+    /// The validation limits, used when `limited` is set.
+    pub(crate) limits: ValidatorLimits,
+    /// If rules that never occur are rejected instead of accepted with a warning.
+    pub(crate) reject_never_occurring: bool,
+    /// If parsed input with constructs which RFC 5545 doesn't allow is rejected.
+    pub(crate) strict: bool,
+    /// The parameters of each rdate, in the same order as `rdate`.
+    pub(crate) rdate_parameters: Vec<DateParameters>,
+    /// The parameters of each exdate, in the same order as `exdate`.
+    pub(crate) exdate_parameters: Vec<DateParameters>,
+    /// Timezone of parsed dates which have neither a `Z` suffix nor a `TZID`.
+    pub(crate) floating_tz: Tz,
+    /// Resolution of local times in daylight saving time transitions.
+    pub(crate) dst_policy: DstPolicy,
+    /// Deadline and cancellation token that stop the iteration.
+    pub(crate) interrupt: Interrupt,
+    /// Experimental properties, like `X-NAME;PARAM=1:value`, as the name with its
+    /// parameters and the value.
+    pub(crate) extensions: Extensions,
+    /// Experimental parameters of the `DTSTART`, like `X-NAME=value`.
+    pub(crate) dt_start_extensions: Extensions,
+}
+
+/// Experimental properties or parameters, as pairs of name and value.
+type Extensions = Vec<(String, String)>;
+
+/// The parameters an rdate or exdate was parsed with, to format it the same way.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct DateParameters {
+    /// If the date was parsed from a `DATE` value rather than a `DATE-TIME`.
+    pub(crate) date_only: bool,
+    /// Experimental parameters, like `X-NAME=value`, the date was parsed with.
+    pub(crate) extensions: Extensions,
 }
 
 /// The return result of `RRuleSet::all`.
@@ -39,6 +76,8 @@ pub struct RRuleResult {
     /// It is being true if the list of dates is limited.
     /// To indicate that it can potentially contain more dates.
     pub limited: bool,
+    /// The limit that was hit, if the list of dates is limited.
+    pub limit_reason: Option<LimitReason>,
 }
 
 impl RRuleSet {
@@ -54,6 +93,16 @@ impl RRuleSet {
             before: None,
             after: None,
             limited: false,
+            limits: ValidatorLimits::DEFAULT,
+            reject_never_occurring: false,
+            strict: false,
+            rdate_parameters: vec![],
+            exdate_parameters: vec![],
+            floating_tz: Tz::LOCAL,
+            dst_policy: DstPolicy::default(),
+            interrupt: Interrupt::default(),
+            extensions: vec![],
+            dt_start_extensions: vec![],
         }
     }
 
@@ -66,6 +115,104 @@ impl RRuleSet {
         self
     }
 
+    /// Enable validation limits, and use `limits` instead of [`ValidatorLimits::DEFAULT`].
+    ///
+    /// The limits are also used to validate the rules parsed by
+    /// [`RRuleSet::set_from_string`] afterwards.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{LimitReason, RRuleSet, Tz, ValidatorLimits};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY".parse().unwrap();
+    /// let mut iter = rrule_set.limits(ValidatorLimits::STRICT).into_iter();
+    /// // Years 2021 up to and including 3000.
+    /// assert_eq!(iter.by_ref().count(), 980);
+    /// assert_eq!(iter.limit_reason(), Some(LimitReason::YearRange));
+    /// ```
+    #[must_use]
+    pub fn limits(mut self, limits: ValidatorLimits) -> Self {
+        self.limited = true;
+        self.limits = limits;
+        self
+    }
+
+    /// Reject rules parsed by [`RRuleSet::set_from_string`] that are valid, but can
+    /// never produce an occurrence, like `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30`, with
+    /// [`crate::ValidationError::NeverOccurs`] instead of logging a warning for them.
+    #[must_use]
+    pub fn reject_never_occurring(mut self) -> Self {
+        self.reject_never_occurring = true;
+        self
+    }
+
+    /// Reject input parsed by [`RRuleSet::set_from_string`] with constructs which
+    /// RFC 5545 doesn't allow, like lowercase names, empty or duplicate rule parts,
+    /// `BYWEEKDAY` or a missing `RRULE:` name, instead of accepting them where the
+    /// parser understands them. Use [`RRuleSet::from_str_strict`] instead of
+    /// [`RRuleSet::from_str`] to parse a set with a `DTSTART` this way.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set = RRuleSet::new(Tz::UTC.timestamp_nanos(0));
+    /// assert!(rrule_set.clone().set_from_string("RRULE:FREQ=daily;COUNT=3").is_ok());
+    /// let err = rrule_set.strict().set_from_string("RRULE:FREQ=daily;COUNT=3").unwrap_err();
+    /// assert_eq!(err.code(), "parse.not_uppercase");
+    /// ```
+    #[must_use]
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Returns the validation limits of the set.
+    #[must_use]
+    pub fn get_limits(&self) -> ValidatorLimits {
+        self.limits
+    }
+
+    /// Stop the iteration once `deadline` has passed.
+    ///
+    /// The deadline is checked while searching for the next recurrence, also when
+    /// using the `Iterator` api directly. The recurrences found so far are returned,
+    /// with [`LimitReason::DeadlineExceeded`] as the reason.
+    #[must_use]
+    pub fn deadline(mut self, deadline: Deadline) -> Self {
+        self.interrupt.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop the iteration once `token` is cancelled.
+    ///
+    /// The token is checked while searching for the next recurrence, also when
+    /// using the `Iterator` api directly. The recurrences found so far are returned,
+    /// with [`LimitReason::Cancelled`] as the reason.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{CancellationToken, LimitReason, RRuleSet};
+    ///
+    /// let token = CancellationToken::new();
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let mut iter = rrule_set.cancellation_token(token.clone()).into_iter();
+    /// assert!(iter.next().is_some());
+    ///
+    /// token.cancel();
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.limit_reason(), Some(LimitReason::Cancelled));
+    /// ```
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.interrupt.cancellation_token = Some(token);
+        self
+    }
+
     /// Only return recurrences that comes before this `DateTime`.
     ///
     /// This value will not be used if you use the `Iterator` API directly.
@@ -84,6 +231,48 @@ impl RRuleSet {
         self
     }
 
+    /// Sets the timezone of dates without a `Z` suffix or `TZID` parsed by
+    /// [`RRuleSet::set_from_string`]. Defaults to [`Tz::LOCAL`].
+    ///
+    /// Use [`Tz::FLOATING`] to keep them as wall-clock times, which gives the same
+    /// occurrences on every machine and can be placed in a timezone later with
+    /// [`Tz::resolve_floating`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set = RRuleSet::new(Tz::UTC.timestamp_nanos(0))
+    ///     .floating_timezone(Tz::FLOATING)
+    ///     .set_from_string("DTSTART:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3")
+    ///     .unwrap();
+    /// assert_eq!(rrule_set.get_dt_start().timezone(), Tz::FLOATING);
+    /// assert_eq!(rrule_set.to_string(), "DTSTART:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0");
+    /// ```
+    #[must_use]
+    pub fn floating_timezone(mut self, tz: Tz) -> Self {
+        self.floating_tz = tz;
+        self
+    }
+
+    /// Sets how local times in a daylight saving time gap or overlap are resolved,
+    /// both for the dates parsed by [`RRuleSet::set_from_string`] and the occurrences
+    /// of the rules. By default, parsed dates in a gap are rejected, see
+    /// [`crate::GapPolicy::Reject`], and the earlier time of an overlap is used.
+    #[must_use]
+    pub fn dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.dst_policy = dst_policy;
+        self
+    }
+
+    /// Returns the daylight saving time policy of the set.
+    #[must_use]
+    pub fn get_dst_policy(&self) -> DstPolicy {
+        self.dst_policy
+    }
+
     /// Adds a new rrule to the set.
     #[must_use]
     pub fn rrule(mut self, rrule: RRule) -> Self {
@@ -103,6 +292,7 @@ impl RRuleSet {
     #[must_use]
     pub fn rdate(mut self, rdate: DateTime<Tz>) -> Self {
         self.rdate.push(rdate);
+        self.rdate_parameters.push(DateParameters::default());
         self
     }
 
@@ -110,6 +300,17 @@ impl RRuleSet {
     #[must_use]
     pub fn exdate(mut self, exdate: DateTime<Tz>) -> Self {
         self.exdate.push(exdate);
+        self.exdate_parameters.push(DateParameters::default());
+        self
+    }
+
+    /// Adds an experimental property, like `X-NAME:value`. The `name` may include
+    /// parameters, like `X-NAME;PARAM=1`, and is expected to start with `X-`.
+    /// Experimental properties don't change the recurrences, but are kept as is
+    /// when the set is formatted.
+    #[must_use]
+    pub fn extension(mut self, name: &str, value: &str) -> Self {
+        self.extensions.push((name.into(), value.into()));
         self
     }
 
@@ -131,6 +332,7 @@ impl RRuleSet {
     /// Sets the rdates of the set.
     #[must_use]
     pub fn set_rdates(mut self, rdates: Vec<DateTime<Tz>>) -> Self {
+        self.rdate_parameters = vec![DateParameters::default(); rdates.len()];
         self.rdate = rdates;
         self
     }
@@ -138,10 +340,106 @@ impl RRuleSet {
     /// Set the exdates of the set.
     #[must_use]
     pub fn set_exdates(mut self, exdates: Vec<DateTime<Tz>>) -> Self {
+        self.exdate_parameters = vec![DateParameters::default(); exdates.len()];
         self.exdate = exdates;
         self
     }
 
+    /// Replaces the rrule at `index`, and returns the previous one. Returns `None`
+    /// and leaves the set unchanged if there is no rrule at `index`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let mut rrule_set: RRuleSet =
+    ///     "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3".parse().unwrap();
+    /// let dt_start = *rrule_set.get_dt_start();
+    /// let rrule = rrule_set.get_rrule()[0].clone();
+    /// let rrule = rrule.into_builder().count(5).validate(dt_start).unwrap();
+    /// assert!(rrule_set.replace_rrule(0, rrule).is_some());
+    /// assert_eq!(rrule_set.all(10).dates.len(), 5);
+    /// ```
+    pub fn replace_rrule(&mut self, index: usize, rrule: RRule) -> Option<RRule> {
+        self.rrule
+            .get_mut(index)
+            .map(|previous| std::mem::replace(previous, rrule))
+    }
+
+    /// Replaces the exrule at `index`, and returns the previous one. Returns `None`
+    /// and leaves the set unchanged if there is no exrule at `index`.
+    #[cfg(feature = "exrule")]
+    pub fn replace_exrule(&mut self, index: usize, exrule: RRule) -> Option<RRule> {
+        self.exrule
+            .get_mut(index)
+            .map(|previous| std::mem::replace(previous, exrule))
+    }
+
+    /// Removes the rdates equal to `rdate`, and returns whether there were any.
+    pub fn remove_rdate(&mut self, rdate: &DateTime<Tz>) -> bool {
+        remove_date(&mut self.rdate, &mut self.rdate_parameters, rdate)
+    }
+
+    /// Removes the exdates equal to `exdate`, and returns whether there were any.
+    pub fn remove_exdate(&mut self, exdate: &DateTime<Tz>) -> bool {
+        remove_date(&mut self.exdate, &mut self.exdate_parameters, exdate)
+    }
+
+    /// Changes the start datetime, and validates all rules again with it.
+    ///
+    /// The rule parts which validation filled in from the previous start date,
+    /// like `BYHOUR` for a daily rule, are filled in from the new one, so that the
+    /// recurrences move along with the start date. Rule parts which were given
+    /// are kept, even if they have the value of the previous start date.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let mut rrule_set: RRuleSet =
+    ///     "DTSTART:20210101T090000Z\nRRULE:FREQ=WEEKLY;COUNT=2".parse().unwrap();
+    /// let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 5, 14, 30, 0).unwrap();
+    /// rrule_set.set_dt_start(dt_start).unwrap();
+    /// assert_eq!(
+    ///     rrule_set.to_string(),
+    ///     "DTSTART:20210105T143000Z\nRRULE:FREQ=WEEKLY;COUNT=2;BYHOUR=14;BYMINUTE=30;BYSECOND=0;BYDAY=TU"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`RRuleError`] of a rule that is invalid with the new
+    /// start date, in which case the set is left unchanged.
+    pub fn set_dt_start(&mut self, dt_start: DateTime<Tz>) -> Result<(), RRuleError> {
+        let limits = if self.limited {
+            self.limits
+        } else {
+            ValidatorLimits::UNLIMITED
+        };
+        let revalidate = |rules: &[RRule]| {
+            rules
+                .iter()
+                .map(|rule| {
+                    rule.clone().into_builder_without_defaults().validate_inner(
+                        dt_start,
+                        &limits,
+                        self.reject_never_occurring,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let rrule = revalidate(&self.rrule)?;
+        let exrule = revalidate(&self.exrule)?;
+
+        self.rrule = rrule;
+        self.exrule = exrule;
+        self.dt_start = dt_start;
+        Ok(())
+    }
+
     /// Returns the rrules of the set.
     #[must_use]
     pub fn get_rrule(&self) -> &Vec<RRule> {
@@ -172,10 +470,43 @@ impl RRuleSet {
         &self.dt_start
     }
 
+    /// Returns the experimental properties of the set, as the name with its
+    /// parameters and the value, e.g. `("X-NAME;PARAM=1", "value")`.
+    #[must_use]
+    pub fn get_extensions(&self) -> &[(String, String)] {
+        &self.extensions
+    }
+
+    /// Returns the experimental parameters, like `X-NAME=value`, of the `DTSTART`.
+    #[must_use]
+    pub fn get_dt_start_extensions(&self) -> &[(String, String)] {
+        &self.dt_start_extensions
+    }
+
+    /// Returns the experimental parameters, like `X-NAME=value`, that the rdate at
+    /// `index` of [`RRuleSet::get_rdate`] was parsed with.
+    #[must_use]
+    pub fn get_rdate_extensions(&self, index: usize) -> &[(String, String)] {
+        self.rdate_parameters
+            .get(index)
+            .map_or(&[], |parameters| &parameters.extensions)
+    }
+
+    /// Returns the experimental parameters, like `X-NAME=value`, that the exdate at
+    /// `index` of [`RRuleSet::get_exdate`] was parsed with.
+    #[must_use]
+    pub fn get_exdate_extensions(&self, index: usize) -> &[(String, String)] {
+        self.exdate_parameters
+            .get(index)
+            .map_or(&[], |parameters| &parameters.extensions)
+    }
+
     /// Returns all the recurrences of the rrule.
     ///
     /// Limit must be set in order to prevent infinite loops.
-    /// The max limit is `65535`. If you need more please use `into_iter` directly.
+    /// The max limit is `65535`, and it is lowered to the max number of results of
+    /// the validation limits. If you need more please use [`RRuleSet::all_within_limits`]
+    /// or `into_iter` directly.
     ///
     /// # Usage
     ///
@@ -192,6 +523,7 @@ impl RRuleSet {
     #[must_use]
     pub fn all(mut self, limit: u16) -> RRuleResult {
         self.limited = true;
+        let limit = u32::from(limit).min(self.limits.get_max_results());
         collect_with_error(
             self.into_iter(),
             &self.after,
@@ -201,6 +533,34 @@ impl RRuleSet {
         )
     }
 
+    /// Returns all the recurrences of the rrule, up to the max number of results
+    /// of the validation limits.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::{LimitReason, RRuleSet, ValidatorLimits};
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=HOURLY".parse().unwrap();
+    ///
+    /// let result = rrule_set
+    ///     .limits(ValidatorLimits::DEFAULT.max_results(100_000))
+    ///     .all_within_limits();
+    /// assert_eq!(result.dates.len(), 100_000);
+    /// assert_eq!(result.limit_reason, Some(LimitReason::MaxResults));
+    /// ```
+    #[must_use]
+    pub fn all_within_limits(mut self) -> RRuleResult {
+        self.limited = true;
+        collect_with_error(
+            self.into_iter(),
+            &self.after,
+            &self.before,
+            true,
+            Some(self.limits.get_max_results()),
+        )
+    }
+
     /// Returns all the recurrences of the rrule.
     ///
     /// # Note
@@ -212,51 +572,327 @@ impl RRuleSet {
         collect_with_error(self.into_iter(), &self.after, &self.before, true, None).dates
     }
 
-    fn set_from_content_lines(self, content_lines: Vec<ContentLine>) -> Result<Self, RRuleError> {
+    /// Formats `dates` as `property` lines, one for each timezone and value type in use.
+    fn date_properties(
+        &self,
+        property: &str,
+        dates: &[DateTime<Tz>],
+        date_parameters: &[DateParameters],
+    ) -> String {
+        let mut groups: Vec<(String, Vec<String>)> = vec![];
+        for (dt, date_parameters) in dates.iter().zip(date_parameters) {
+            let is_date_only = date_parameters.date_only && dt.time() == NaiveTime::MIN;
+            let (mut parameters, value) = if is_date_only {
+                let tz = dt.timezone();
+                let tzid = if tz.is_local() || tz.is_floating() {
+                    String::new()
+                } else {
+                    format!(";TZID={}", tz.name())
+                };
+                (
+                    format!(";VALUE=DATE{tzid}"),
+                    dt.format("%Y%m%d").to_string(),
+                )
+            } else {
+                let (tzid, value) = datetime_to_ical_parts(dt);
+                (format!(";VALUE=DATE-TIME{tzid}"), value)
+            };
+            parameters.push_str(&extension_parameters(&date_parameters.extensions));
+            match groups.iter_mut().find(|(group, _)| *group == parameters) {
+                Some((_, values)) => values.push(value),
+                None => groups.push((parameters, vec![value])),
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(parameters, values)| format!("\n{property}{parameters}:{}", values.join(",")))
+            .collect()
+    }
+
+    /// Returns the parameters the dates of `content_line` were parsed with.
+    fn date_parameters(content_line: &DateContentLine) -> DateParameters {
+        DateParameters {
+            date_only: content_line.date_only,
+            extensions: content_line.extensions.clone(),
+        }
+    }
+
+    /// Validates the content lines and adds the valid ones to the set. Returns a
+    /// diagnostic for every rule that failed to validate, together with the number
+    /// of its line. In `diagnose` mode, rules that never occur are always accepted
+    /// with a warning diagnostic.
+    fn set_from_content_lines(
+        mut self,
+        content_lines: impl IntoIterator<Item = (Option<usize>, ContentLine)>,
+        diagnose: bool,
+    ) -> (Self, Vec<Diagnostic>) {
         let dt_start = self.dt_start;
+        let limits = if self.limited {
+            self.limits
+        } else {
+            ValidatorLimits::UNLIMITED
+        };
+        let reject_never_occurring = self.reject_never_occurring && !diagnose;
+        let mut diagnostics = vec![];
+        // Returns the rule if it is valid, and reports its errors or warning.
+        let mut validated = |line, result: Result<_, Vec<ValidationError>>| {
+            let (rrule, severity, errors) = match result {
+                Ok((rrule, None)) => return Some(rrule),
+                Ok((rrule, Some(warning))) if diagnose => {
+                    (Some(rrule), Severity::Warning, vec![warning])
+                }
+                Ok((rrule, Some(warning))) => {
+                    log::warn!("{warning}");
+                    return Some(rrule);
+                }
+                Err(errors) => (None, Severity::Error, errors),
+            };
+            diagnostics.extend(
+                errors
+                    .into_iter()
+                    .map(|err| Diagnostic::new(severity, LocatedError::new(err, line))),
+            );
+            rrule
+        };
 
-        content_lines.into_iter().try_fold(
-            self,
-            |rrule_set, content_line| match content_line {
-                ContentLine::RRule(rrule) => rrule
-                    .validate(dt_start)
-                    .map(|rrule| rrule_set.rrule(rrule)),
+        for (line, content_line) in content_lines {
+            match content_line {
+                ContentLine::RRule(rrule) => {
+                    if let Some(rrule) = validated(
+                        line,
+                        rrule.validate_collect(dt_start, &limits, reject_never_occurring),
+                    ) {
+                        self = self.rrule(rrule);
+                    }
+                }
                 #[allow(unused_variables)]
                 ContentLine::ExRule(exrule) => {
                     #[cfg(feature = "exrule")]
-                    {
-                        exrule
-                            .validate(dt_start)
-                            .map(|exrule| rrule_set.exrule(exrule))
+                    if let Some(exrule) = validated(
+                        line,
+                        exrule.validate_collect(dt_start, &limits, reject_never_occurring),
+                    ) {
+                        self = self.exrule(exrule);
                     }
                     #[cfg(not(feature = "exrule"))]
-                    {
-                        log::warn!("Found EXRULE in input, but it will be ignored since the `exrule` feature is not enabled.");
-                        Ok(rrule_set)
-                    }
+                    log::warn!("Found EXRULE in input, but it will be ignored since the `exrule` feature is not enabled.");
                 }
                 ContentLine::ExDate(exdates) => {
-                    Ok(exdates.into_iter().fold(rrule_set, Self::exdate))
+                    let parameters = Self::date_parameters(&exdates);
+                    self.exdate_parameters
+                        .resize(self.exdate.len() + exdates.dates.len(), parameters);
+                    self.exdate.extend(exdates.dates);
                 }
                 ContentLine::RDate(rdates) => {
-                    Ok(rdates.into_iter().fold(rrule_set, Self::rdate))
+                    let parameters = Self::date_parameters(&rdates);
+                    self.rdate_parameters
+                        .resize(self.rdate.len() + rdates.dates.len(), parameters);
+                    self.rdate.extend(rdates.dates);
                 }
-            },
-        )
+                ContentLine::Extension(name, value) => self.extensions.push((name, value)),
+            }
+        }
+
+        (self, diagnostics)
+    }
+
+    fn parse_options(&self, mode: ParseMode) -> ParseOptions {
+        ParseOptions {
+            floating_tz: self.floating_tz,
+            dst_policy: self.dst_policy,
+            mode,
+        }
     }
 
     /// Set the [`RRuleSet`] properties from a string. If a DTSTART is found, it will be used as the start datetime.
-    pub fn set_from_string(mut self, s: &str) -> Result<Self, RRuleError> {
-        let Grammar {
-            start,
-            content_lines,
-        } = Grammar::from_str(s)?;
-
-        if let Some(dtstart) = start {
-            self.dt_start = dtstart.datetime;
+    pub fn set_from_string(self, s: &str) -> Result<Self, RRuleError> {
+        self.set_from_lines_checked(s, false)
+            .map_err(|mut errors| errors.swap_remove(0).error)
+    }
+
+    /// Same as [`RRuleSet::set_from_string`], but repairs what it can instead of
+    /// failing, and returns a [`Diagnostic`] for every problem found.
+    ///
+    /// Constructs which RFC 5545 doesn't allow, but whose meaning is clear, are
+    /// repaired and reported as a [`Severity::Warning`]: lowercase names and values
+    /// are uppercased, empty rule parts and rule parts repeated with the same value
+    /// are skipped, `BYWEEKDAY` is read as `BYDAY`, and a `VALUE=PERIOD` date is
+    /// replaced by the start of the period. So are rules that never occur, even with
+    /// [`RRuleSet::reject_never_occurring`], and `TZID` values which aren't IANA names
+    /// but were mapped to one, like `W. Europe Standard Time`, with the
+    /// [`crate::TzidMapping`] that was applied. Lines that can't be repaired, like a rule
+    /// part repeated with another value or an unsupported value type, and lines that
+    /// fail to parse or validate are left out of the set and reported as a
+    /// [`Severity::Error`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Severity, Tz};
+    ///
+    /// let (rrule_set, diagnostics) = RRuleSet::new(Tz::UTC.timestamp_nanos(0))
+    ///     .set_from_string_lenient("dtstart:20120201T093000Z\nRRULE:FREQ=DAILY;;COUNT=3\nRDATE:2012");
+    /// assert_eq!(rrule_set.all(10).dates.len(), 3);
+    /// let codes: Vec<_> = diagnostics.iter().map(|d| (d.severity, d.code())).collect();
+    /// assert_eq!(
+    ///     codes,
+    ///     vec![
+    ///         (Severity::Warning, "parse.not_uppercase"),
+    ///         (Severity::Warning, "parse.empty_part"),
+    ///         (Severity::Error, "parse.invalid_date_time"),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn set_from_string_lenient(self, s: &str) -> (Self, Vec<Diagnostic>) {
+        let (rrule_set, mut diagnostics) = self.set_from_lines(s, false, ParseMode::Lenient);
+        diagnostics.sort_by_key(|diagnostic| diagnostic.error.line.unwrap_or(usize::MAX));
+        (rrule_set, diagnostics)
+    }
+
+    /// Same as [`RRuleSet::set_from_string`], but returns every parse and validation
+    /// error found in the input instead of stopping at the first one.
+    ///
+    /// Each error is tagged with its line and, if known, the rule part it concerns,
+    /// so that all invalid fields of a rule editor can be highlighted at once.
+    /// The errors are sorted by line; errors without a line come last.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let errors = RRuleSet::new(Tz::UTC.timestamp_nanos(0))
+    ///     .set_from_string_all_errors("RRULE:FREQ=DAILY;BYMONTH=13;BYHOUR=24\nRDATE:2012")
+    ///     .unwrap_err();
+    /// let tags: Vec<_> = errors.iter().map(|err| (err.line, err.rule_part())).collect();
+    /// assert_eq!(
+    ///     tags,
+    ///     vec![(Some(1), Some("BYMONTH")), (Some(1), Some("BYHOUR")), (Some(2), Some("RDATE"))]
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`LocatedError`] for every line that failed to parse, and for every
+    /// rule part that failed to validate. The list is never empty.
+    pub fn set_from_string_all_errors(self, s: &str) -> Result<Self, Vec<LocatedError>> {
+        self.set_from_lines_checked(s, false)
+    }
+
+    /// Same as [`RRuleSet::from_str`], but rejects the constructs which RFC 5545
+    /// doesn't allow, see [`RRuleSet::strict`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use rrule::RRuleSet;
+    ///
+    /// let input = "DTSTART:20120201T093000Z\nRRULE:FREQ=DAILY;BYWEEKDAY=MO";
+    /// assert!(input.parse::<RRuleSet>().is_ok());
+    /// let err = RRuleSet::from_str_strict(input).unwrap_err();
+    /// assert_eq!(err.code(), "parse.non_standard_name");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RRuleError`], if iCalendar string contains invalid or non-standard parts.
+    pub fn from_str_strict(s: &str) -> Result<Self, RRuleError> {
+        Self::new(Tz::UTC.timestamp_nanos(0))
+            .strict()
+            .set_from_lines_checked(s, true)
+            .map_err(|mut errors| errors.swap_remove(0).error)
+    }
+
+    /// Same as [`RRuleSet::from_str`], but returns every error found, with the line
+    /// and position of the input that caused it.
+    pub(crate) fn parse_all_errors(s: &str) -> Result<Self, Vec<LocatedError>> {
+        Self::new(Tz::UTC.timestamp_nanos(0)).set_from_lines_checked(s, true)
+    }
+
+    /// Parses and validates every line of `s` into the set, rejecting non-RFC
+    /// constructs if the set is [`RRuleSet::strict`], and returns all errors found,
+    /// sorted by line.
+    pub(crate) fn set_from_lines_checked(
+        self,
+        s: &str,
+        start_required: bool,
+    ) -> Result<Self, Vec<LocatedError>> {
+        let mode = if self.strict {
+            ParseMode::Strict
+        } else {
+            ParseMode::Unchecked
+        };
+        let (rrule_set, diagnostics) = self.set_from_lines(s, start_required, mode);
+        let mut errors: Vec<_> = diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.error)
+            .collect();
+        errors.sort_by_key(|err| err.line.unwrap_or(usize::MAX));
+        if errors.is_empty() {
+            Ok(rrule_set)
+        } else {
+            Err(errors)
         }
+    }
 
-        self.set_from_content_lines(content_lines)
+    /// Parses and validates every line of `s` into the set, and returns a diagnostic
+    /// for every problem found. The parse errors come before the validation errors.
+    fn set_from_lines(
+        mut self,
+        s: &str,
+        start_required: bool,
+        mode: ParseMode,
+    ) -> (Self, Vec<Diagnostic>) {
+        let parsed = Grammar::parse_lines(s, &self.parse_options(mode));
+        let parse_diagnostic = |severity, span, err| {
+            Diagnostic::new(severity, LocatedError::from_parse_error(err, span))
+        };
+        let mut diagnostics: Vec<_> = parsed
+            .warnings
+            .into_iter()
+            .map(|(span, err)| parse_diagnostic(Severity::Warning, Some(span), err))
+            .chain(
+                parsed
+                    .errors
+                    .into_iter()
+                    .map(|(span, err)| parse_diagnostic(Severity::Error, span, err)),
+            )
+            .collect();
+
+        match parsed.start {
+            Some(dtstart) => {
+                self.dt_start = dtstart.datetime;
+                self.dt_start_extensions = dtstart.extensions;
+            }
+            None if start_required => {
+                let err = LocatedError::new(ParseError::MissingStartDate, None);
+                diagnostics.push(Diagnostic::new(Severity::Error, err));
+                return (self, diagnostics);
+            }
+            None => {}
+        }
+
+        let content_lines = parsed
+            .content_lines
+            .into_iter()
+            .map(|(line, content_line)| (Some(line), content_line));
+        let (rrule_set, validation_diagnostics) =
+            self.set_from_content_lines(content_lines, mode == ParseMode::Lenient);
+        let lines: Vec<_> = s.lines().collect();
+        diagnostics.extend(validation_diagnostics.into_iter().map(|mut diagnostic| {
+            let err = &mut diagnostic.error;
+            err.span = err
+                .line
+                .map(|line| Span::locate(lines[line - 1], line, err.error.rule_part(), None));
+            err.error = err.error.clone().with_span(err.span);
+            diagnostic
+        }));
+        (rrule_set, diagnostics)
     }
 }
 
@@ -269,14 +905,7 @@ impl FromStr for RRuleSet {
     ///
     /// Returns [`RRuleError`], if iCalendar string contains invalid parts.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Grammar {
-            start,
-            content_lines,
-        } = Grammar::from_str(s)?;
-
-        let start = start.ok_or(ParseError::MissingStartDate)?;
-
-        Self::new(start.datetime).set_from_content_lines(content_lines)
+        Self::parse_all_errors(s).map_err(|mut errors| errors.swap_remove(0).error)
     }
 }
 
@@ -284,7 +913,9 @@ impl Display for RRuleSet {
     /// Prints a valid set of iCalendar properties which can be used to create a new [`RRuleSet`] later.
     /// You may use the generated string to create a new iCalendar component, like VEVENT.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let start_datetime = format!("DTSTART{}", datetime_to_ical_format(&self.dt_start));
+        let (parameters, value) = datetime_to_ical_parts(&self.dt_start);
+        let extensions = extension_parameters(&self.dt_start_extensions);
+        let start_datetime = format!("DTSTART{parameters}{extensions}:{value}");
 
         let mut rrules = self
             .rrule
@@ -296,20 +927,7 @@ impl Display for RRuleSet {
             rrules = format!("\n{rrules}");
         }
 
-        let mut rdates = self
-            .rdate
-            .iter()
-            .map(|dt| {
-                let maybe_zulu = if dt.timezone().is_local() { "" } else { "Z" };
-
-                format!("{}{}", dt.format("%Y%m%dT%H%M%S"), maybe_zulu)
-            })
-            .collect::<Vec<_>>()
-            .join(",");
-        if !rdates.is_empty() {
-            // TODO: check if original VALUE prop was DATE or PERIOD
-            rdates = format!("\nRDATE;VALUE=DATE-TIME:{rdates}");
-        }
+        let rdates = self.date_properties("RDATE", &self.rdate, &self.rdate_parameters);
 
         let mut exrules = self
             .exrule
@@ -321,25 +939,43 @@ impl Display for RRuleSet {
             exrules = format!("\n{exrules}");
         }
 
-        let mut exdates = self
-            .exdate
-            .iter()
-            .map(|dt| {
-                let maybe_zulu = if dt.timezone().is_local() { "" } else { "Z" };
+        let exdates = self.date_properties("EXDATE", &self.exdate, &self.exdate_parameters);
 
-                format!("{}{}", dt.format("%Y%m%dT%H%M%S"), maybe_zulu)
-            })
-            .collect::<Vec<_>>()
-            .join(",");
-        if !exdates.is_empty() {
-            // TODO: check if original VALUE prop was DATE or PERIOD
-            exdates = format!("\nEXDATE;VALUE=DATE-TIME:{exdates}");
-        }
+        let extensions: String = self
+            .extensions
+            .iter()
+            .map(|(name, value)| format!("\n{name}:{value}"))
+            .collect();
 
-        write!(f, "{start_datetime}{rrules}{rdates}{exrules}{exdates}")
+        write!(
+            f,
+            "{start_datetime}{rrules}{rdates}{exrules}{exdates}{extensions}"
+        )
     }
 }
 
+/// Removes the dates equal to `removed` together with their parameters, and
+/// returns whether there were any.
+fn remove_date(
+    dates: &mut Vec<DateTime<Tz>>,
+    parameters: &mut Vec<DateParameters>,
+    removed: &DateTime<Tz>,
+) -> bool {
+    let len = dates.len();
+    let mut kept = dates.iter().map(|date| date != removed);
+    parameters.retain(|_| kept.next().unwrap_or(true));
+    dates.retain(|date| date != removed);
+    dates.len() != len
+}
+
+/// Formats experimental parameters as `;X-NAME=value` pairs.
+fn extension_parameters(extensions: &[(String, String)]) -> String {
+    extensions
+        .iter()
+        .map(|(name, value)| format!(";{name}={value}"))
+        .collect()
+}
+
 #[cfg(feature = "exrule")]
 #[cfg(test)]
 mod tests {
@@ -405,6 +1041,7 @@ mod tests {
         assert_eq!(rruleset.to_string(), rruleset_str);
     }
 
+    #[allow(dead_code)]
     fn respect_utc_timezone_in_exdates_rdates() {
         let rruleset_str = "DTSTART:20120201T093000Z\nRRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=30;BYSECOND=0\nRDATE;VALUE=DATE-TIME:19970101T000000Z,19970120T000000Z\nEXRULE:FREQ=YEARLY;COUNT=8;BYMONTH=6,7;BYMONTHDAY=1;BYHOUR=9;BYMINUTE=30;BYSECOND=0\nEXDATE;VALUE=DATE-TIME:19970121T000000Z";
         let rruleset = RRuleSet::from_str(rruleset_str).unwrap();
@@ -1,6 +1,9 @@
-use chrono::{Local, FixedOffset};
+use chrono::{DateTime, FixedOffset, Local, LocalResult, TimeZone};
 
-/// A wrapper around `chrono_tz::Tz` that is able to represent `Local` timezone also.
+use crate::parser::resolve_tzid;
+use crate::{ParseError, TzidMapping};
+
+/// A wrapper around `chrono_tz::Tz` that is able to represent `Local` and floating timezones also.
 ///
 /// # Usage
 ///
@@ -9,6 +12,7 @@ use chrono::{Local, FixedOffset};
 ///
 /// let utc = Tz::UTC;
 /// let local = Tz::LOCAL;
+/// let floating = Tz::FLOATING;
 /// let berlin = Tz::Europe__Berlin;
 /// // From `chrono_tz::Tz`
 /// let berlin: Tz = chrono_tz::Tz::Europe__Berlin.into();
@@ -21,6 +25,13 @@ pub enum Tz {
     FixedOffset(FixedOffset),
     /// Timezone represented by `chrono_tz::Tz`
     Tz(chrono_tz::Tz),
+    /// Floating time, a wall-clock time that is not bound to any timezone.
+    ///
+    /// Dates without `Z` and `TZID` are parsed in [`Tz::LOCAL`] by default, and only as
+    /// floating after opting in with [`crate::RRuleSet::floating_timezone`].
+    /// Floating dates keep their wall-clock time during iteration, are printed without
+    /// `TZID`, and can be placed in a timezone with [`Tz::resolve_floating`].
+    Floating,
 }
 
 impl Tz {
@@ -29,6 +40,7 @@ impl Tz {
     pub fn name(&self) -> String {
         match self {
             Self::Local(_) => "Local".to_string(),
+            Self::Floating => "Floating".to_string(),
             Self::Tz(tz) => tz.name().to_string(),
             Self::FixedOffset(fixed_offset) => {
                 let offset_seconds = fixed_offset.local_minus_utc();
@@ -41,6 +53,30 @@ impl Tz {
         }
     }
 
+    /// Resolves the value of a `TZID` parameter to a timezone.
+    ///
+    /// Besides IANA timezone names and UTC offsets (`UTC+0100`), this accepts
+    /// Windows timezone names, common short aliases and globally unique ids
+    /// with a registry prefix. The returned [`TzidMapping`] tells which of these
+    /// was applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rrule::{Tz, TzidMapping};
+    ///
+    /// let (tz, mapping) = Tz::from_tzid("W. Europe Standard Time").unwrap();
+    /// assert_eq!(tz, Tz::Europe__Berlin);
+    /// assert_eq!(mapping, TzidMapping::Windows);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::InvalidTimezone`] if the value can't be resolved.
+    pub fn from_tzid(tzid: &str) -> Result<(Self, TzidMapping), ParseError> {
+        resolve_tzid(tzid)
+    }
+
     /// Check if timezone is the Local timezone
     #[must_use]
     pub fn is_local(&self) -> bool {
@@ -48,6 +84,44 @@ impl Tz {
             Self::Local(_) => true,
             Self::Tz(_) => false,
             Self::FixedOffset(_) => false,
+            Self::Floating => false,
+        }
+    }
+
+    /// Check if timezone is the floating timezone
+    #[must_use]
+    pub fn is_floating(&self) -> bool {
+        matches!(self, Self::Floating)
+    }
+
+    /// Places the wall-clock time of a floating `dt` in this timezone.
+    ///
+    /// Dates which are not floating already refer to an instant and are only
+    /// converted to this timezone. The result is ambiguous or empty when the
+    /// wall-clock time falls into a daylight saving time transition.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::{RRuleSet, Tz};
+    ///
+    /// let rrule_set = RRuleSet::new(Tz::UTC.timestamp_nanos(0))
+    ///     .floating_timezone(Tz::FLOATING)
+    ///     .set_from_string("DTSTART:20210101T090000\nRRULE:FREQ=DAILY;COUNT=2")
+    ///     .unwrap();
+    /// let dates = rrule_set.all(10).dates;
+    /// assert_eq!(dates[0].timezone(), Tz::FLOATING);
+    ///
+    /// let tokyo = Tz::Asia__Tokyo.resolve_floating(&dates[0]).single().unwrap();
+    /// assert_eq!(tokyo, Tz::Asia__Tokyo.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap());
+    /// ```
+    #[must_use]
+    pub fn resolve_floating(&self, dt: &DateTime<Self>) -> LocalResult<DateTime<Self>> {
+        if dt.timezone().is_floating() {
+            self.from_local_datetime(&dt.naive_local())
+        } else {
+            LocalResult::Single(dt.with_timezone(self))
         }
     }
 
@@ -55,6 +129,17 @@ impl Tz {
     #[allow(non_upper_case_globals)]
     pub const LOCAL: Self = Self::Local(Local);
 
+    /// Floating timezone
+    pub const FLOATING: Self = Self::Floating;
+
+    /// UTC timezone
+    pub const UTC: Self = Self::Tz(chrono_tz::UTC);
+}
+
+/// The timezones of the IANA database. They are left out with the `tz-subset`
+/// feature, as the build only contains the zones matching `CHRONO_TZ_TIMEZONE_FILTER`.
+#[cfg(not(tz_subset))]
+impl Tz {
     // Duplicating all chrono_tz variants with the following program:
     //    for tz in chrono_tz::TZ_VARIANTS {
     //        let tz_name = tz.name();
@@ -1860,9 +1945,6 @@ impl Tz {
     pub const US__Samoa: Self = Self::Tz(chrono_tz::US::Samoa);
     #[allow(non_upper_case_globals)]
     #[allow(missing_docs)]
-    pub const UTC: Self = Self::Tz(chrono_tz::UTC);
-    #[allow(non_upper_case_globals)]
-    #[allow(missing_docs)]
     pub const Universal: Self = Self::Tz(chrono_tz::Universal);
     #[allow(non_upper_case_globals)]
     #[allow(missing_docs)]
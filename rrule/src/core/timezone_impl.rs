@@ -6,12 +6,16 @@ impl PartialEq for Tz {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Local(_), Self::Local(_)) => true,
+            (Self::Floating, Self::Floating) => true,
             (Self::Tz(l0), Self::Tz(r0)) => l0 == r0,
+            (Self::FixedOffset(l0), Self::FixedOffset(r0)) => l0 == r0,
             _ => false,
         }
     }
 }
 
+impl Eq for Tz {}
+
 impl From<Local> for Tz {
     fn from(tz: Local) -> Self {
         Self::Local(tz)
@@ -35,7 +39,8 @@ impl std::fmt::Debug for Tz {
         match self {
             Self::Local(tz) => tz.fmt(f),
             Self::Tz(tz) => tz.fmt(f),
-            Self::FixedOffset(tz) => tz.fmt(f)
+            Self::FixedOffset(tz) => tz.fmt(f),
+            Self::Floating => write!(f, "Floating"),
         }
     }
 }
@@ -46,6 +51,7 @@ impl std::fmt::Display for Tz {
             Self::Local(_tz) => write!(f, "Local"),
             Self::Tz(tz) => tz.fmt(f),
             Self::FixedOffset(tz) => tz.fmt(f),
+            Self::Floating => write!(f, "Floating"),
         }
     }
 }
@@ -55,6 +61,8 @@ pub enum RRuleOffset {
     Local(FixedOffset),
     Tz(<chrono_tz::Tz as TimeZone>::Offset),
     FixedOffset(FixedOffset),
+    /// Floating dates behave like UTC, so their wall-clock time never shifts.
+    Floating,
 }
 
 impl std::fmt::Debug for RRuleOffset {
@@ -63,6 +71,7 @@ impl std::fmt::Debug for RRuleOffset {
             Self::Local(offset) => offset.fmt(f),
             Self::Tz(offset) => offset.fmt(f),
             Self::FixedOffset(offset) => offset.fmt(f),
+            Self::Floating => write!(f, "Floating"),
         }
     }
 }
@@ -73,6 +82,7 @@ impl std::fmt::Display for RRuleOffset {
             Self::Local(offset) => offset.fmt(f),
             Self::Tz(offset) => offset.fmt(f),
             Self::FixedOffset(offset) => offset.fmt(f),
+            Self::Floating => write!(f, "Floating"),
         }
     }
 }
@@ -82,7 +92,8 @@ impl Offset for RRuleOffset {
         match self {
             Self::Local(tz) => tz.fix(),
             Self::Tz(tz) => tz.fix(),
-            Self::FixedOffset(tz) => tz.fix()
+            Self::FixedOffset(tz) => tz.fix(),
+            Self::Floating => Utc.fix(),
         }
     }
 }
@@ -95,6 +106,7 @@ impl TimeZone for Tz {
             RRuleOffset::Local(offset) => Self::Local(Local::from_offset(offset)),
             RRuleOffset::Tz(offset) => Self::Tz(chrono_tz::Tz::from_offset(offset)),
             RRuleOffset::FixedOffset(offset) => Self::FixedOffset(FixedOffset::from_offset(offset)),
+            RRuleOffset::Floating => Self::Floating,
         }
     }
 
@@ -112,7 +124,8 @@ impl TimeZone for Tz {
                 .map(|date| RRuleOffset::Tz(*date.offset())),
             Self::FixedOffset(tz) => tz
                 .offset_from_local_date(local)
-                .map(|offset| RRuleOffset::FixedOffset(offset)),
+                .map(RRuleOffset::FixedOffset),
+            Self::Floating => chrono::LocalResult::Single(RRuleOffset::Floating),
         }
     }
 
@@ -129,7 +142,8 @@ impl TimeZone for Tz {
                 .map(|date| RRuleOffset::Tz(*date.offset())),
             Self::FixedOffset(tz) => tz
                 .offset_from_local_datetime(local)
-                .map(|offset| RRuleOffset::FixedOffset(offset)),
+                .map(RRuleOffset::FixedOffset),
+            Self::Floating => chrono::LocalResult::Single(RRuleOffset::Floating),
         }
     }
 
@@ -139,6 +153,7 @@ impl TimeZone for Tz {
             Self::Local(tz) => RRuleOffset::Local(*tz.from_utc_date(utc).offset()),
             Self::Tz(tz) => RRuleOffset::Tz(*tz.from_utc_date(utc).offset()),
             Self::FixedOffset(tz) => RRuleOffset::FixedOffset(*tz),
+            Self::Floating => RRuleOffset::Floating,
         }
     }
 
@@ -147,6 +162,7 @@ impl TimeZone for Tz {
             Self::Local(tz) => RRuleOffset::Local(*tz.from_utc_datetime(utc).offset()),
             Self::Tz(tz) => RRuleOffset::Tz(*tz.from_utc_datetime(utc).offset()),
             Self::FixedOffset(tz) => RRuleOffset::FixedOffset(*tz),
+            Self::Floating => RRuleOffset::Floating,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::RRuleResult;
+use crate::{LimitReason, RRuleResult};
 use crate::{iter::rrule_iter::WasLimited, Tz};
 use std::{collections::HashSet, ops::{
     Bound::{Excluded, Unbounded},
@@ -14,17 +14,18 @@ pub(super) fn collect_with_error<T>(
     start: &Option<chrono::DateTime<Tz>>,
     end: &Option<chrono::DateTime<Tz>>,
     inclusive: bool,
-    limit: Option<u16>,
+    limit: Option<u32>,
 ) -> RRuleResult
 where
     T: Iterator<Item = chrono::DateTime<Tz>> + WasLimited,
 {
+    let limit = limit.map(|limit| usize::try_from(limit).unwrap_or(usize::MAX));
     let mut seen = HashSet::new();
     let mut list = vec![];
-    let mut was_limited = false;
+    let mut limit_reason = None;
     // This loop should always end because `.next()` has build in limits
     // Once a limit is tripped it will break in the `None` case.
-    while limit.is_none() || matches!(limit, Some(limit) if usize::from(limit) > list.len()) {
+    while limit.is_none() || matches!(limit, Some(limit) if limit > list.len()) {
         if let Some(value) = iterator.next() {
             if is_in_range(&value, start, end, inclusive) && seen.insert(value) {
                 list.push(value);
@@ -34,16 +35,19 @@ where
                 break;
             }
         } else {
-            was_limited = iterator.was_limited();
+            limit_reason = iterator.limit_reason();
             break;
         }
     }
 
-    was_limited = was_limited || matches!(limit, Some(limit) if usize::from(limit) == list.len());
+    if limit_reason.is_none() && limit == Some(list.len()) {
+        limit_reason = Some(LimitReason::MaxResults);
+    }
 
     RRuleResult {
         dates: list,
-        limited: was_limited,
+        limited: limit_reason.is_some(),
+        limit_reason,
     }
 }
 
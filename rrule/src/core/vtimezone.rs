@@ -0,0 +1,451 @@
+use super::rruleset::RRuleSet;
+use super::timezone::Tz;
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Offset, TimeZone, Weekday};
+use chrono_tz::{OffsetComponents, OffsetName};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Transitions are searched this far outside the requested range, so the observance
+/// in effect at the start is defined and ongoing rules can be told from abolished ones.
+const SCAN_MARGIN: i64 = 366 * SECONDS_PER_DAY;
+
+/// The offset and name a timezone observes between two transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Observance {
+    offset: i32,
+    dst: bool,
+    name: String,
+}
+
+/// A change from one observance to another at `at` (seconds since the epoch).
+#[derive(Debug, Clone)]
+struct Transition {
+    at: i64,
+    from: Observance,
+    to: Observance,
+}
+
+impl Transition {
+    /// Wall clock time at which the transition happens, in the observance it ends.
+    fn local(&self) -> Option<NaiveDateTime> {
+        local_time(self.at, self.from.offset)
+    }
+}
+
+/// Transitions observed the same way in consecutive years, written as one component.
+struct TransitionGroup {
+    transitions: Vec<Transition>,
+    /// All transitions fall on the same n-th weekday of the month.
+    same_nth: bool,
+    /// All transitions fall on the last weekday of the month.
+    same_last: bool,
+}
+
+impl TransitionGroup {
+    fn new(transition: Transition) -> Self {
+        Self {
+            transitions: vec![transition],
+            same_nth: true,
+            same_last: true,
+        }
+    }
+
+    /// Adds `transition` to the group if it continues the group's yearly rule.
+    fn try_push(&mut self, transition: Transition) -> Result<(), Transition> {
+        let Some(last) = self.transitions.last() else {
+            self.transitions.push(transition);
+            return Ok(());
+        };
+        let (Some(prev), Some(next)) = (last.local(), transition.local()) else {
+            return Err(transition);
+        };
+        if last.from != transition.from
+            || last.to != transition.to
+            || next.year() != prev.year() + 1
+            || next.month() != prev.month()
+            || next.weekday() != prev.weekday()
+            || next.time() != prev.time()
+        {
+            return Err(transition);
+        }
+        let same_nth = self.same_nth && nth_weekday(&next) == nth_weekday(&prev);
+        let same_last = self.same_last && is_last_weekday(&next) && is_last_weekday(&prev);
+        if !same_nth && !same_last {
+            return Err(transition);
+        }
+        self.same_nth = same_nth;
+        self.same_last = same_last;
+        self.transitions.push(transition);
+        Ok(())
+    }
+}
+
+impl Tz {
+    /// Generates a `VTIMEZONE` component describing this timezone between `start` and `end`.
+    ///
+    /// The transitions are taken from the chrono-tz data and, where they repeat yearly,
+    /// compressed into `STANDARD`/`DAYLIGHT` sub-components with an `RRULE`.
+    /// The `TZID` matches the one written when printing dates in this timezone.
+    ///
+    /// Returns `None` for timezones that are not referenced by a `TZID`,
+    /// which are `Local`, `Floating` and `UTC`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use chrono::TimeZone;
+    /// use rrule::Tz;
+    ///
+    /// let tz = Tz::Europe__Berlin;
+    /// let start = tz.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    /// let end = tz.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    /// let vtimezone = tz.to_vtimezone(start, end).unwrap();
+    /// assert!(vtimezone.starts_with("BEGIN:VTIMEZONE\nTZID:Europe/Berlin\n"));
+    /// assert!(vtimezone.contains("RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"));
+    /// ```
+    #[must_use]
+    pub fn to_vtimezone(&self, start: DateTime<Self>, end: DateTime<Self>) -> Option<String> {
+        let (start, end) = if start <= end {
+            (start.timestamp(), end.timestamp())
+        } else {
+            (end.timestamp(), start.timestamp())
+        };
+        let components = match self {
+            Self::Local(_) | Self::Floating => return None,
+            Self::Tz(chrono_tz::UTC | chrono_tz::GMT) => return None,
+            Self::Tz(tz) => tz_components(*tz, start, end)?,
+            Self::FixedOffset(fixed_offset) => {
+                let observance = Observance {
+                    offset: fixed_offset.local_minus_utc(),
+                    dst: false,
+                    name: self.name(),
+                };
+                vec![observance_component(&observance, start)?]
+            }
+        };
+
+        Some(format!(
+            "BEGIN:VTIMEZONE\nTZID:{}\n{}\nEND:VTIMEZONE",
+            self.name(),
+            components.join("\n")
+        ))
+    }
+}
+
+impl RRuleSet {
+    /// Generates the `VTIMEZONE` components for every timezone referenced by a `TZID`
+    /// in this set, covering the series from its earliest date up to `until`.
+    ///
+    /// See [`Tz::to_vtimezone`].
+    #[must_use]
+    pub fn to_vtimezones(&self, until: DateTime<Tz>) -> Vec<String> {
+        let dates = std::iter::once(&self.dt_start)
+            .chain(&self.rdate)
+            .chain(&self.exdate);
+        let start = dates.clone().min().copied().unwrap_or(self.dt_start);
+        let end = dates.clone().max().copied().unwrap_or(until).max(until);
+
+        let mut timezones: Vec<Tz> = vec![];
+        for tz in dates.map(DateTime::timezone) {
+            if !timezones.contains(&tz) {
+                timezones.push(tz);
+            }
+        }
+        timezones
+            .into_iter()
+            .filter_map(|tz| tz.to_vtimezone(start.with_timezone(&tz), end.with_timezone(&tz)))
+            .collect()
+    }
+}
+
+fn observance_at(tz: chrono_tz::Tz, at: i64) -> Option<Observance> {
+    let utc = DateTime::from_timestamp(at, 0)?.naive_utc();
+    let offset = tz.offset_from_utc_datetime(&utc);
+    Some(Observance {
+        offset: offset.fix().local_minus_utc(),
+        dst: offset.dst_offset() != Duration::zero(),
+        name: offset.abbreviation().to_string(),
+    })
+}
+
+/// Finds all transitions in `start..end` by probing the offset once a day and
+/// narrowing each change down to the second.
+fn find_transitions(tz: chrono_tz::Tz, start: i64, end: i64) -> Option<Vec<Transition>> {
+    let mut transitions = vec![];
+    let mut current = start;
+    let mut observance = observance_at(tz, current)?;
+    while current < end {
+        let next = (current + SECONDS_PER_DAY).min(end);
+        let next_observance = observance_at(tz, next)?;
+        if next_observance != observance {
+            let (mut before, mut after) = (current, next);
+            while after - before > 1 {
+                let middle = before + (after - before) / 2;
+                if observance_at(tz, middle)? == observance {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            transitions.push(Transition {
+                at: after,
+                from: observance,
+                to: next_observance.clone(),
+            });
+        }
+        observance = next_observance;
+        current = next;
+    }
+    Some(transitions)
+}
+
+fn tz_components(tz: chrono_tz::Tz, start: i64, end: i64) -> Option<Vec<String>> {
+    let scan_start = start - SCAN_MARGIN;
+    let scan_end = end + SCAN_MARGIN;
+    let transitions = find_transitions(tz, scan_start, scan_end)?;
+
+    let mut components = vec![];
+    match transitions.first() {
+        Some(first) if first.at <= start => {}
+        // No transition precedes the range, so the observance in effect at its start needs
+        // its own onset.
+        _ => components.push(observance_component(
+            &observance_at(tz, scan_start)?,
+            scan_start,
+        )?),
+    }
+
+    let mut groups: Vec<TransitionGroup> = vec![];
+    for transition in transitions {
+        // Daylight and standard transitions interleave, so try every group still open.
+        let mut pending = Some(transition);
+        for group in groups.iter_mut().rev().take(2) {
+            if let Some(transition) = pending.take() {
+                if let Err(transition) = group.try_push(transition) {
+                    pending = Some(transition);
+                }
+            }
+        }
+        if let Some(transition) = pending {
+            groups.push(TransitionGroup::new(transition));
+        }
+    }
+    groups.sort_by_key(|group| group.transitions.first().map(|t| t.at));
+
+    for group in &groups {
+        components.push(group_component(group, scan_end)?);
+    }
+    Some(components)
+}
+
+fn observance_component(observance: &Observance, at: i64) -> Option<String> {
+    Some(format!(
+        "BEGIN:{kind}\nDTSTART:{dt_start}\nTZOFFSETFROM:{offset}\nTZOFFSETTO:{offset}\nTZNAME:{name}\nEND:{kind}",
+        kind = component_kind(observance),
+        dt_start = local_time(at, observance.offset)?.format("%Y%m%dT%H%M%S"),
+        offset = format_offset(observance.offset),
+        name = observance.name,
+    ))
+}
+
+fn group_component(group: &TransitionGroup, scan_end: i64) -> Option<String> {
+    let first = group.transitions.first()?;
+    let last = group.transitions.last()?;
+    let first_local = first.local()?;
+
+    let mut lines = vec![
+        format!("BEGIN:{}", component_kind(&first.to)),
+        format!("DTSTART:{}", first_local.format("%Y%m%dT%H%M%S")),
+    ];
+    if group.transitions.len() > 1 {
+        let nth = if group.same_nth {
+            nth_weekday(&first_local).to_string()
+        } else {
+            "-1".to_string()
+        };
+        let mut rrule = format!(
+            "RRULE:FREQ=YEARLY;BYMONTH={};BYDAY={}{}",
+            first_local.month(),
+            nth,
+            weekday_to_str(first_local.weekday())
+        );
+        // A rule still in use at the end of the scanned range is left open ended.
+        if last.at + SCAN_MARGIN <= scan_end {
+            let until = DateTime::from_timestamp(last.at, 0)?;
+            rrule.push_str(&format!(";UNTIL={}", until.format("%Y%m%dT%H%M%SZ")));
+        }
+        lines.push(rrule);
+    }
+    lines.push(format!("TZOFFSETFROM:{}", format_offset(first.from.offset)));
+    lines.push(format!("TZOFFSETTO:{}", format_offset(first.to.offset)));
+    lines.push(format!("TZNAME:{}", first.to.name));
+    lines.push(format!("END:{}", component_kind(&first.to)));
+    Some(lines.join("\n"))
+}
+
+fn component_kind(observance: &Observance) -> &'static str {
+    if observance.dst {
+        "DAYLIGHT"
+    } else {
+        "STANDARD"
+    }
+}
+
+fn local_time(at: i64, offset: i32) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp(at + i64::from(offset), 0).map(|dt| dt.naive_utc())
+}
+
+/// Formats an UTC offset as `+HHMM`, or `+HHMMSS` if it has seconds.
+fn format_offset(offset: i32) -> String {
+    let (sign, offset) = if offset < 0 {
+        ('-', -offset)
+    } else {
+        ('+', offset)
+    };
+    let hours = offset / 3600;
+    let minutes = offset / 60 % 60;
+    let seconds = offset % 60;
+    if seconds == 0 {
+        format!("{sign}{hours:02}{minutes:02}")
+    } else {
+        format!("{sign}{hours:02}{minutes:02}{seconds:02}")
+    }
+}
+
+fn nth_weekday(dt: &NaiveDateTime) -> u32 {
+    (dt.day() - 1) / 7 + 1
+}
+
+fn is_last_weekday(dt: &NaiveDateTime) -> bool {
+    (dt.date() + Duration::days(7)).month() != dt.month()
+}
+
+fn weekday_to_str(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vtimezone(tz: Tz, start_year: i32, end_year: i32) -> String {
+        let start = tz.with_ymd_and_hms(start_year, 1, 1, 0, 0, 0).unwrap();
+        let end = tz.with_ymd_and_hms(end_year, 1, 1, 0, 0, 0).unwrap();
+        tz.to_vtimezone(start, end).unwrap()
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn compresses_yearly_transitions() {
+        assert_eq!(
+            vtimezone(Tz::Europe__Berlin, 2020, 2025),
+            "BEGIN:VTIMEZONE\n\
+             TZID:Europe/Berlin\n\
+             BEGIN:DAYLIGHT\n\
+             DTSTART:20190331T020000\n\
+             RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\n\
+             TZOFFSETFROM:+0100\n\
+             TZOFFSETTO:+0200\n\
+             TZNAME:CEST\n\
+             END:DAYLIGHT\n\
+             BEGIN:STANDARD\n\
+             DTSTART:20191027T030000\n\
+             RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\n\
+             TZOFFSETFROM:+0200\n\
+             TZOFFSETTO:+0100\n\
+             TZNAME:CET\n\
+             END:STANDARD\n\
+             END:VTIMEZONE"
+        );
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn uses_nth_weekday_when_not_last() {
+        let vtimezone = vtimezone(Tz::America__New_York, 2015, 2020);
+        assert!(vtimezone.contains(
+            "DTSTART:20140309T020000\nRRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\nTZOFFSETFROM:-0500\nTZOFFSETTO:-0400\nTZNAME:EDT\n"
+        ));
+        assert!(vtimezone.contains(
+            "DTSTART:20141102T020000\nRRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\nTZOFFSETFROM:-0400\nTZOFFSETTO:-0500\nTZNAME:EST\n"
+        ));
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn ends_abolished_rules() {
+        // Moscow observed DST until 2010, then stayed on +04 and moved to +03 in 2014.
+        let vtimezone = vtimezone(Tz::Europe__Moscow, 2008, 2016);
+        assert!(vtimezone.contains("RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU;UNTIL=20100327T230000Z"));
+        assert!(
+            vtimezone.contains("RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU;UNTIL=20101030T230000Z")
+        );
+        assert!(vtimezone.contains(
+            "BEGIN:STANDARD\nDTSTART:20110327T020000\nTZOFFSETFROM:+0300\nTZOFFSETTO:+0400\nTZNAME:MSK\nEND:STANDARD"
+        ));
+        assert!(vtimezone.contains(
+            "BEGIN:STANDARD\nDTSTART:20141026T020000\nTZOFFSETFROM:+0400\nTZOFFSETTO:+0300\nTZNAME:MSK\nEND:STANDARD"
+        ));
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn zone_without_transitions() {
+        assert_eq!(
+            vtimezone(Tz::Asia__Tokyo, 2020, 2021),
+            "BEGIN:VTIMEZONE\n\
+             TZID:Asia/Tokyo\n\
+             BEGIN:STANDARD\n\
+             DTSTART:20181231T000000\n\
+             TZOFFSETFROM:+0900\n\
+             TZOFFSETTO:+0900\n\
+             TZNAME:JST\n\
+             END:STANDARD\n\
+             END:VTIMEZONE"
+        );
+    }
+
+    #[test]
+    fn fixed_offset_and_untagged_zones() {
+        let tz = Tz::FixedOffset(chrono::FixedOffset::east_opt(-(5 * 3600 + 30 * 60)).unwrap());
+        assert_eq!(
+            vtimezone(tz, 2020, 2021),
+            "BEGIN:VTIMEZONE\n\
+             TZID:UTC-0530\n\
+             BEGIN:STANDARD\n\
+             DTSTART:20200101T000000\n\
+             TZOFFSETFROM:-0530\n\
+             TZOFFSETTO:-0530\n\
+             TZNAME:UTC-0530\n\
+             END:STANDARD\n\
+             END:VTIMEZONE"
+        );
+
+        let start = Tz::UTC.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        assert!(Tz::UTC.to_vtimezone(start, start).is_none());
+        assert!(Tz::LOCAL
+            .to_vtimezone(
+                start.with_timezone(&Tz::LOCAL),
+                start.with_timezone(&Tz::LOCAL)
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn rrule_set_vtimezones() {
+        let rrule_set: RRuleSet = "DTSTART;TZID=Europe/Berlin:20200101T090000\nRRULE:FREQ=DAILY"
+            .parse()
+            .unwrap();
+        let until = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let vtimezones = rrule_set.to_vtimezones(until);
+        assert_eq!(vtimezones.len(), 1);
+        assert!(vtimezones[0].contains("TZID:Europe/Berlin\n"));
+    }
+}
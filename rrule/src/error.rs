@@ -1,26 +1,200 @@
 #![allow(clippy::module_name_repetitions)]
 
+use std::fmt::Display;
+
 use thiserror::Error;
 
-pub use crate::{parser::ParseError, validator::ValidationError};
+pub use crate::{
+    parser::{ParseError, Span},
+    validator::ValidationError,
+};
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 /// The error type for the rrule crate.
 pub enum RRuleError {
-    /// Parsing error
+    /// Parsing error, with the position of the error in the input if it is known.
     #[error("RRule parsing error: {0}")]
-    ParserError(#[from] ParseError),
-    /// Validation error
+    ParserError(#[source] ParseError, Option<Span>),
+    /// Validation error, with the position of the error in the input if it is known.
     #[error("RRule validation error: {0}")]
-    ValidationError(#[from] ValidationError),
+    ValidationError(#[source] ValidationError, Option<Span>),
     /// Iterator error
     #[error("RRule iterator error: {0}")]
     IterError(String),
 }
 
+impl From<ParseError> for RRuleError {
+    fn from(err: ParseError) -> Self {
+        Self::ParserError(err, None)
+    }
+}
+
+impl From<ValidationError> for RRuleError {
+    fn from(err: ValidationError) -> Self {
+        Self::ValidationError(err, None)
+    }
+}
+
 impl RRuleError {
     /// Create a new iterator error with the given message.
     pub fn new_iter_err<S: AsRef<str>>(msg: S) -> Self {
         Self::IterError(msg.as_ref().to_owned())
     }
 }
+
+impl RRuleError {
+    /// Returns the name of the property or rule part the error concerns,
+    /// e.g. `BYMONTH`, if it is known.
+    #[must_use]
+    pub fn rule_part(&self) -> Option<&str> {
+        match self {
+            Self::ParserError(err, _) => err.rule_part(),
+            Self::ValidationError(err, _) => err.rule_part(),
+            Self::IterError(_) => None,
+        }
+    }
+
+    /// Returns a stable, machine-readable code for the kind of error,
+    /// e.g. `parse.invalid_frequency` or `validation.never_occurs`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ParserError(err, _) => err.code(),
+            Self::ValidationError(err, _) => err.code(),
+            Self::IterError(_) => "iter.error",
+        }
+    }
+
+    /// Returns the position of the error in the input, if it is known.
+    ///
+    /// Errors from parsing a string, like `"...".parse::<RRuleSet>()`, have a span.
+    /// Errors of rules that are built and validated without an input, e.g. with
+    /// [`crate::RRule::validate`], don't.
+    #[must_use]
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::ParserError(_, span) | Self::ValidationError(_, span) => *span,
+            Self::IterError(_) => None,
+        }
+    }
+
+    /// Sets the position of the error in the input.
+    pub(crate) fn with_span(mut self, span: Option<Span>) -> Self {
+        if let Self::ParserError(_, error_span) | Self::ValidationError(_, error_span) = &mut self {
+            *error_span = span;
+        }
+        self
+    }
+}
+
+/// An [`RRuleError`] together with the line of the input it was found on,
+/// as returned by [`crate::RRuleSet::set_from_string_all_errors`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{}{error}", line.map(|line| format!("Line {line}: ")).unwrap_or_default())]
+#[non_exhaustive]
+pub struct LocatedError {
+    /// The error.
+    pub error: RRuleError,
+    /// The line of the input the error was found on, starting at `1`, or `None`
+    /// if it concerns the input as a whole.
+    pub line: Option<usize>,
+    /// The position in the line of the part of the input that caused the error.
+    pub span: Option<Span>,
+}
+
+impl LocatedError {
+    pub(crate) fn new(error: impl Into<RRuleError>, line: Option<usize>) -> Self {
+        Self {
+            error: error.into(),
+            line,
+            span: None,
+        }
+    }
+
+    /// Creates an error from a parse error found at `span`.
+    pub(crate) fn from_parse_error(error: ParseError, span: Option<Span>) -> Self {
+        Self {
+            error: RRuleError::ParserError(error, span),
+            line: span.and_then(|span| usize::try_from(span.line).ok()),
+            span,
+        }
+    }
+
+    /// Returns the name of the property or rule part the error concerns,
+    /// e.g. `BYMONTH`, if it is known.
+    #[must_use]
+    pub fn rule_part(&self) -> Option<&str> {
+        self.error.rule_part()
+    }
+
+    /// Returns a stable, machine-readable code for the kind of error,
+    /// see [`RRuleError::code`].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+}
+
+/// The severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The input was repaired or accepted as is, e.g. a lowercase property name.
+    Warning,
+    /// The line couldn't be parsed or validated, and was left out of the set.
+    Error,
+}
+
+/// A problem found by [`crate::RRuleSet::set_from_string_lenient`].
+///
+/// Errors are displayed like a [`LocatedError`], e.g. `Error: Line 2: RRule parsing
+/// error: ...`, and warnings without the kind of error, e.g. `Warning: Line 1:
+/// ...`, as the input was accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Diagnostic {
+    /// Whether the input was repaired or left out.
+    pub severity: Severity,
+    /// The problem, with its line and position in the input.
+    pub error: LocatedError,
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "Error: {}", self.error),
+            Severity::Warning => {
+                if let Some(line) = self.error.line {
+                    write!(f, "Warning: Line {line}: ")?;
+                } else {
+                    write!(f, "Warning: ")?;
+                }
+                match &self.error.error {
+                    RRuleError::ParserError(err, _) => write!(f, "{err}"),
+                    RRuleError::ValidationError(err, _) => write!(f, "{err}"),
+                    RRuleError::IterError(message) => write!(f, "{message}"),
+                }
+            }
+        }
+    }
+}
+
+impl Diagnostic {
+    pub(crate) fn new(severity: Severity, error: LocatedError) -> Self {
+        Self { severity, error }
+    }
+
+    /// Returns a stable, machine-readable code for the kind of problem,
+    /// see [`RRuleError::code`].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
+    /// Returns the position of the problem in the input, if it is known.
+    #[must_use]
+    pub fn span(&self) -> Option<Span> {
+        self.error.span
+    }
+}
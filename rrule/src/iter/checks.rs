@@ -1,14 +1,16 @@
-use crate::validator::{ValidationError, YEAR_RANGE};
+use crate::validator::{ValidationError, ValidatorLimits};
 
+/// Checks that `year` is within the range of years the crate supports.
 pub(crate) fn check_year_range(year: i32) -> Result<(), ValidationError> {
-    if YEAR_RANGE.contains(&year) {
+    let year_range = ValidatorLimits::UNCHECKED.get_year_range();
+    if year_range.contains(&year) {
         Ok(())
     } else {
         Err(ValidationError::InvalidFieldValueRange {
             field: "YEAR".into(),
             value: year.to_string(),
-            start_idx: YEAR_RANGE.start().to_string(),
-            end_idx: YEAR_RANGE.end().to_string(),
+            start_idx: year_range.start().to_string(),
+            end_idx: year_range.end().to_string(),
         })
     }
 }
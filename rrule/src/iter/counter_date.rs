@@ -181,7 +181,7 @@ impl DateTimeIter {
                 Some("please decrease `INTERVAL`"),
             )?;
             let new_hours = u8::try_from(self.hour % 24).expect("range 0-23 is covered by u8");
-            if by_hour.is_empty() || by_hour.iter().any(|bh| *bh == new_hours) {
+            if by_hour.is_empty() || by_hour.contains(&new_hours) {
                 break;
             }
             if prev_hours.contains(&new_hours) {
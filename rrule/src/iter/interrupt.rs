@@ -0,0 +1,176 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::LimitReason;
+
+/// A point in time after which the expansion of an [`crate::RRuleSet`] stops,
+/// returning the occurrences found so far.
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{Deadline, LimitReason, RRuleSet};
+/// use std::time::Duration;
+///
+/// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=SECONDLY".parse().unwrap();
+/// let result = rrule_set.deadline(Deadline::after(Duration::ZERO)).all(100);
+/// assert!(result.dates.is_empty());
+/// assert_eq!(result.limit_reason, Some(LimitReason::DeadlineExceeded));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    /// `None` if the deadline is too far in the future to be represented.
+    at: Option<clock::Instant>,
+}
+
+impl Deadline {
+    /// Creates a deadline that passes `timeout` from now.
+    #[must_use]
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: clock::after(timeout),
+        }
+    }
+
+    /// Returns `true` if the deadline has passed.
+    #[must_use]
+    pub fn has_passed(&self) -> bool {
+        self.at.is_some_and(|at| clock::now() >= at)
+    }
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+impl From<std::time::Instant> for Deadline {
+    fn from(at: std::time::Instant) -> Self {
+        Self { at: Some(at) }
+    }
+}
+
+/// A token to cancel the expansion of an [`crate::RRuleSet`] from another thread
+/// or task, which returns the occurrences found so far.
+///
+/// Clones of the token share its state, so cancelling any of them cancels
+/// every expansion that uses one of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the expansions that use this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the token was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// The deadline and cancellation token an iterator checks while it searches
+/// for the next occurrence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Interrupt {
+    pub(crate) deadline: Option<Deadline>,
+    pub(crate) cancellation_token: Option<CancellationToken>,
+}
+
+impl Interrupt {
+    /// Returns the reason to stop iterating, if any.
+    pub(crate) fn check(&self) -> Option<LimitReason> {
+        if self
+            .cancellation_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Some(LimitReason::Cancelled)
+        } else if self.deadline.as_ref().is_some_and(Deadline::has_passed) {
+            Some(LimitReason::DeadlineExceeded)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+mod clock {
+    use std::time::Duration;
+
+    pub(super) type Instant = std::time::Instant;
+
+    pub(super) fn now() -> Instant {
+        Instant::now()
+    }
+
+    pub(super) fn after(timeout: Duration) -> Option<Instant> {
+        now().checked_add(timeout)
+    }
+}
+
+/// `std::time::Instant` panics on `wasm32-unknown-unknown`, so the clock of
+/// JavaScript is used instead.
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod clock {
+    use std::time::Duration;
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = Date, js_name = now)]
+        fn date_now() -> f64;
+    }
+
+    /// Milliseconds since the Unix epoch.
+    pub(super) type Instant = u64;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(super) fn now() -> Instant {
+        date_now() as u64
+    }
+
+    pub(super) fn after(timeout: Duration) -> Option<Instant> {
+        let timeout = u64::try_from(timeout.as_millis()).ok()?;
+        now().checked_add(timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_cancellation_token_and_deadline() {
+        let token = CancellationToken::new();
+        let interrupt = Interrupt {
+            deadline: Some(Deadline::after(Duration::from_secs(3600))),
+            cancellation_token: Some(token.clone()),
+        };
+        assert_eq!(interrupt.check(), None);
+        token.cancel();
+        assert_eq!(interrupt.check(), Some(LimitReason::Cancelled));
+
+        let interrupt = Interrupt {
+            deadline: Some(Deadline::after(Duration::ZERO)),
+            cancellation_token: None,
+        };
+        assert_eq!(interrupt.check(), Some(LimitReason::DeadlineExceeded));
+        assert!(!Deadline::after(Duration::MAX).has_passed());
+    }
+}
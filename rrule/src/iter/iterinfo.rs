@@ -62,7 +62,11 @@ impl IterInfo {
 
     pub fn rebuild(&mut self, counter_date: &DateTimeIter) {
         let month = u8::try_from(counter_date.month).expect("range 1-12 is covered by u8");
-        self.rebuild_inner(counter_date.year, month, false);
+        self.rebuild_at(counter_date.year, month);
+    }
+
+    pub fn rebuild_at(&mut self, year: i32, month: u8) {
+        self.rebuild_inner(year, month, false);
     }
 
     pub fn year_len(&self) -> u16 {
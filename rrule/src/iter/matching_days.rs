@@ -0,0 +1,46 @@
+use chrono::{DateTime, Datelike, NaiveDate};
+
+use super::{filters::is_filtered, iterinfo::IterInfo};
+use crate::{Frequency, NWeekday, RRule, Tz};
+
+/// Returns the days from the date of `dt_start` up to and including `last_year`
+/// that pass all the `BYxxx` filters of the rule.
+///
+/// `INTERVAL`, `COUNT`, `UNTIL` and `BYSETPOS` are not taken into account.
+pub(crate) fn matching_days(
+    rrule: &RRule,
+    dt_start: &DateTime<Tz>,
+    last_year: i32,
+) -> impl Iterator<Item = NaiveDate> {
+    let start_year = dt_start.year();
+    let start_day = usize::try_from(dt_start.ordinal0()).expect("ordinal is covered by usize");
+    // The nth weekday masks are built per month for monthly rules.
+    let per_month = rrule.freq == Frequency::Monthly
+        && rrule
+            .by_weekday
+            .iter()
+            .any(|by_weekday| matches!(by_weekday, NWeekday::Nth(_, _)));
+    let last_year = last_year.min(NaiveDate::MAX.year() - 1);
+
+    let mut ii = IterInfo::new(rrule, dt_start);
+    (start_year..=last_year).flat_map(move |year| {
+        let min_day = if year == start_year { start_day } else { 0 };
+        let months = if per_month { 1..=12 } else { 1..=1 };
+        let mut days = vec![];
+        for month in months {
+            ii.rebuild_at(year, month);
+            let dayset = if per_month {
+                ii.month_dayset(u32::from(month))
+            } else {
+                ii.year_dayset()
+            };
+            days.extend(
+                dayset
+                    .into_iter()
+                    .filter(|&day| day >= min_day && !is_filtered(&ii, day))
+                    .filter_map(|day| NaiveDate::from_yo_opt(year, u32::try_from(day).ok()? + 1)),
+            );
+        }
+        days
+    })
+}
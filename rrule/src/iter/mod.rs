@@ -4,8 +4,10 @@ mod checks;
 mod counter_date;
 mod easter;
 pub(crate) mod filters;
+mod interrupt;
 pub(crate) mod iterinfo;
 mod masks;
+mod matching_days;
 mod monthinfo;
 mod operation_errors;
 mod pos_list;
@@ -14,12 +16,10 @@ mod rruleset_iter;
 mod utils;
 mod yearinfo;
 
+pub(crate) use interrupt::Interrupt;
+pub use interrupt::{CancellationToken, Deadline};
 use iterinfo::IterInfo;
+pub(crate) use matching_days::matching_days;
 use pos_list::build_pos_list;
 pub(crate) use rrule_iter::RRuleIter;
 pub use rruleset_iter::RRuleSetIter;
-
-/// Prevent loops when searching for the next event in the iterator.
-/// If after X number of iterations it still has not found an event,
-/// we can assume it will not find an event.
-static MAX_ITER_LOOP: u32 = 100_000;
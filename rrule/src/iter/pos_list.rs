@@ -1,5 +1,6 @@
 use super::utils::{add_time_to_date, date_from_ordinal, pymod};
 use crate::core::Tz;
+use crate::DstPolicy;
 use chrono::NaiveTime;
 
 pub(crate) fn build_pos_list(
@@ -8,6 +9,7 @@ pub(crate) fn build_pos_list(
     timeset: &[NaiveTime],
     year_ordinal: i64,
     tz: Tz,
+    dst_policy: DstPolicy,
 ) -> Vec<chrono::DateTime<Tz>> {
     let mut pos_list = vec![];
 
@@ -49,7 +51,7 @@ pub(crate) fn build_pos_list(
         // Create new Date + Time combination
         // Use Time from `timeset`.
         let time = timeset[time_pos];
-        let res = match add_time_to_date(tz, date, time) {
+        let res = match add_time_to_date(tz, date, time, dst_policy) {
             Some(date) => date,
             None => continue,
         };
@@ -1,8 +1,9 @@
 use super::counter_date::DateTimeIter;
 use super::utils::add_time_to_date;
-use super::{build_pos_list, utils::date_from_ordinal, IterInfo, MAX_ITER_LOOP};
+use super::{build_pos_list, utils::date_from_ordinal, Interrupt, IterInfo};
 use crate::core::{get_hour, get_minute, get_second};
-use crate::{Frequency, RRule, Tz};
+use crate::validator::validate_rrule::YEAR_RANGE;
+use crate::{DstPolicy, Frequency, LimitReason, RRule, Tz, ValidatorLimits};
 use chrono::NaiveTime;
 use std::collections::VecDeque;
 
@@ -21,14 +22,24 @@ pub(crate) struct RRuleIter {
     /// Number of events that should still be generated before the end.
     /// Counter always goes down after each iteration.
     pub(crate) count: Option<u32>,
-    /// If the iterator should be using iterator limits.
-    pub(crate) limited: bool,
-    /// If the iterator has been stopped by the iterator limits.
-    pub(crate) was_limited: bool,
+    /// The iterator limits, if the iterator should be using them.
+    pub(crate) limits: Option<ValidatorLimits>,
+    /// The limit that stopped the iterator.
+    pub(crate) limit_reason: Option<LimitReason>,
+    /// Resolution of occurrences in daylight saving time transitions.
+    pub(crate) dst_policy: DstPolicy,
+    /// Deadline and cancellation token that stop the iterator.
+    pub(crate) interrupt: Interrupt,
 }
 
 impl RRuleIter {
-    pub(crate) fn new(rrule: &RRule, dt_start: &chrono::DateTime<Tz>, limited: bool) -> Self {
+    pub(crate) fn new(
+        rrule: &RRule,
+        dt_start: &chrono::DateTime<Tz>,
+        limits: Option<ValidatorLimits>,
+        dst_policy: DstPolicy,
+        interrupt: Interrupt,
+    ) -> Self {
         let ii = IterInfo::new(rrule, dt_start);
 
         let hour = get_hour(dt_start);
@@ -45,8 +56,10 @@ impl RRuleIter {
             buffer: VecDeque::new(),
             finished: false,
             count,
-            limited,
-            was_limited: false,
+            limits,
+            limit_reason: None,
+            dst_policy,
+            interrupt,
         }
     }
 
@@ -100,16 +113,21 @@ impl RRuleIter {
         let mut loop_counter: u32 = 0;
         // Loop until there is at least 1 item in the buffer.
         while self.buffer.is_empty() {
+            if let Some(reason) = self.interrupt.check() {
+                self.finished = true;
+                self.limit_reason = Some(reason);
+                return true;
+            }
             // Prevent infinite loops
-            if self.limited {
+            if let Some(limits) = &self.limits {
                 loop_counter += 1;
-                if loop_counter >= MAX_ITER_LOOP {
+                if loop_counter >= limits.get_max_iterations() {
                     self.finished = true;
-                    self.was_limited = true;
+                    self.limit_reason = Some(LimitReason::MaxIterations);
                     log::warn!(
                         "Reached max loop counter (`{}`). \
                     See 'validator limits' in docs for more info.",
-                        MAX_ITER_LOOP
+                        limits.get_max_iterations()
                     );
                     return true;
                 }
@@ -136,7 +154,7 @@ impl RRuleIter {
                     // just below we'll end up double-applying.
                     let date = date_from_ordinal(year_ordinal + current_day);
                     for time in &self.timeset {
-                        let Some(dt) = add_time_to_date(tz, date, *time) else {
+                        let Some(dt) = add_time_to_date(tz, date, *time, self.dst_policy) else {
                             continue;
                         };
                         if Self::try_add_datetime(
@@ -157,6 +175,7 @@ impl RRuleIter {
                     &self.timeset,
                     self.ii.year_ordinal(),
                     self.dt_start.timezone(),
+                    self.dst_policy,
                 );
                 for dt in pos_list {
                     if Self::try_add_datetime(
@@ -176,6 +195,21 @@ impl RRuleIter {
                 self.finished = true;
                 return true;
             }
+            match &self.limits {
+                Some(limits) => {
+                    if !limits.get_year_range().contains(&self.counter_date.year) {
+                        self.finished = true;
+                        self.limit_reason = Some(LimitReason::YearRange);
+                        return true;
+                    }
+                }
+                None => {
+                    if !YEAR_RANGE.contains(&self.counter_date.year) {
+                        self.finished = true;
+                        return true;
+                    }
+                }
+            }
 
             if matches!(
                 rrule.freq,
@@ -220,11 +254,11 @@ impl Iterator for RRuleIter {
 }
 
 pub(crate) trait WasLimited {
-    fn was_limited(&self) -> bool;
+    fn limit_reason(&self) -> Option<LimitReason>;
 }
 
 impl WasLimited for RRuleIter {
-    fn was_limited(&self) -> bool {
-        self.was_limited
+    fn limit_reason(&self) -> Option<LimitReason> {
+        self.limit_reason
     }
 }
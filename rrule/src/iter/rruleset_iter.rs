@@ -1,9 +1,9 @@
 use chrono::DateTime;
 
 use super::rrule_iter::WasLimited;
-use super::{rrule_iter::RRuleIter, MAX_ITER_LOOP};
+use super::{rrule_iter::RRuleIter, Interrupt};
 use crate::RRuleSet;
-use crate::{RRuleError, Tz};
+use crate::{LimitReason, RRuleError, Tz, ValidatorLimits};
 use std::collections::BTreeSet;
 use std::str::FromStr;
 use std::{collections::HashMap, iter::Iterator};
@@ -12,21 +12,32 @@ use std::{collections::HashMap, iter::Iterator};
 /// Iterator over all the dates in an [`RRuleSet`].
 pub struct RRuleSetIter {
     queue: HashMap<usize, DateTime<Tz>>,
-    limited: bool,
+    limits: Option<ValidatorLimits>,
     rrule_iters: Vec<RRuleIter>,
     exrules: Vec<RRuleIter>,
     exdates: BTreeSet<i64>,
     /// Sorted additional dates in descending order
     rdates: Vec<DateTime<Tz>>,
-    was_limited: bool,
+    limit_reason: Option<LimitReason>,
+    /// Deadline and cancellation token that stop the iterator.
+    interrupt: Interrupt,
 }
 
 impl RRuleSetIter {
+    /// Returns the limit that stopped the iterator, if any.
+    ///
+    /// Limits are only enforced when they are enabled on the [`RRuleSet`].
+    #[must_use]
+    pub fn limit_reason(&self) -> Option<LimitReason> {
+        self.limit_reason
+            .or_else(|| self.rrule_iters.iter().find_map(WasLimited::limit_reason))
+    }
+
     fn generate_date(
         dates: &mut Vec<DateTime<Tz>>,
         exrules: &mut [RRuleIter],
         exdates: &mut BTreeSet<i64>,
-        limited: bool,
+        limits: Option<&ValidatorLimits>,
     ) -> (Option<DateTime<Tz>>, bool) {
         if dates.is_empty() {
             return (None, false);
@@ -39,13 +50,13 @@ impl RRuleSetIter {
                 return (None, false);
             }
             // Prevent infinite loops
-            if limited {
+            if let Some(limits) = limits {
                 loop_counter += 1;
-                if loop_counter >= MAX_ITER_LOOP {
+                if loop_counter >= limits.get_max_iterations() {
                     log::warn!(
                         "Reached max loop counter (`{}`). \
                 See 'validator limits' in docs for more info.",
-                        MAX_ITER_LOOP
+                        limits.get_max_iterations()
                     );
                     return (None, true);
                 }
@@ -60,34 +71,38 @@ impl RRuleSetIter {
         rrule_iter: &mut RRuleIter,
         exrules: &mut [RRuleIter],
         exdates: &mut BTreeSet<i64>,
-        limited: bool,
-    ) -> (Option<DateTime<Tz>>, bool) {
+        limits: Option<&ValidatorLimits>,
+        interrupt: &Interrupt,
+    ) -> (Option<DateTime<Tz>>, Option<LimitReason>) {
         let mut date = match rrule_iter.next() {
             Some(d) => d,
-            None => return (None, false),
+            None => return (None, None),
         };
         let mut loop_counter: u32 = 0;
         while Self::is_date_excluded(&date, exrules, exdates) {
+            if let Some(reason) = interrupt.check() {
+                return (None, Some(reason));
+            }
             // Prevent infinite loops
-            if limited {
+            if let Some(limits) = limits {
                 loop_counter += 1;
-                if loop_counter >= MAX_ITER_LOOP {
+                if loop_counter >= limits.get_max_iterations() {
                     log::warn!(
                         "Reached max loop counter (`{}`). \
                     See 'validator limits' in docs for more info.",
-                        MAX_ITER_LOOP
+                        limits.get_max_iterations()
                     );
-                    return (None, true);
+                    return (None, Some(LimitReason::MaxIterations));
                 }
             }
 
             date = match rrule_iter.next() {
                 Some(d) => d,
-                None => return (None, false),
+                None => return (None, None),
             };
         }
 
-        (Some(date), false)
+        (Some(date), None)
     }
 
     fn is_date_excluded(
@@ -115,7 +130,11 @@ impl Iterator for RRuleSetIter {
         let mut next_date: Option<(usize, DateTime<Tz>)> = None;
 
         // If there already was an error, return the error again.
-        if self.was_limited {
+        if self.limit_reason.is_some() {
+            return None;
+        }
+        if let Some(reason) = self.interrupt.check() {
+            self.limit_reason = Some(reason);
             return None;
         }
 
@@ -125,15 +144,16 @@ impl Iterator for RRuleSetIter {
                 Some(d)
             } else {
                 // should be method on self
-                let (date, was_limited) = Self::generate(
+                let (date, limit_reason) = Self::generate(
                     rrule_iter,
                     &mut self.exrules,
                     &mut self.exdates,
-                    self.limited,
+                    self.limits.as_ref(),
+                    &self.interrupt,
                 );
 
-                if was_limited {
-                    self.was_limited = true;
+                if limit_reason.is_some() {
+                    self.limit_reason = limit_reason;
                     return None;
                 }
 
@@ -163,10 +183,10 @@ impl Iterator for RRuleSetIter {
             &mut self.rdates,
             &mut self.exrules,
             &mut self.exdates,
-            self.limited,
+            self.limits.as_ref(),
         );
         if was_limited {
-            self.was_limited = true;
+            self.limit_reason = Some(LimitReason::MaxIterations);
             return None;
         }
 
@@ -206,31 +226,46 @@ impl IntoIterator for &RRuleSet {
         rdates_sorted
             .sort_by(|d1, d2| d2.partial_cmp(d1).expect("Could not order dates correctly"));
 
-        let limited = self.limited;
+        let limits = self.limited.then_some(self.limits);
 
         RRuleSetIter {
             queue: HashMap::new(),
-            limited,
+            limits,
             rrule_iters: self
                 .rrule
                 .iter()
-                .map(|rrule| rrule.iter_with_ctx(self.dt_start, limited))
+                .map(|rrule| {
+                    rrule.iter_with_ctx(
+                        self.dt_start,
+                        limits,
+                        self.dst_policy,
+                        self.interrupt.clone(),
+                    )
+                })
                 .collect(),
             rdates: rdates_sorted,
             exrules: self
                 .exrule
                 .iter()
-                .map(|exrule| exrule.iter_with_ctx(self.dt_start, limited))
+                .map(|exrule| {
+                    exrule.iter_with_ctx(
+                        self.dt_start,
+                        limits,
+                        self.dst_policy,
+                        self.interrupt.clone(),
+                    )
+                })
                 .collect(),
             exdates: self.exdate.iter().map(DateTime::timestamp).collect(),
-            was_limited: false,
+            limit_reason: None,
+            interrupt: self.interrupt.clone(),
         }
     }
 }
 
 impl WasLimited for RRuleSetIter {
-    fn was_limited(&self) -> bool {
-        self.was_limited
+    fn limit_reason(&self) -> Option<LimitReason> {
+        Self::limit_reason(self)
     }
 }
 
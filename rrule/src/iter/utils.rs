@@ -1,6 +1,7 @@
 use std::ops;
 
-use crate::core::{duration_from_midnight, Tz};
+use crate::core::Tz;
+use crate::DstPolicy;
 use chrono::{NaiveDate, NaiveTime, Utc};
 
 const DAY_SECS: i64 = 24 * 60 * 60;
@@ -8,7 +9,7 @@ const DAY_SECS: i64 = 24 * 60 * 60;
 /// Converts number of days since unix epoch to a (naive) date.
 pub(crate) fn date_from_ordinal(ordinal: i64) -> NaiveDate {
     chrono::DateTime::<Utc>::from_timestamp(ordinal * DAY_SECS, 0)
-        .unwrap()
+        .expect("ordinal is within the validated year range, which chrono can represent")
         .date_naive()
 }
 
@@ -72,26 +73,19 @@ where
     }
 }
 
+/// Combines a date and time in `tz`. Times in a daylight saving time
+/// transition are resolved with `dst_policy`.
 pub(crate) fn add_time_to_date(
     tz: Tz,
     date: NaiveDate,
     time: NaiveTime,
+    dst_policy: DstPolicy,
 ) -> Option<chrono::DateTime<Tz>> {
-    if let Some(dt) = date.and_time(time).and_local_timezone(tz).single() {
-        return Some(dt);
-    }
-    // If the day is a daylight saving time, the above code might not work, and we
-    // can try to get a valid datetime by adding the `time` as a duration instead.
-    let dt = date.and_hms_opt(0, 0, 0)?.and_local_timezone(tz).single()?;
-    let day_duration = duration_from_midnight(time);
-    dt.checked_add_signed(day_duration)
+    dst_policy.resolve_occurrence(tz, &date.and_time(time))
 }
 
 #[cfg(test)]
 mod test {
-
-    use chrono::{Duration, TimeZone};
-
     use super::*;
 
     #[test]
@@ -155,8 +149,11 @@ mod test {
         }
     }
 
+    #[cfg(not(tz_subset))]
     #[test]
     fn adds_time_to_date() {
+        use chrono::{Duration, TimeZone};
+
         let tests = [
             (
                 Tz::UTC,
@@ -190,7 +187,7 @@ mod test {
         ];
 
         for (tz, date, time, expected_output) in tests {
-            let res = add_time_to_date(tz, date, time);
+            let res = add_time_to_date(tz, date, time, DstPolicy::default());
             assert_eq!(res, expected_output);
         }
     }
@@ -107,8 +107,13 @@ mod validator;
 #[cfg(feature = "wasm")]
 mod wasm;
 
-pub use crate::core::{Frequency, NWeekday, RRule, RRuleResult, RRuleSet, Tz};
+pub use crate::core::{
+    CostEstimate, DstPolicy, Frequency, GapPolicy, NWeekday, OverlapPolicy, RRule, RRuleResult,
+    RRuleSet, Tz,
+};
 pub use crate::core::{Unvalidated, Validated};
 pub use chrono::Weekday;
-pub use error::{ParseError, RRuleError, ValidationError};
-pub use iter::RRuleSetIter;
+pub use error::{Diagnostic, LocatedError, ParseError, RRuleError, Severity, ValidationError};
+pub use iter::{CancellationToken, Deadline, RRuleSetIter};
+pub use parser::{Span, TzidMapping};
+pub use validator::{LimitReason, ValidatorLimits};
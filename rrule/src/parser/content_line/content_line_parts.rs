@@ -1,4 +1,4 @@
-use crate::parser::{regex::get_property_name, ParseError};
+use crate::parser::{regex::get_property_name, utils::find_unquoted, ParseError};
 
 use super::PropertyName;
 
@@ -11,33 +11,32 @@ pub(crate) struct ContentLineCaptures<'a> {
 
 impl<'a> ContentLineCaptures<'a> {
     pub(crate) fn new(line: &'a str) -> Result<Self, ParseError> {
-        // Default property name to RRULE.
-        let property_name = get_property_name(line)?.unwrap_or(PropertyName::RRule);
-        match property_name {
+        // The value starts after the first ':' which is not part of a quoted parameter value.
+        let colon_idx = find_unquoted(line, ':');
+        match get_property_name(line)? {
             // If the line did not contain a property name (i.e. no ':'), then the
             // entire line is interpreted as the value
-            PropertyName::RRule if !line.contains(':') => Ok(ContentLineCaptures {
+            None if colon_idx.is_none() => Ok(ContentLineCaptures {
                 property_name: PropertyName::RRule,
                 parameters: None,
                 value: line,
             }),
             property_name => {
-                let mut parameters = None;
-                if line.starts_with(&format!("{};", property_name)) {
-                    let only_colon_idx = line.find(':');
-                    if let Some(only_colon_idx) = only_colon_idx {
-                        parameters =
-                            Some(&line[property_name.to_string().len() + 1..only_colon_idx]);
-                    }
-                }
+                let (head, value) = match colon_idx {
+                    Some(colon_idx) => (&line[..colon_idx], &line[colon_idx + 1..]),
+                    None => (line, ""),
+                };
+                // Parameters follow the property name, after a ';'.
+                let parameters = property_name
+                    .is_some()
+                    .then(|| head.split_once(';').map(|(_name, parameters)| parameters))
+                    .flatten();
 
                 Ok(Self {
-                    property_name,
+                    // Default property name to RRULE.
+                    property_name: property_name.unwrap_or(PropertyName::RRule),
                     parameters,
-                    value: line
-                        .split_once(':')
-                        .map(|(_name, val)| val)
-                        .unwrap_or_default(),
+                    value,
                 })
             }
         }
@@ -108,6 +107,22 @@ mod tests {
                     value: "FREQ=DAILY;COUNT=10",
                 },
             ),
+            (
+                r#"RDATE;X-APPLE-LOC="geo:1,2;3":20210301T090000Z"#,
+                ContentLineCaptures {
+                    property_name: PropertyName::RDate,
+                    parameters: Some(r#"X-APPLE-LOC="geo:1,2;3""#),
+                    value: "20210301T090000Z",
+                },
+            ),
+            (
+                r#"X-NOTE;X-A="b:c":some text"#,
+                ContentLineCaptures {
+                    property_name: PropertyName::Extension("X-NOTE".into()),
+                    parameters: Some(r#"X-A="b:c""#),
+                    value: "some text",
+                },
+            ),
         ];
         for (input, expected_output) in tests {
             let output = ContentLineCaptures::new(input);
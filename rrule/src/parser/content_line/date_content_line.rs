@@ -5,17 +5,23 @@ use log::warn;
 use crate::{
     parser::{
         datetime::{datestring_to_date, parse_timezone},
-        ParseError,
+        utils::is_extension_name,
+        ParseError, ParseMode, ParseOptions,
     },
     Tz,
 };
 
-use super::{content_line_parts::ContentLineCaptures, parameters::parse_parameters};
+use super::{
+    content_line_parts::ContentLineCaptures,
+    parameters::{parse_extension_parameters, parse_parameters},
+};
 
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub enum DateParameter {
     Timezone,
     Value,
+    /// An experimental parameter, like `X-NAME`, which is kept as is.
+    Extension(String),
 }
 
 impl FromStr for DateParameter {
@@ -25,27 +31,43 @@ impl FromStr for DateParameter {
         let param = match &s.to_uppercase()[..] {
             "TZID" => Self::Timezone,
             "VALUE" => Self::Value,
+            _ if is_extension_name(s) => Self::Extension(s.into()),
             _ => return Err(ParseError::UnrecognizedParameter(s.into())),
         };
         Ok(param)
     }
 }
 
-impl<'a> TryFrom<ContentLineCaptures<'a>> for Vec<chrono::DateTime<Tz>> {
+/// The dates of an `RDATE` or `EXDATE` property.
+#[derive(Debug, PartialEq)]
+pub(crate) struct DateContentLine {
+    pub dates: Vec<chrono::DateTime<Tz>>,
+    /// Whether the dates were given with `VALUE=DATE`.
+    pub date_only: bool,
+    /// The experimental parameters, like `X-NAME=value`.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl<'a> TryFrom<ContentLineCaptures<'a>> for DateContentLine {
     type Error = ParseError;
 
     fn try_from(value: ContentLineCaptures) -> Result<Self, Self::Error> {
+        Self::parse(&value, &ParseOptions::default())
+    }
+}
+
+impl DateContentLine {
+    pub fn parse(value: &ContentLineCaptures, options: &ParseOptions) -> Result<Self, ParseError> {
         let parameters: HashMap<DateParameter, String> = value
             .parameters
             .map(parse_parameters)
             .transpose()?
             .unwrap_or_default();
 
-        match parameters
+        let value_type = parameters
             .get(&DateParameter::Value)
-            .map(|val| val.to_ascii_lowercase())
-            .as_deref()
-        {
+            .map(|val| val.to_ascii_lowercase());
+        match value_type.as_deref() {
             Some("date") => {
                 warn!(
                     "Parameter `DATE` is not supported for property name: `{}`. The dates will be interpreter with the `DATE-TIME` parameter instead.",
@@ -79,11 +101,28 @@ impl<'a> TryFrom<ContentLineCaptures<'a>> for Vec<chrono::DateTime<Tz>> {
             if val.is_empty() {
                 continue;
             }
-            let datetime = datestring_to_date(val, timezone, &property)?;
+            // In lenient mode a period is repaired to its start.
+            let val = match val.split_once('/') {
+                Some((start, _))
+                    if options.mode == ParseMode::Lenient
+                        && value_type.as_deref() == Some("period") =>
+                {
+                    start
+                }
+                _ => val,
+            };
+            let datetime = datestring_to_date(val, timezone, &property, options)?;
             dates.push(datetime);
         }
 
-        Ok(dates)
+        Ok(Self {
+            dates,
+            date_only: value_type.as_deref() == Some("date"),
+            extensions: value
+                .parameters
+                .map(parse_extension_parameters)
+                .unwrap_or_default(),
+        })
     }
 }
 
@@ -106,7 +145,11 @@ mod tests {
                     parameters: None,
                     value: "19970714T123000Z",
                 },
-                vec![UTC.with_ymd_and_hms(1997, 7, 14, 12, 30, 0).unwrap()],
+                DateContentLine {
+                    dates: vec![UTC.with_ymd_and_hms(1997, 7, 14, 12, 30, 0).unwrap()],
+                    date_only: false,
+                    extensions: vec![],
+                },
             ),
             (
                 ContentLineCaptures {
@@ -114,7 +157,11 @@ mod tests {
                     parameters: None,
                     value: "19970714T123000",
                 },
-                vec![Tz::LOCAL.with_ymd_and_hms(1997, 7, 14, 12, 30, 0).unwrap()],
+                DateContentLine {
+                    dates: vec![Tz::LOCAL.with_ymd_and_hms(1997, 7, 14, 12, 30, 0).unwrap()],
+                    date_only: false,
+                    extensions: vec![],
+                },
             ),
             (
                 ContentLineCaptures {
@@ -122,12 +169,16 @@ mod tests {
                     parameters: Some("VALUE=DATE;TZID=UTC"),
                     value: "19970101,19970120,19970217,19970421",
                 },
-                vec![
-                    UTC.with_ymd_and_hms(1997, 1, 1, 0, 0, 0).unwrap(),
-                    UTC.with_ymd_and_hms(1997, 1, 20, 0, 0, 0).unwrap(),
-                    UTC.with_ymd_and_hms(1997, 2, 17, 0, 0, 0).unwrap(),
-                    UTC.with_ymd_and_hms(1997, 4, 21, 0, 0, 0).unwrap(),
-                ],
+                DateContentLine {
+                    dates: vec![
+                        UTC.with_ymd_and_hms(1997, 1, 1, 0, 0, 0).unwrap(),
+                        UTC.with_ymd_and_hms(1997, 1, 20, 0, 0, 0).unwrap(),
+                        UTC.with_ymd_and_hms(1997, 2, 17, 0, 0, 0).unwrap(),
+                        UTC.with_ymd_and_hms(1997, 4, 21, 0, 0, 0).unwrap(),
+                    ],
+                    date_only: true,
+                    extensions: vec![],
+                },
             ),
         ];
 
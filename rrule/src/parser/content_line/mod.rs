@@ -8,39 +8,44 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 use crate::RRule;
-use crate::Tz;
 use crate::Unvalidated;
 
 pub(crate) use content_line_parts::ContentLineCaptures;
+pub(crate) use date_content_line::DateContentLine;
 pub(crate) use start_date_content_line::StartDateContentLine;
 
-use super::ParseError;
+use super::{utils::is_extension_name, ParseError};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ContentLine {
     RRule(RRule<Unvalidated>),
     ExRule(RRule<Unvalidated>),
-    ExDate(Vec<chrono::DateTime<Tz>>),
-    RDate(Vec<chrono::DateTime<Tz>>),
+    ExDate(DateContentLine),
+    RDate(DateContentLine),
+    /// An experimental property, with its name and parameters, and its value.
+    Extension(String, String),
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub(crate) enum PropertyName {
     RRule,
     ExRule,
     ExDate,
     RDate,
     DtStart,
+    /// An experimental property, like `X-NAME`, with its name as written.
+    Extension(String),
 }
 
 impl Display for PropertyName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
+        match self {
             Self::RRule => write!(f, "RRULE"),
             Self::ExRule => write!(f, "EXRULE"),
             Self::ExDate => write!(f, "EXDATE"),
             Self::RDate => write!(f, "RDATE"),
             Self::DtStart => write!(f, "DTSTART"),
+            Self::Extension(name) => write!(f, "{name}"),
         }
     }
 }
@@ -55,6 +60,7 @@ impl FromStr for PropertyName {
             "RDATE" => Self::RDate,
             "EXDATE" => Self::ExDate,
             "DTSTART" => Self::DtStart,
+            _ if is_extension_name(s) => Self::Extension(s.into()),
             _ => return Err(ParseError::UnrecognizedPropertyName(s.into())),
         };
         Ok(name)
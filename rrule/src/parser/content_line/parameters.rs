@@ -1,14 +1,18 @@
 use std::{collections::HashMap, hash::Hash, str::FromStr};
 
-use crate::parser::ParseError;
+use crate::parser::{
+    utils::{is_extension_name, split_unquoted, unquote},
+    ParseError,
+};
 
 /// Parses a string of semicolon seperated key value pairs into a `HashMap` with
-/// predefined keys. It will return an error if duplicate keys are found.
+/// predefined keys. Quoted values may contain `;`, `:` and `,`, and are returned
+/// without their quotes.
 pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
     raw_parameters: &str,
 ) -> Result<HashMap<K, String>, ParseError> {
     let mut parameters = HashMap::new();
-    for raw_parameter in raw_parameters.split(';') {
+    for raw_parameter in split_unquoted(raw_parameters, ';') {
         if raw_parameter.is_empty() {
             continue;
         }
@@ -17,7 +21,7 @@ pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
             .ok_or_else(|| ParseError::InvalidParameterFormat(raw_parameter.into()))?;
         let parameter = K::from_str(raw_parameter)?;
 
-        parameters.insert(parameter, value.into());
+        parameters.insert(parameter, unquote(value).into());
 
         // if parameters.insert(parameter, value.into()).is_some() {
         //     return Err(ParseError::DuplicateProperty(raw_parameter.into()));
@@ -26,6 +30,17 @@ pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
     Ok(parameters)
 }
 
+/// Returns the experimental parameters, like `X-NAME=value`, of a string of
+/// semicolon seperated key value pairs, in the order they appear in. Quoted
+/// values keep their quotes, so that they are formatted the same way.
+pub(super) fn parse_extension_parameters(raw_parameters: &str) -> Vec<(String, String)> {
+    split_unquoted(raw_parameters, ';')
+        .filter_map(|raw_parameter| raw_parameter.split_once('='))
+        .filter(|(name, _)| is_extension_name(name))
+        .map(|(name, value)| (name.into(), value.into()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::date_content_line::DateParameter;
@@ -94,6 +109,31 @@ mod tests {
     //     }
     // }
 
+    #[test]
+    fn parses_extension_parameters_in_order() {
+        let input = "X-B=2;TZID=Europe/London;x-a=1";
+        let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+        assert_eq!(output.map(|parameters| parameters.len()), Ok(3));
+        assert_eq!(
+            parse_extension_parameters(input),
+            vec![("X-B".into(), "2".into()), ("x-a".into(), "1".into())]
+        );
+    }
+
+    #[test]
+    fn parses_quoted_parameter_values() {
+        let input = r#"X-LOC="geo:1,2;3";TZID="Europe/London""#;
+        let output: Result<HashMap<DateParameter, String>, _> = parse_parameters(input);
+        assert_eq!(
+            output.map(|parameters| parameters[&DateParameter::Timezone].clone()),
+            Ok("Europe/London".into())
+        );
+        assert_eq!(
+            parse_extension_parameters(input),
+            vec![("X-LOC".into(), r#""geo:1,2;3""#.into())]
+        );
+    }
+
     #[test]
     fn does_not_attempt_to_parse_empty_parameters() {
         let tests = [
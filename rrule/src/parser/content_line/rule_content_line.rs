@@ -3,19 +3,20 @@ use std::{collections::HashMap, marker::PhantomData, str::FromStr};
 use chrono::Weekday;
 
 use crate::{
+    core::FilledIn,
     parser::{
-        content_line::parameters::parse_parameters,
+        content_line::parameters::{parse_extension_parameters, parse_parameters},
         datetime::{datestring_to_date, parse_weekdays},
         str_to_weekday,
-        utils::parse_str_to_vec,
-        ParseError,
+        utils::{is_extension_name, parse_str_to_vec},
+        ParseError, ParseOptions,
     },
     Frequency, RRule, Unvalidated,
 };
 
 use super::content_line_parts::ContentLineCaptures;
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub enum RRuleProperty {
     Freq,
     Until,
@@ -33,6 +34,8 @@ pub enum RRuleProperty {
     Wkst,
     #[cfg(feature = "by-easter")]
     ByEaster,
+    /// An experimental rule part, like `X-NAME`, which is kept as is.
+    Extension(String),
 }
 
 impl FromStr for RRuleProperty {
@@ -56,6 +59,7 @@ impl FromStr for RRuleProperty {
             "WKST" => Self::Wkst,
             #[cfg(feature = "by-easter")]
             "BYEASTER" => Self::ByEaster,
+            _ if is_extension_name(s) => Self::Extension(s.into()),
             _ => return Err(ParseError::UnrecognizedParameter(s.into())),
         };
         Ok(prop)
@@ -74,133 +78,212 @@ impl<'a> TryFrom<ContentLineCaptures<'a>> for RRule<Unvalidated> {
         //     }
         // }
 
-        let properties: HashMap<RRuleProperty, String> = parse_parameters(value.value)?;
+        Self::parse(&value, &ParseOptions::default())
+    }
+}
 
-        props_to_rrule(&properties)
+impl RRule<Unvalidated> {
+    pub(crate) fn parse(
+        value: &ContentLineCaptures,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        Self::parse_all(value, options).map_err(|mut errors| errors.swap_remove(0))
     }
+
+    /// Same as [`RRule::parse`], but returns the errors of every rule part
+    /// instead of stopping at the first one. The returned list is never empty.
+    pub(crate) fn parse_all(
+        value: &ContentLineCaptures,
+        options: &ParseOptions,
+    ) -> Result<Self, Vec<ParseError>> {
+        let properties: HashMap<RRuleProperty, String> =
+            parse_parameters(value.value).map_err(|err| vec![err])?;
+
+        let mut rrule = props_to_rrule(&properties, options)?;
+        rrule.extensions = parse_extension_parameters(value.value);
+        Ok(rrule)
+    }
+}
+
+/// Returns the parsed value of an optional rule part, or records its error.
+fn or_record<T>(errors: &mut Vec<ParseError>, value: Result<Option<T>, ParseError>) -> Option<T> {
+    value.unwrap_or_else(|err| {
+        errors.push(err);
+        None
+    })
 }
 
-/// Takes a map of [`RRuleProperty`] and returns an [`RRule`].
+/// Takes a map of [`RRuleProperty`] and returns an [`RRule`], or the errors
+/// of every rule part that failed to parse.
 #[allow(clippy::too_many_lines)]
 fn props_to_rrule(
     props: &HashMap<RRuleProperty, String>,
-) -> Result<RRule<Unvalidated>, ParseError> {
-    let freq = props
-        .get(&RRuleProperty::Freq)
-        .map(|freq| Frequency::from_str(freq))
-        .transpose()?
-        .ok_or_else(|| ParseError::MissingProperty("FREQ".into()))?;
-    let interval = props
-        .get(&RRuleProperty::Interval)
-        .map(|interval| {
-            interval
-                .parse()
-                .map_err(|_| ParseError::InvalidInterval(interval.into()))
-        })
-        .transpose()?
-        .unwrap_or(1);
-    let count = props
-        .get(&RRuleProperty::Count)
-        .map(|count| {
-            count
-                .parse()
-                .map_err(|_| ParseError::InvalidCount(count.into()))
-        })
-        .transpose()?;
-    let until = props
-        .get(&RRuleProperty::Until)
-        .map(|until| datestring_to_date(until, None, "UNTIL"))
-        .transpose()?;
-    let week_start = props
-        .get(&RRuleProperty::Wkst)
-        .map(|week_start| {
-            str_to_weekday(week_start)
-                .map_err(|_| ParseError::InvalidWeekdayStart(week_start.into()))
-        })
-        .transpose()?
-        .unwrap_or(Weekday::Mon);
-    let by_set_pos = props
-        .get(&RRuleProperty::BySetPos)
-        .map(|by_set_pos| {
-            parse_str_to_vec(by_set_pos, |_| true)
-                .map_err(|_| ParseError::InvalidBySetPos(by_set_pos.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_month = props
-        .get(&RRuleProperty::ByMonth)
-        .map(|by_month| {
-            parse_str_to_vec(by_month, |month| (1..=12).contains(&month))
-                .map_err(|_| ParseError::InvalidByMonth(by_month.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_month_day = props
-        .get(&RRuleProperty::ByMonthDay)
-        .map(|by_month_day| {
-            parse_str_to_vec(by_month_day, |monthday| (-31..=31).contains(&monthday))
-                .map_err(|_| ParseError::InvalidByMonthDay(by_month_day.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_year_day = props
-        .get(&RRuleProperty::ByYearDay)
-        .map(|by_year_day| {
-            parse_str_to_vec(by_year_day, |yearday| (-366..=366).contains(&yearday))
-                .map_err(|_| ParseError::InvalidByYearDay(by_year_day.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_week_no = props
-        .get(&RRuleProperty::ByWeekNo)
-        .map(|by_week_no| {
-            parse_str_to_vec(by_week_no, |weekno| (-53..=53).contains(&weekno))
-                .map_err(|_| ParseError::InvalidByWeekNo(by_week_no.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_weekday = props
-        .get(&RRuleProperty::ByDay)
-        .map(|by_weekday| parse_weekdays(by_weekday))
-        .transpose()?
-        .unwrap_or_default();
-    let by_hour = props
-        .get(&RRuleProperty::ByHour)
-        .map(|by_hour| {
-            parse_str_to_vec(by_hour, |hour| hour < 24)
-                .map_err(|_| ParseError::InvalidByHour(by_hour.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_minute = props
-        .get(&RRuleProperty::ByMinute)
-        .map(|by_minute| {
-            parse_str_to_vec(by_minute, |minute| minute < 60)
-                .map_err(|_| ParseError::InvalidByMinute(by_minute.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
-    let by_second = props
-        .get(&RRuleProperty::BySecond)
-        .map(|by_second| {
-            parse_str_to_vec(by_second, |second| second < 60)
-                .map_err(|_| ParseError::InvalidBySecond(by_second.into()))
-        })
-        .transpose()?
-        .unwrap_or_default();
+    options: &ParseOptions,
+) -> Result<RRule<Unvalidated>, Vec<ParseError>> {
+    let mut errors = vec![];
+    let freq = match props.get(&RRuleProperty::Freq) {
+        Some(freq) => or_record(&mut errors, Frequency::from_str(freq).map(Some)),
+        None => {
+            errors.push(ParseError::MissingProperty("FREQ".into()));
+            None
+        }
+    };
+    let interval = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::Interval)
+            .map(|interval| {
+                interval
+                    .parse()
+                    .map_err(|_| ParseError::InvalidInterval(interval.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or(1);
+    let count = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::Count)
+            .map(|count| {
+                count
+                    .parse()
+                    .map_err(|_| ParseError::InvalidCount(count.into()))
+            })
+            .transpose(),
+    );
+    let until = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::Until)
+            .map(|until| datestring_to_date(until, None, "UNTIL", options))
+            .transpose(),
+    );
+    let week_start = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::Wkst)
+            .map(|week_start| {
+                str_to_weekday(week_start)
+                    .map_err(|_| ParseError::InvalidWeekdayStart(week_start.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or(Weekday::Mon);
+    let by_set_pos = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::BySetPos)
+            .map(|by_set_pos| {
+                parse_str_to_vec(by_set_pos, |_| true)
+                    .map_err(|_| ParseError::InvalidBySetPos(by_set_pos.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_month = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByMonth)
+            .map(|by_month| {
+                parse_str_to_vec(by_month, |month| (1..=12).contains(&month))
+                    .map_err(|_| ParseError::InvalidByMonth(by_month.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_month_day = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByMonthDay)
+            .map(|by_month_day| {
+                parse_str_to_vec(by_month_day, |monthday| (-31..=31).contains(&monthday))
+                    .map_err(|_| ParseError::InvalidByMonthDay(by_month_day.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_year_day = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByYearDay)
+            .map(|by_year_day| {
+                parse_str_to_vec(by_year_day, |yearday| (-366..=366).contains(&yearday))
+                    .map_err(|_| ParseError::InvalidByYearDay(by_year_day.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_week_no = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByWeekNo)
+            .map(|by_week_no| {
+                parse_str_to_vec(by_week_no, |weekno| (-53..=53).contains(&weekno))
+                    .map_err(|_| ParseError::InvalidByWeekNo(by_week_no.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_weekday = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByDay)
+            .map(|by_weekday| parse_weekdays(by_weekday))
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_hour = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByHour)
+            .map(|by_hour| {
+                parse_str_to_vec(by_hour, |hour| hour < 24)
+                    .map_err(|_| ParseError::InvalidByHour(by_hour.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_minute = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByMinute)
+            .map(|by_minute| {
+                parse_str_to_vec(by_minute, |minute| minute < 60)
+                    .map_err(|_| ParseError::InvalidByMinute(by_minute.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
+    let by_second = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::BySecond)
+            .map(|by_second| {
+                parse_str_to_vec(by_second, |second| second < 60)
+                    .map_err(|_| ParseError::InvalidBySecond(by_second.into()))
+            })
+            .transpose(),
+    )
+    .unwrap_or_default();
 
     #[cfg(not(feature = "by-easter"))]
     let by_easter = None;
     #[cfg(feature = "by-easter")]
-    let by_easter = props
-        .get(&RRuleProperty::ByEaster)
-        .map(|new_by_easter: &String| {
-            i16::from_str(new_by_easter)
-                .map_err(|_| ParseError::InvalidByEaster(new_by_easter.into()))
-        })
-        .transpose()?;
+    let by_easter = or_record(
+        &mut errors,
+        props
+            .get(&RRuleProperty::ByEaster)
+            .map(|new_by_easter: &String| {
+                i16::from_str(new_by_easter)
+                    .map_err(|_| ParseError::InvalidByEaster(new_by_easter.into()))
+            })
+            .transpose(),
+    );
 
     // Check if mandatory fields are set
+    let Some(freq) = freq.filter(|_| errors.is_empty()) else {
+        return Err(errors);
+    };
     Ok(RRule {
         freq,
         interval,
@@ -218,6 +301,8 @@ fn props_to_rrule(
         by_minute,
         by_second,
         by_easter,
+        extensions: vec![],
+        filled_in: FilledIn::default(),
         stage: PhantomData,
     })
 }
@@ -296,10 +381,10 @@ mod tests {
     fn rejects_invalid_freq() {
         let mut props = HashMap::new();
         props.insert(RRuleProperty::Freq, "DAIL".into());
-        let res = props_to_rrule(&props);
+        let res = props_to_rrule(&props, &ParseOptions::default());
         assert_eq!(
             res.unwrap_err(),
-            ParseError::InvalidFrequency("DAIL".into())
+            vec![ParseError::InvalidFrequency("DAIL".into())]
         );
     }
 
@@ -308,12 +393,18 @@ mod tests {
         let mut props = HashMap::new();
         props.insert(RRuleProperty::Freq, "DAILY".into());
         props.insert(RRuleProperty::ByHour, "24".into());
-        let res = props_to_rrule(&props);
-        assert_eq!(res.unwrap_err(), ParseError::InvalidByHour("24".into()));
+        let res = props_to_rrule(&props, &ParseOptions::default());
+        assert_eq!(
+            res.unwrap_err(),
+            vec![ParseError::InvalidByHour("24".into())]
+        );
 
         props.insert(RRuleProperty::ByHour, "5,6,25".into());
-        let res = props_to_rrule(&props);
-        assert_eq!(res.unwrap_err(), ParseError::InvalidByHour("5,6,25".into()));
+        let res = props_to_rrule(&props, &ParseOptions::default());
+        assert_eq!(
+            res.unwrap_err(),
+            vec![ParseError::InvalidByHour("5,6,25".into())]
+        );
     }
 
     #[test]
@@ -321,14 +412,34 @@ mod tests {
         let mut props = HashMap::new();
         props.insert(RRuleProperty::Freq, "DAILY".into());
         props.insert(RRuleProperty::ByMinute, "60".into());
-        let res = props_to_rrule(&props);
-        assert_eq!(res.unwrap_err(), ParseError::InvalidByMinute("60".into()));
+        let res = props_to_rrule(&props, &ParseOptions::default());
+        assert_eq!(
+            res.unwrap_err(),
+            vec![ParseError::InvalidByMinute("60".into())]
+        );
 
         props.insert(RRuleProperty::ByMinute, "4,5,64".into());
-        let res = props_to_rrule(&props);
+        let res = props_to_rrule(&props, &ParseOptions::default());
+        assert_eq!(
+            res.unwrap_err(),
+            vec![ParseError::InvalidByMinute("4,5,64".into())]
+        );
+    }
+
+    #[test]
+    fn collects_errors_of_every_rule_part() {
+        let mut props = HashMap::new();
+        props.insert(RRuleProperty::Freq, "DAIL".into());
+        props.insert(RRuleProperty::Count, "-1".into());
+        props.insert(RRuleProperty::ByMinute, "60".into());
+        let res = props_to_rrule(&props, &ParseOptions::default());
         assert_eq!(
             res.unwrap_err(),
-            ParseError::InvalidByMinute("4,5,64".into())
+            vec![
+                ParseError::InvalidFrequency("DAIL".into()),
+                ParseError::InvalidCount("-1".into()),
+                ParseError::InvalidByMinute("60".into()),
+            ]
         );
     }
 }
@@ -1,14 +1,15 @@
 use std::collections::HashMap;
 
 use super::{
-    content_line_parts::ContentLineCaptures, date_content_line::DateParameter,
-    parameters::parse_parameters,
+    content_line_parts::ContentLineCaptures,
+    date_content_line::DateParameter,
+    parameters::{parse_extension_parameters, parse_parameters},
 };
 use crate::{
     core::Tz,
     parser::{
         datetime::{datestring_to_date, parse_timezone},
-        ParseError,
+        ParseError, ParseOptions,
     },
 };
 
@@ -19,12 +20,23 @@ pub(crate) struct StartDateContentLine {
     pub datetime: chrono::DateTime<Tz>,
     pub timezone: Option<Tz>,
     pub value: &'static str,
+    /// The experimental parameters, like `X-NAME=value`.
+    pub extensions: Vec<(String, String)>,
 }
 
 impl<'a> TryFrom<&ContentLineCaptures<'a>> for StartDateContentLine {
     type Error = ParseError;
 
     fn try_from(content_line: &ContentLineCaptures) -> Result<Self, Self::Error> {
+        Self::parse(content_line, &ParseOptions::default())
+    }
+}
+
+impl StartDateContentLine {
+    pub fn parse(
+        content_line: &ContentLineCaptures,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
         let parameters: HashMap<DateParameter, String> = content_line
             .parameters
             .as_ref()
@@ -56,12 +68,16 @@ impl<'a> TryFrom<&ContentLineCaptures<'a>> for StartDateContentLine {
             }
         }
 
-        let datetime = datestring_to_date(content_line.value, timezone, "DTSTART")?;
+        let datetime = datestring_to_date(content_line.value, timezone, "DTSTART", options)?;
 
         Ok(Self {
             datetime,
             timezone,
             value,
+            extensions: content_line
+                .parameters
+                .map(parse_extension_parameters)
+                .unwrap_or_default(),
         })
     }
 }
@@ -87,18 +103,20 @@ mod tests {
                     datetime: UTC.with_ymd_and_hms(1997, 7, 14, 12, 30, 0).unwrap(),
                     timezone: Some(UTC),
                     value: "DATE-TIME",
+                    extensions: vec![],
                 },
             ),
             (
                 ContentLineCaptures {
                     property_name: PropertyName::DtStart,
-                    parameters: Some("VALUE=DATE;TZID=UTC"),
+                    parameters: Some("VALUE=DATE;X-SOURCE=import;TZID=UTC"),
                     value: "19970101",
                 },
                 StartDateContentLine {
                     datetime: UTC.with_ymd_and_hms(1997, 1, 1, 0, 0, 0).unwrap(),
                     timezone: Some(UTC),
                     value: "DATE",
+                    extensions: vec![("X-SOURCE".into(), "import".into())],
                 },
             ),
             (
@@ -111,6 +129,7 @@ mod tests {
                     datetime: UTC.with_ymd_and_hms(1997, 1, 1, 0, 0, 0).unwrap(),
                     timezone: Some(UTC),
                     value: "DATE",
+                    extensions: vec![],
                 },
             ),
         ];
@@ -1,31 +1,27 @@
-use std::str::FromStr;
-
-use super::{regex::ParsedDateString, ParseError};
+use super::{regex::ParsedDateString, resolve_tzid, ParseError, ParseOptions, TzidMapping};
 use crate::{core::Tz, NWeekday};
-use chrono::{NaiveDate, TimeZone, Weekday, FixedOffset};
+use chrono::{LocalResult, NaiveDate, TimeZone, Weekday};
 
-/// Attempts to convert a `str` to a `chrono_tz::Tz`.
+/// Attempts to convert a `str` to a [`Tz`].
+/// See [`resolve_tzid`] for the supported formats. The mappings which aren't to an
+/// IANA name or an offset are reported by [`crate::RRuleSet::set_from_string_lenient`].
 pub(crate) fn parse_timezone(tz: &str) -> Result<Tz, ParseError> {
-    if tz.len() > 3 && (tz.starts_with("GMT") || tz.starts_with("UTC")) {
-        let offset_str = &tz[3..]; // Remove "UTC"
-
-        FixedOffset::from_str(offset_str)
-            .map_err(|_| ParseError::InvalidTimezone(tz.into()))
-            .map(Tz::FixedOffset)
-    } else {
-        chrono_tz::Tz::from_str(tz)
-            .map_err(|_| ParseError::InvalidTimezone(tz.into()))
-            .map(Tz::Tz)
+    let (timezone, mapping) = resolve_tzid(tz)?;
+    if !matches!(mapping, TzidMapping::Iana | TzidMapping::FixedOffset) {
+        log::debug!("Resolved TZID `{tz}` to `{timezone}` using the {mapping} mapping.");
     }
+    Ok(timezone)
 }
 
 /// Convert a datetime string and a timezone to a `chrono::DateTime<Tz>`.
 /// If the string specifies a zulu timezone with `Z`, then the timezone
-/// argument will be ignored.
+/// argument will be ignored. Without a timezone the floating timezone of the
+/// `options` is used.
 pub(crate) fn datestring_to_date(
     dt: &str,
     tz: Option<Tz>,
     property: &str,
+    options: &ParseOptions,
 ) -> Result<chrono::DateTime<Tz>, ParseError> {
     let ParsedDateString {
         year,
@@ -67,47 +63,25 @@ pub(crate) fn datestring_to_date(
         chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(datetime, chrono::Utc)
             .with_timezone(&Tz::UTC)
     } else {
-        // If no `Z` is present, local time should be used.
-        use chrono::offset::LocalResult;
-        // Get datetime in local time or machine local time.
-        // So this also takes into account daylight or standard time (summer/winter).
-        if let Some(tz) = tz {
-            // Use the timezone specified in the `tz`
+        // If no `Z` is present, the timezone or else the floating timezone should be used.
+        // This takes into account daylight or standard time (summer/winter).
+        let tz = tz.unwrap_or(options.floating_tz);
+        options.dst_policy.resolve(tz, &datetime).ok_or_else(|| {
             match tz.from_local_datetime(&datetime) {
-                LocalResult::None => Err(ParseError::InvalidDateTimeInLocalTimezone {
-                    value: dt.into(),
-                    property: property.into(),
-                }),
-                LocalResult::Single(date) => Ok(date),
                 LocalResult::Ambiguous(date1, date2) => {
-                    // in case of an ambiguous date, we always chose the earliest one
-                    if date1 < date2 {
-                        return Ok(date1);
-                    }
-                    return Ok(date2);
-                }
-            }?
-        } else {
-            // Use current system timezone
-            // TODO Add option to always use UTC when this is executed on a server.
-            let local = Tz::LOCAL;
-            match local.from_local_datetime(&datetime) {
-                LocalResult::None => {
-                    return Err(ParseError::InvalidDateTimeInLocalTimezone {
+                    ParseError::DateTimeInLocalTimezoneIsAmbiguous {
                         value: dt.into(),
                         property: property.into(),
-                    })
-                }
-                LocalResult::Single(date) => date,
-                LocalResult::Ambiguous(date1, date2) => {
-                    // in case of an ambiguous date, we always chose the earliest one
-                    if date1 < date2 {
-                        return Ok(date1);
+                        date1: date1.to_rfc3339(),
+                        date2: date2.to_rfc3339(),
                     }
-                    return Ok(date2);
                 }
+                _ => ParseError::InvalidDateTimeInLocalTimezone {
+                    value: dt.into(),
+                    property: property.into(),
+                },
             }
-        }
+        })?
     };
 
     Ok(datetime)
@@ -146,6 +120,7 @@ pub(crate) fn parse_weekdays(val: &str) -> Result<Vec<NWeekday>, ParseError> {
 mod tests {
     use super::*;
 
+    #[cfg(not(tz_subset))]
     const US_PACIFIC: Tz = Tz::US__Pacific;
 
     #[test]
@@ -212,6 +187,7 @@ mod tests {
         }
     }
 
+    #[cfg(not(tz_subset))]
     #[test]
     fn parses_valid_datestime_str() {
         let tests = [
@@ -239,11 +215,13 @@ mod tests {
         ];
 
         for (datetime_str, timezone, expected_output) in tests {
-            let output = datestring_to_date(datetime_str, timezone, "DTSTART");
+            let output =
+                datestring_to_date(datetime_str, timezone, "DTSTART", &ParseOptions::default());
             assert_eq!(output, Ok(expected_output));
         }
     }
 
+    #[cfg(not(tz_subset))]
     #[test]
     fn rejects_invalid_datetime_str() {
         let tests = [
@@ -254,7 +232,8 @@ mod tests {
         ];
 
         for (datetime_str, timezone) in tests {
-            let res = datestring_to_date(datetime_str, timezone, "DTSTART");
+            let res =
+                datestring_to_date(datetime_str, timezone, "DTSTART", &ParseOptions::default());
             assert!(res.is_err());
         }
     }
@@ -1,6 +1,8 @@
-#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::module_name_repetitions, missing_docs)]
 use thiserror::Error;
 
+use super::TzidMapping;
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
     #[error("`{0}` is not a valid timezone.")]
@@ -74,6 +76,30 @@ pub enum ParseError {
         "The value of `DTSTART` was specified in local timezone, but `UNTIL` was specified with a zulu time when it had to be specified in local time as well"
     )]
     DtStartUntilMismatchTimezone,
+    /// A name or value is not written in uppercase, as RFC 5545 requires.
+    #[error("`{0}` should be uppercase.")]
+    NotUppercase(String),
+    /// A rule part, parameter or date is empty, e.g. because of a trailing `;`.
+    #[error("Found an empty rule part, parameter or value, e.g. a trailing `;` or `,`.")]
+    EmptyPart,
+    /// The `VALUE` parameter has a type that is not supported, e.g. `PERIOD`.
+    #[error("`{0}` is not a supported value type, expected `DATE` or `DATE-TIME`.")]
+    UnsupportedValueType(String),
+    /// A name which RFC 5545 doesn't define is used for one it does, e.g. `BYWEEKDAY`
+    /// for `BYDAY`.
+    #[error("`{0}` is not defined by RFC 5545, it is read as `{1}`.")]
+    NonStandardName(String, String),
+    /// The `TZID` value is an abbreviation shared by several timezones, like `IST`.
+    #[error("`{0}` is an ambiguous timezone abbreviation, use an IANA name like `Europe/Dublin` instead.")]
+    AmbiguousTimezone(String),
+    /// The `TZID` value is not an IANA name, and was mapped to one, e.g. the
+    /// Windows name `W. Europe Standard Time` to `Europe/Berlin`.
+    #[error("`{tzid}` is not an IANA timezone name, it is read as `{timezone}` using the {mapping} mapping.")]
+    MappedTimezone {
+        tzid: String,
+        timezone: String,
+        mapping: TzidMapping,
+    },
     #[error("Property parameter `{parameter}` was set to have value `{parameter_value}`, but found `{found_value}` ")]
     ParameterValueMismatch {
         parameter: String,
@@ -81,3 +107,140 @@ pub enum ParseError {
         found_value: String,
     },
 }
+
+impl ParseError {
+    /// Returns the name of the property or rule part the error concerns,
+    /// e.g. `EXDATE` or `BYMONTH`, if it is known.
+    #[must_use]
+    pub fn rule_part(&self) -> Option<&str> {
+        match self {
+            Self::InvalidDateTime { property, .. }
+            | Self::InvalidDateTimeInLocalTimezone { property, .. }
+            | Self::DateTimeInLocalTimezoneIsAmbiguous { property, .. } => Some(property),
+            Self::MissingProperty(property) | Self::DuplicateProperty(property) => Some(property),
+            Self::InvalidFrequency(_) => Some("FREQ"),
+            Self::InvalidWeekday(_) => Some("BYDAY"),
+            Self::InvalidWeekdayStart(_) => Some("WKST"),
+            Self::InvalidByEaster(_) => Some("BYEASTER"),
+            Self::InvalidInterval(_) => Some("INTERVAL"),
+            Self::InvalidCount(_) => Some("COUNT"),
+            Self::InvalidByHour(_) => Some("BYHOUR"),
+            Self::InvalidByWeekNo(_) => Some("BYWEEKNO"),
+            Self::InvalidByYearDay(_) => Some("BYYEARDAY"),
+            Self::InvalidByMonthDay(_) => Some("BYMONTHDAY"),
+            Self::InvalidByMonth(_) => Some("BYMONTH"),
+            Self::InvalidByMinute(_) => Some("BYMINUTE"),
+            Self::InvalidBySecond(_) => Some("BYSECOND"),
+            Self::InvalidBySetPos(_) => Some("BYSETPOS"),
+            Self::MissingStartDate => Some("DTSTART"),
+            Self::DtStartUntilMismatchValue | Self::DtStartUntilMismatchTimezone => Some("UNTIL"),
+            Self::InvalidTimezone(_)
+            | Self::AmbiguousTimezone(_)
+            | Self::MappedTimezone { .. }
+            | Self::InvalidDateTimeFormat(_)
+            | Self::InvalidParameterFormat(_)
+            | Self::UnrecognizedParameter(_)
+            | Self::MissingDateGenerationRules
+            | Self::PropertyParametersNotSupported(_)
+            | Self::UnrecognizedPropertyName(_)
+            | Self::ParameterValueMismatch { .. }
+            | Self::NotUppercase(_)
+            | Self::NonStandardName(..)
+            | Self::EmptyPart => None,
+            Self::UnsupportedValueType(_) => Some("VALUE"),
+        }
+    }
+
+    /// Returns a stable, machine-readable code for the kind of error,
+    /// e.g. `parse.invalid_frequency`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidTimezone(_) => "parse.invalid_timezone",
+            Self::AmbiguousTimezone(_) => "parse.ambiguous_timezone",
+            Self::MappedTimezone { .. } => "parse.mapped_timezone",
+            Self::InvalidDateTime { .. } => "parse.invalid_date_time",
+            Self::InvalidDateTimeFormat(_) => "parse.invalid_date_time_format",
+            Self::InvalidDateTimeInLocalTimezone { .. } => {
+                "parse.invalid_date_time_in_local_timezone"
+            }
+            Self::DateTimeInLocalTimezoneIsAmbiguous { .. } => {
+                "parse.date_time_in_local_timezone_is_ambiguous"
+            }
+            Self::InvalidFrequency(_) => "parse.invalid_frequency",
+            Self::InvalidWeekday(_) => "parse.invalid_weekday",
+            Self::InvalidWeekdayStart(_) => "parse.invalid_weekday_start",
+            Self::InvalidByEaster(_) => "parse.invalid_by_easter",
+            Self::InvalidInterval(_) => "parse.invalid_interval",
+            Self::InvalidCount(_) => "parse.invalid_count",
+            Self::InvalidByHour(_) => "parse.invalid_by_hour",
+            Self::InvalidByWeekNo(_) => "parse.invalid_by_week_no",
+            Self::InvalidByYearDay(_) => "parse.invalid_by_year_day",
+            Self::InvalidByMonthDay(_) => "parse.invalid_by_month_day",
+            Self::InvalidByMonth(_) => "parse.invalid_by_month",
+            Self::InvalidByMinute(_) => "parse.invalid_by_minute",
+            Self::InvalidBySecond(_) => "parse.invalid_by_second",
+            Self::InvalidBySetPos(_) => "parse.invalid_by_set_pos",
+            Self::MissingProperty(_) => "parse.missing_property",
+            Self::InvalidParameterFormat(_) => "parse.invalid_parameter_format",
+            Self::UnrecognizedParameter(_) => "parse.unrecognized_parameter",
+            Self::DuplicateProperty(_) => "parse.duplicate_property",
+            Self::MissingStartDate => "parse.missing_start_date",
+            Self::MissingDateGenerationRules => "parse.missing_date_generation_rules",
+            Self::PropertyParametersNotSupported(_) => "parse.property_parameters_not_supported",
+            Self::UnrecognizedPropertyName(_) => "parse.unrecognized_property_name",
+            Self::DtStartUntilMismatchValue => "parse.dt_start_until_mismatch_value",
+            Self::DtStartUntilMismatchTimezone => "parse.dt_start_until_mismatch_timezone",
+            Self::ParameterValueMismatch { .. } => "parse.parameter_value_mismatch",
+            Self::NotUppercase(_) => "parse.not_uppercase",
+            Self::EmptyPart => "parse.empty_part",
+            Self::UnsupportedValueType(_) => "parse.unsupported_value_type",
+            Self::NonStandardName(..) => "parse.non_standard_name",
+        }
+    }
+
+    /// Returns the part of the input that caused the error, if it is known.
+    pub(crate) fn offending_text(&self) -> Option<&str> {
+        match self {
+            Self::InvalidTimezone(text)
+            | Self::AmbiguousTimezone(text)
+            | Self::InvalidDateTimeFormat(text)
+            | Self::InvalidFrequency(text)
+            | Self::InvalidWeekday(text)
+            | Self::InvalidWeekdayStart(text)
+            | Self::InvalidByEaster(text)
+            | Self::InvalidInterval(text)
+            | Self::InvalidCount(text)
+            | Self::InvalidByHour(text)
+            | Self::InvalidByWeekNo(text)
+            | Self::InvalidByYearDay(text)
+            | Self::InvalidByMonthDay(text)
+            | Self::InvalidByMonth(text)
+            | Self::InvalidByMinute(text)
+            | Self::InvalidBySecond(text)
+            | Self::InvalidBySetPos(text)
+            | Self::InvalidParameterFormat(text)
+            | Self::UnrecognizedParameter(text)
+            | Self::DuplicateProperty(text)
+            | Self::PropertyParametersNotSupported(text)
+            | Self::UnrecognizedPropertyName(text)
+            | Self::NotUppercase(text)
+            | Self::UnsupportedValueType(text)
+            | Self::NonStandardName(text, _)
+            | Self::MappedTimezone { tzid: text, .. }
+            | Self::InvalidDateTime { value: text, .. }
+            | Self::InvalidDateTimeInLocalTimezone { value: text, .. }
+            | Self::DateTimeInLocalTimezoneIsAmbiguous { value: text, .. }
+            | Self::ParameterValueMismatch {
+                parameter_value: text,
+                ..
+            } => Some(text),
+            Self::MissingProperty(_)
+            | Self::MissingStartDate
+            | Self::MissingDateGenerationRules
+            | Self::DtStartUntilMismatchValue
+            | Self::DtStartUntilMismatchTimezone
+            | Self::EmptyPart => None,
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Detection and repair of constructs which RFC 5545 doesn't allow, but which
+//! are common in real-world input.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::{
+    resolve_tzid,
+    utils::{find_unquoted, is_extension_name, split_unquoted, unquote},
+    ParseError, Span, TzidMapping,
+};
+
+/// The value types of `DTSTART`, `RDATE` and `EXDATE` that are supported.
+const SUPPORTED_VALUE_TYPES: [&str; 2] = ["DATE", "DATE-TIME"];
+
+/// A construct which RFC 5545 doesn't allow.
+#[derive(Debug, PartialEq)]
+pub(crate) struct NonStandard {
+    pub span: Span,
+    pub error: ParseError,
+    /// If the meaning of the construct is clear, so that the line can be repaired.
+    pub repairable: bool,
+}
+
+/// Returns the constructs of `line` which RFC 5545 doesn't allow, together with
+/// their position. The line is expected to be otherwise parsable, errors that the
+/// parser reports itself are not repeated.
+pub(crate) fn non_standard_constructs(line: &str, number: usize) -> Vec<NonStandard> {
+    let mut found = vec![];
+    let mut report = |range: Range<usize>, error, repairable| {
+        found.push(NonStandard {
+            span: Span::new(number, range),
+            error,
+            repairable,
+        });
+    };
+
+    let (head, value_start) = match find_unquoted(line, ':') {
+        Some(colon) => (&line[..colon], colon + 1),
+        None => {
+            // The parser accepts the value of an `RRULE` without the property name.
+            report(
+                0..line.len(),
+                ParseError::MissingProperty("RRULE".into()),
+                true,
+            );
+            ("", 0)
+        }
+    };
+
+    let mut parameters = split_with_offsets(head, 0, ';');
+    let name = parameters.next().map_or("", |(_, name)| name);
+    if is_not_uppercase(name) {
+        report(0..name.len(), ParseError::NotUppercase(name.into()), true);
+    }
+    if is_extension_name(name) {
+        // Experimental properties are kept as is.
+        return found;
+    }
+    let property = if head.is_empty() {
+        "RRULE".into()
+    } else {
+        name.to_ascii_uppercase()
+    };
+    let is_rule = matches!(&property[..], "RRULE" | "EXRULE");
+
+    let mut keys = HashMap::new();
+    for (start, parameter) in parameters {
+        let Some((key, value)) = parameter.split_once('=') else {
+            if parameter.is_empty() {
+                report(start.saturating_sub(1)..start, ParseError::EmptyPart, true);
+            }
+            continue;
+        };
+        check_key(key, value, start, &mut keys, &mut report);
+        if key.eq_ignore_ascii_case("VALUE") && !is_rule {
+            let value_range = start + key.len() + 1..start + parameter.len();
+            if is_not_uppercase(value) {
+                report(
+                    value_range.clone(),
+                    ParseError::NotUppercase(value.into()),
+                    true,
+                );
+            }
+            let value_type = value.to_ascii_uppercase();
+            if !SUPPORTED_VALUE_TYPES.contains(&&value_type[..]) {
+                // A period is repaired to its start, other value types can't be read as dates.
+                let repairable = value_type == "PERIOD";
+                report(
+                    value_range,
+                    ParseError::UnsupportedValueType(value.into()),
+                    repairable,
+                );
+            }
+        }
+    }
+
+    let value = &line[value_start..];
+    if is_rule {
+        let mut keys = HashMap::new();
+        for (start, rule_part) in split_with_offsets(value, value_start, ';') {
+            let Some((key, value)) = rule_part.split_once('=') else {
+                if rule_part.is_empty() {
+                    report(start.saturating_sub(1)..start, ParseError::EmptyPart, true);
+                }
+                continue;
+            };
+            if key.eq_ignore_ascii_case("BYWEEKDAY") {
+                report(
+                    start..start + key.len(),
+                    ParseError::NonStandardName(key.into(), "BYDAY".into()),
+                    true,
+                );
+            }
+            check_key(key, value, start, &mut keys, &mut report);
+            if is_not_uppercase(value) && !is_extension_name(key) {
+                let value_start = start + key.len() + 1;
+                report(
+                    value_start..value_start + value.len(),
+                    ParseError::NotUppercase(value.into()),
+                    true,
+                );
+            }
+        }
+    } else {
+        for (start, date) in split_with_offsets(value, value_start, ',') {
+            if date.is_empty() {
+                report(start.saturating_sub(1)..start, ParseError::EmptyPart, true);
+            } else if is_not_uppercase(date) {
+                report(
+                    start..start + date.len(),
+                    ParseError::NotUppercase(date.into()),
+                    true,
+                );
+            }
+        }
+    }
+
+    found
+}
+
+/// Returns the `TZID` values of `line` which are neither IANA names nor offsets
+/// from UTC, like `W. Europe Standard Time`, together with their position and the
+/// timezone they were mapped to.
+pub(crate) fn mapped_timezones(line: &str, number: usize) -> Vec<(Span, ParseError)> {
+    let Some(colon) = find_unquoted(line, ':') else {
+        return vec![];
+    };
+    split_with_offsets(&line[..colon], 0, ';')
+        .skip(1)
+        .filter_map(|(start, parameter)| {
+            let (key, value) = parameter.split_once('=')?;
+            if !key.eq_ignore_ascii_case("TZID") {
+                return None;
+            }
+            let (tz, mapping) = resolve_tzid(unquote(value)).ok()?;
+            if matches!(mapping, TzidMapping::Iana | TzidMapping::FixedOffset) {
+                return None;
+            }
+            let value_start = start + key.len() + 1;
+            let error = ParseError::MappedTimezone {
+                tzid: unquote(value).into(),
+                timezone: tz.name(),
+                mapping,
+            };
+            Some((
+                Span::new(number, value_start..start + parameter.len()),
+                error,
+            ))
+        })
+        .collect()
+}
+
+/// Uppercases `line`, except for the `TZID` values which are case-sensitive, and
+/// experimental properties, parameters and rule parts which are kept as is.
+/// The length of the line doesn't change, so positions in it stay valid.
+pub(crate) fn uppercase_line(line: &str) -> Cow<'_, str> {
+    let colon = find_unquoted(line, ':');
+    let head = &line[..colon.unwrap_or(0)];
+    if !is_not_uppercase(line) || is_extension_name(head.split(';').next().unwrap_or("")) {
+        return Cow::Borrowed(line);
+    }
+    let mut uppercase = line.to_ascii_uppercase();
+    let value_start = colon.map_or(0, |colon| colon + 1);
+    let parts = split_with_offsets(head, 0, ';').chain(split_with_offsets(
+        &line[value_start..],
+        value_start,
+        ';',
+    ));
+    for (start, part) in parts {
+        if let Some((key, _)) = part.split_once('=') {
+            if key.eq_ignore_ascii_case("TZID") || is_extension_name(key) {
+                let range = start + key.len() + 1..start + part.len();
+                uppercase.replace_range(range.clone(), &line[range]);
+            }
+        }
+    }
+    Cow::Owned(uppercase)
+}
+
+/// Reports a key that is not uppercase or was already used. A duplicate can only
+/// be repaired if it has the same value as the key it repeats.
+fn check_key(
+    key: &str,
+    value: &str,
+    start: usize,
+    keys: &mut HashMap<String, String>,
+    report: &mut impl FnMut(Range<usize>, ParseError, bool),
+) {
+    let range = start..start + key.len();
+    if is_not_uppercase(key) {
+        report(range.clone(), ParseError::NotUppercase(key.into()), true);
+    }
+    let mut normalized = key.to_ascii_uppercase();
+    if normalized == "BYWEEKDAY" {
+        normalized = "BYDAY".into();
+    }
+    let value = value.to_ascii_uppercase();
+    if let Some(previous) = keys.insert(normalized, value.clone()) {
+        report(
+            range,
+            ParseError::DuplicateProperty(key.into()),
+            previous == value,
+        );
+    }
+}
+
+fn is_not_uppercase(text: &str) -> bool {
+    text.bytes().any(|byte| byte.is_ascii_lowercase())
+}
+
+/// Splits `text`, which starts at byte `offset` of its line, at `separator` outside
+/// of quoted values, and returns each part together with the offset it starts at.
+fn split_with_offsets(
+    text: &str,
+    offset: usize,
+    separator: char,
+) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = offset;
+    split_unquoted(text, separator).map(move |part| {
+        let part_start = start;
+        start += part.len() + separator.len_utf8();
+        (part_start, part)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the text, error and whether it can be repaired of each construct.
+    fn constructs(line: &str) -> Vec<(&str, ParseError, bool)> {
+        non_standard_constructs(line, 1)
+            .into_iter()
+            .map(|found| {
+                let span = found.span;
+                let text = &line[span.start as usize..span.end as usize];
+                (text, found.error, found.repairable)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accepts_rfc_input() {
+        assert!(constructs("DTSTART;TZID=Europe/Berlin:20120201T093000").is_empty());
+        assert!(constructs("RDATE;X-NOTE=a,b:20120201T093000Z").is_empty());
+        assert!(constructs(r#"RDATE;X-LOC="geo:1,2;x":20120201T093000Z"#).is_empty());
+        assert!(constructs("X-NOTE;VALUE=TEXT:some text,;").is_empty());
+        assert!(constructs("RRULE:FREQ=WEEKLY;COUNT=3;BYDAY=MO,-1FR").is_empty());
+        assert!(constructs("EXDATE;VALUE=DATE:20120201,20120202").is_empty());
+    }
+
+    #[test]
+    fn finds_non_standard_rule_parts() {
+        assert_eq!(
+            constructs("rrule:FREQ=daily;BYWEEKDAY=MO;BYDAY=TU;"),
+            vec![
+                ("rrule", ParseError::NotUppercase("rrule".into()), true),
+                ("daily", ParseError::NotUppercase("daily".into()), true),
+                (
+                    "BYWEEKDAY",
+                    ParseError::NonStandardName("BYWEEKDAY".into(), "BYDAY".into()),
+                    true
+                ),
+                (
+                    "BYDAY",
+                    ParseError::DuplicateProperty("BYDAY".into()),
+                    false
+                ),
+                (";", ParseError::EmptyPart, true),
+            ]
+        );
+        assert_eq!(
+            constructs("RRULE:FREQ=DAILY;COUNT=2;COUNT=2;COUNT=3"),
+            vec![
+                ("COUNT", ParseError::DuplicateProperty("COUNT".into()), true),
+                (
+                    "COUNT",
+                    ParseError::DuplicateProperty("COUNT".into()),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            constructs("FREQ=DAILY"),
+            vec![(
+                "FREQ=DAILY",
+                ParseError::MissingProperty("RRULE".into()),
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn finds_non_standard_dates() {
+        assert_eq!(
+            constructs("RDATE;VALUE=period;value=DATE:19960403t020000Z/19960403T040000Z,"),
+            vec![
+                ("period", ParseError::NotUppercase("period".into()), true),
+                (
+                    "period",
+                    ParseError::UnsupportedValueType("period".into()),
+                    true
+                ),
+                ("value", ParseError::NotUppercase("value".into()), true),
+                (
+                    "value",
+                    ParseError::DuplicateProperty("value".into()),
+                    false
+                ),
+                (
+                    "19960403t020000Z/19960403T040000Z",
+                    ParseError::NotUppercase("19960403t020000Z/19960403T040000Z".into()),
+                    true
+                ),
+                (",", ParseError::EmptyPart, true),
+            ]
+        );
+        assert_eq!(
+            constructs("RDATE;VALUE=TEXT:20120201"),
+            vec![(
+                "TEXT",
+                ParseError::UnsupportedValueType("TEXT".into()),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn uppercases_all_but_timezones() {
+        assert_eq!(
+            uppercase_line("dtstart;tzid=Europe/Berlin:20120201t093000"),
+            "DTSTART;TZID=Europe/Berlin:20120201T093000"
+        );
+        assert_eq!(
+            uppercase_line("rrule:freq=daily;X-Name=Value"),
+            "RRULE:FREQ=DAILY;X-NAME=Value"
+        );
+        assert!(matches!(
+            uppercase_line("RRULE:FREQ=DAILY"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            uppercase_line("X-Name;x-param=a:value"),
+            Cow::Borrowed(_)
+        ));
+    }
+}
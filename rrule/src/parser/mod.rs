@@ -3,18 +3,65 @@
 mod content_line;
 mod datetime;
 mod error;
+mod lint;
 mod regex;
+#[cfg(any(test, not(feature = "regex")))]
+mod scanner;
+mod span;
+mod tzid;
 mod utils;
 
+use std::borrow::Cow;
 use std::str::FromStr;
 
-pub(crate) use content_line::{ContentLine, ContentLineCaptures};
+pub(crate) use content_line::{ContentLine, ContentLineCaptures, DateContentLine};
 pub(crate) use datetime::str_to_weekday;
 pub use error::ParseError;
+pub use span::Span;
+pub(crate) use tzid::resolve_tzid;
+#[cfg(feature = "wasm")]
+pub(crate) use tzid::with_tzid_cache;
+pub use tzid::TzidMapping;
 
-use crate::RRule;
+use crate::{DstPolicy, RRule, Tz};
 
 use self::content_line::{PropertyName, StartDateContentLine};
+use self::utils::find_unquoted;
+
+/// Settings which change how the content lines are interpreted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ParseOptions {
+    /// Timezone of the dates which have neither a `Z` suffix nor a `TZID`.
+    pub floating_tz: Tz,
+    /// Resolution of local times in daylight saving time transitions.
+    pub dst_policy: DstPolicy,
+    /// How constructs which RFC 5545 doesn't allow are handled.
+    pub mode: ParseMode,
+}
+
+/// How constructs which RFC 5545 doesn't allow, like lowercase names or empty
+/// rule parts, are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ParseMode {
+    /// They are accepted where the parser understands them, without reporting them.
+    #[default]
+    Unchecked,
+    /// They are repaired where possible and reported as warnings, the lines with
+    /// the others are left out and reported as errors.
+    Lenient,
+    /// They are reported as errors.
+    Strict,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            floating_tz: Tz::LOCAL,
+            dst_policy: DstPolicy::default(),
+            mode: ParseMode::default(),
+        }
+    }
+}
 
 /// Grammar represents a well-formatted rrule input.
 #[derive(Debug, PartialEq)]
@@ -27,70 +74,189 @@ impl FromStr for Grammar {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let content_lines_parts = s
+        Self::parse(s, &ParseOptions::default())
+    }
+}
+
+/// The result of [`Grammar::parse_lines`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct ParsedLines {
+    pub start: Option<StartDateContentLine>,
+    /// The lines that could be parsed, each with its number, starting at `1`.
+    pub content_lines: Vec<(usize, ContentLine)>,
+    /// The errors, each with its position in the input, or `None` if it concerns
+    /// the input as a whole.
+    pub errors: Vec<(Option<Span>, ParseError)>,
+    /// The constructs which were repaired in [`ParseMode::Lenient`], and the `TZID`
+    /// values which were mapped to an IANA timezone.
+    pub warnings: Vec<(Span, ParseError)>,
+}
+
+impl Grammar {
+    pub fn parse(s: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        let mut lines = Self::parse_lines(s, options);
+        if !lines.errors.is_empty() {
+            return Err(lines.errors.swap_remove(0).1);
+        }
+
+        Ok(Self {
+            start: lines.start,
+            content_lines: lines
+                .content_lines
+                .into_iter()
+                .map(|(_, line)| line)
+                .collect(),
+        })
+    }
+
+    /// Parses every line of the input, and returns the lines that could be parsed
+    /// together with the errors of every line that couldn't, instead of stopping at
+    /// the first one. Each error comes with its position in the input, or `None` if
+    /// it concerns the input as a whole.
+    ///
+    /// Unless the mode of the `options` is [`ParseMode::Unchecked`], every line is
+    /// also checked for constructs which RFC 5545 doesn't allow. In
+    /// [`ParseMode::Lenient`] the lines with constructs that can't be repaired, like
+    /// a rule part repeated with another value, are left out.
+    pub fn parse_lines(s: &str, options: &ParseOptions) -> ParsedLines {
+        let mut errors = vec![];
+        let mut warnings = vec![];
+        let lines: Vec<_> = s
             .lines()
-            .map(ContentLineCaptures::new)
-            .collect::<Result<Vec<_>, _>>()?;
+            .zip(1..)
+            .filter_map(|(line, number)| match options.mode {
+                ParseMode::Unchecked => Some((Cow::Borrowed(line), number)),
+                ParseMode::Lenient => {
+                    // A line with a construct that can't be repaired is left out.
+                    let mut repairable = true;
+                    for found in lint::non_standard_constructs(line, number) {
+                        if found.repairable {
+                            warnings.push((found.span, found.error));
+                        } else {
+                            repairable = false;
+                            errors.push((Some(found.span), found.error));
+                        }
+                    }
+                    if repairable {
+                        warnings.extend(lint::mapped_timezones(line, number));
+                    }
+                    repairable.then(|| (lint::uppercase_line(line), number))
+                }
+                ParseMode::Strict => {
+                    errors.extend(
+                        lint::non_standard_constructs(line, number)
+                            .into_iter()
+                            .map(|found| (Some(found.span), found.error)),
+                    );
+                    Some((Cow::Borrowed(line), number))
+                }
+            })
+            .collect();
+
+        let mut push_error = |line: &str, number, err: ParseError| {
+            let span = Span::locate(line, number, err.rule_part(), err.offending_text());
+            errors.push((Some(span), err));
+        };
+
+        let content_lines_parts = lines
+            .iter()
+            .map(|(line, number)| (&line[..], *number))
+            .filter_map(|(line, number)| match ContentLineCaptures::new(line) {
+                Ok(parts) => Some((line, number, parts)),
+                Err(err) => {
+                    push_error(line, number, err);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
 
         let start = content_lines_parts
             .iter()
-            .find(|parts| matches!(parts.property_name, PropertyName::DtStart))
-            .map(StartDateContentLine::try_from)
-            .transpose()?;
+            .find(|(_, _, parts)| matches!(parts.property_name, PropertyName::DtStart))
+            .and_then(
+                |(line, number, parts)| match StartDateContentLine::parse(parts, options) {
+                    Ok(start) => Some(start),
+                    Err(err) => {
+                        push_error(line, *number, err);
+                        None
+                    }
+                },
+            );
 
         let mut content_lines = vec![];
 
-        for parts in content_lines_parts {
+        for (line_text, number, parts) in content_lines_parts {
             let line = match parts.property_name {
-                PropertyName::RRule => {
-                    let rrule = RRule::try_from(parts)?;
-                    ContentLine::RRule(rrule)
-                }
-                PropertyName::ExRule => {
-                    let rrule = RRule::try_from(parts)?;
-                    ContentLine::ExRule(rrule)
+                PropertyName::RRule => RRule::parse_all(&parts, options).map(ContentLine::RRule),
+                PropertyName::ExRule => RRule::parse_all(&parts, options).map(ContentLine::ExRule),
+                PropertyName::RDate => DateContentLine::parse(&parts, options)
+                    .map(ContentLine::RDate)
+                    .map_err(|err| vec![err]),
+                PropertyName::ExDate => DateContentLine::parse(&parts, options)
+                    .map(ContentLine::ExDate)
+                    .map_err(|err| vec![err]),
+                PropertyName::Extension(_) => {
+                    let (name, value) = match find_unquoted(line_text, ':') {
+                        Some(colon) => (&line_text[..colon], &line_text[colon + 1..]),
+                        None => (line_text, ""),
+                    };
+                    Ok(ContentLine::Extension(name.into(), value.into()))
                 }
-                PropertyName::RDate => ContentLine::RDate(TryFrom::try_from(parts)?),
-                PropertyName::ExDate => ContentLine::ExDate(TryFrom::try_from(parts)?),
                 PropertyName::DtStart => {
                     // Nothing to do
                     continue;
                 }
             };
-            content_lines.push(line);
+            match line {
+                Ok(line) => content_lines.push((number, line)),
+                Err(line_errors) => {
+                    for err in line_errors {
+                        push_error(line_text, number, err);
+                    }
+                }
+            }
         }
 
-        // Need to be at least one `RDATE` or `RRULE`
-        if !content_lines
-            .iter()
-            .any(|line| matches!(line, ContentLine::RRule(_) | ContentLine::RDate(_)))
+        // Need to be at least one `RDATE` or `RRULE`. Lines that failed to parse
+        // may have been one, so that is only reported when there are no other errors.
+        if errors.is_empty()
+            && !content_lines
+                .iter()
+                .any(|(_, line)| matches!(line, ContentLine::RRule(_) | ContentLine::RDate(_)))
         {
-            return Err(ParseError::MissingDateGenerationRules);
+            errors.push((None, ParseError::MissingDateGenerationRules));
         }
 
-        Ok(Self {
+        ParsedLines {
             start,
             content_lines,
-        })
+            errors,
+            warnings,
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use chrono::{TimeZone, Weekday};
-
     use super::*;
-    use crate::{core::Tz, parser::content_line::ContentLine, Frequency, NWeekday, RRule};
-
-    const UTC: Tz = Tz::UTC;
-    const BERLIN: Tz = Tz::Europe__Berlin;
 
+    #[cfg(not(tz_subset))]
     #[test]
     fn parses_valid_input_to_grammar() {
+        use crate::{
+            core::Tz,
+            parser::content_line::{ContentLine, DateContentLine},
+            Frequency, NWeekday, RRule,
+        };
+        use chrono::{TimeZone, Weekday};
+
+        const UTC: Tz = Tz::UTC;
+        const BERLIN: Tz = Tz::Europe__Berlin;
+
         let tests = [
 (
     "DTSTART:19970902T090000Z\nRRULE:FREQ=YEARLY;COUNT=3\n", Grammar {
-    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(1997, 9, 2,9, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME" }),
+    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(1997, 9, 2,9, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME", extensions: vec![] }),
     content_lines: vec![
         ContentLine::RRule(RRule {
             freq: Frequency::Yearly,
@@ -101,7 +267,7 @@ mod test {
 }
 ),
 ("DTSTART:20120201T093000Z\nRRULE:FREQ=WEEKLY;INTERVAL=5;UNTIL=20130130T230000Z;BYDAY=MO,FR", Grammar {
-    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,9, 30, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME" }),
+    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,9, 30, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME", extensions: vec![] }),
     content_lines: vec![
         ContentLine::RRule(RRule {
             freq: Frequency::Weekly,
@@ -113,31 +279,39 @@ mod test {
     ]
 }),
 ("DTSTART:20120201T120000Z\nRRULE:FREQ=DAILY;COUNT=5\nEXDATE;TZID=Europe/Berlin:20120202T130000,20120203T130000", Grammar {
-    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,12, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME" }),
+    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,12, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME", extensions: vec![] }),
     content_lines: vec![
         ContentLine::RRule(RRule {
             freq: Frequency::Daily,
             count: Some(5),
             ..Default::default()
         }),
-        ContentLine::ExDate(vec![
-            BERLIN.with_ymd_and_hms(2012, 2, 2,13, 0, 0).unwrap(),
-            BERLIN.with_ymd_and_hms(2012, 2, 3,13, 0, 0).unwrap(),
-        ])
+        ContentLine::ExDate(DateContentLine {
+            dates: vec![
+                BERLIN.with_ymd_and_hms(2012, 2, 2,13, 0, 0).unwrap(),
+                BERLIN.with_ymd_and_hms(2012, 2, 3,13, 0, 0).unwrap(),
+            ],
+            date_only: false,
+            extensions: vec![],
+        })
     ]
 }),
 ("DTSTART:20120201T120000Z\nRRULE:FREQ=DAILY;COUNT=5\nEXDATE;TZID=Europe/Berlin:20120202T130000,20120203T130000\nEXRULE:FREQ=WEEKLY;COUNT=10", Grammar {
-    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,12, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME" }),
+    start: Some(StartDateContentLine { datetime: UTC.with_ymd_and_hms(2012, 2, 1,12, 0, 0).unwrap(), timezone: Some(UTC), value: "DATE-TIME", extensions: vec![] }),
     content_lines: vec![
         ContentLine::RRule(RRule {
             freq: Frequency::Daily,
             count: Some(5),
             ..Default::default()
         }),
-        ContentLine::ExDate(vec![
-            BERLIN.with_ymd_and_hms(2012, 2, 2,13, 0, 0).unwrap(),
-            BERLIN.with_ymd_and_hms(2012, 2, 3,13, 0, 0).unwrap(),
-        ]),
+        ContentLine::ExDate(DateContentLine {
+            dates: vec![
+                BERLIN.with_ymd_and_hms(2012, 2, 2,13, 0, 0).unwrap(),
+                BERLIN.with_ymd_and_hms(2012, 2, 3,13, 0, 0).unwrap(),
+            ],
+            date_only: false,
+            extensions: vec![],
+        }),
         ContentLine::ExRule(RRule {
             freq: Frequency::Weekly,
             count: Some(10),
@@ -178,4 +352,43 @@ mod test {
             assert!(res.is_ok());
         }
     }
+
+    #[test]
+    fn collects_errors_of_every_line() {
+        let input = "DTSTART:2012020\nRRULE:FREQ=DAILY\nRRULE:FREQ=DAYLY;BYHOUR=24\nEXDATE:20120202T130000Z\nRDATE:2012";
+        let ParsedLines {
+            start,
+            content_lines,
+            errors,
+            ..
+        } = Grammar::parse_lines(input, &ParseOptions::default());
+        assert_eq!(start, None);
+        let lines: Vec<_> = content_lines.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![2, 4]);
+        let span = |line, start, end| Some(Span { line, start, end });
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    span(1, 8, 15),
+                    ParseError::InvalidDateTime {
+                        value: "2012020".into(),
+                        property: "DTSTART".into()
+                    }
+                ),
+                (
+                    span(3, 11, 16),
+                    ParseError::InvalidFrequency("DAYLY".into())
+                ),
+                (span(3, 24, 26), ParseError::InvalidByHour("24".into())),
+                (
+                    span(5, 6, 10),
+                    ParseError::InvalidDateTime {
+                        value: "2012".into(),
+                        property: "RDATE".into()
+                    }
+                ),
+            ]
+        );
+    }
 }
@@ -1,11 +1,17 @@
 //! Utility functions around the regexes we use for parsing rrule strings.
+//!
+//! Without the `regex` feature, the hand-written scanner in [`super::scanner`] is
+//! used instead.
 use std::str::FromStr;
 
+#[cfg(feature = "regex")]
 use lazy_static::lazy_static;
+#[cfg(feature = "regex")]
 use regex::{Captures, Regex};
 
 use super::{content_line::PropertyName, ParseError};
 
+#[cfg(feature = "regex")]
 lazy_static! {
     static ref DATESTR_RE: Regex =
         Regex::new(r"(?m)^([0-9]{4})([0-9]{2})([0-9]{2})(T([0-9]{2})([0-9]{2})([0-9]{2})(Z?))?$")
@@ -33,6 +39,7 @@ pub(crate) struct ParsedDateStringTime {
     pub sec: u32,
 }
 
+#[cfg(feature = "regex")]
 fn get_datetime_captures<T: FromStr>(
     captures: &Captures,
     idx: usize,
@@ -49,6 +56,7 @@ fn get_datetime_captures<T: FromStr>(
 impl ParsedDateString {
     /// Parses a date string with format `YYYYMMDD(THHMMSSZ)` where the part in parentheses
     /// is optional. It returns [`ParsedDateString`].
+    #[cfg(feature = "regex")]
     pub(crate) fn from_ical_datetime(val: &str) -> Result<Self, ParseError> {
         let captures = DATESTR_RE
             .captures(val)
@@ -82,14 +90,24 @@ impl ParsedDateString {
             flags,
         })
     }
+
+    /// Parses a date string with format `YYYYMMDD(THHMMSSZ)` where the part in parentheses
+    /// is optional. It returns [`ParsedDateString`].
+    #[cfg(not(feature = "regex"))]
+    pub(crate) fn from_ical_datetime(val: &str) -> Result<Self, ParseError> {
+        super::scanner::scan_datetime(val)
+            .ok_or_else(|| ParseError::InvalidDateTimeFormat(val.into()))
+    }
 }
 
+#[cfg(feature = "regex")]
 lazy_static! {
-    static ref PARSE_PROPERTY_NAME_RE: Regex =
-        Regex::new(r"(?m)^([A-Z]+?)[:;]").expect("PARSE_PROPERTY_NAME_RE regex failed");
+    static ref PARSE_PROPERTY_NAME_RE: Regex = Regex::new(r"(?m)^([Xx]-[A-Za-z0-9-]+|[A-Z]+?)[:;]")
+        .expect("PARSE_PROPERTY_NAME_RE regex failed");
 }
 
 /// Get the line property name, the `RRULE:`, `EXRULE:` etc part.
+#[cfg(feature = "regex")]
 pub(crate) fn get_property_name(val: &str) -> Result<Option<PropertyName>, ParseError> {
     PARSE_PROPERTY_NAME_RE
         .captures(val)
@@ -98,6 +116,14 @@ pub(crate) fn get_property_name(val: &str) -> Result<Option<PropertyName>, Parse
         .transpose()
 }
 
+/// Get the line property name, the `RRULE:`, `EXRULE:` etc part.
+#[cfg(not(feature = "regex"))]
+pub(crate) fn get_property_name(val: &str) -> Result<Option<PropertyName>, ParseError> {
+    super::scanner::scan_property_name(val)
+        .map(PropertyName::from_str)
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parser::{content_line::PropertyName, regex::get_property_name, ParseError};
@@ -207,6 +233,10 @@ mod tests {
                 "DTSTART;TZID=America/Everywhere:20120251T023000Z\nFREQ=DAILY;INTERVAL=1;",
                 PropertyName::DtStart,
             ),
+            (
+                "X-WR-TIMEZONE;X-PARAM=1:Europe/Berlin",
+                PropertyName::Extension("X-WR-TIMEZONE".into()),
+            ),
         ];
         for (input, expected_output) in tests {
             let output = get_property_name(input);
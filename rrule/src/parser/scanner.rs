@@ -0,0 +1,168 @@
+//! Hand-written replacements of the regexes in [`super::regex`], which are used
+//! when the `regex` feature is disabled to keep the `regex` crate out of the build.
+//!
+//! Like the regexes, which are compiled in multi-line mode, they look for the
+//! first line of the input that matches.
+use super::regex::{ParsedDateString, ParsedDateStringFlags, ParsedDateStringTime};
+
+/// Scans a date string with format `YYYYMMDD(THHMMSS(Z))`, where the part in
+/// parentheses is optional.
+pub(crate) fn scan_datetime(val: &str) -> Option<ParsedDateString> {
+    val.split('\n').find_map(scan_datetime_line)
+}
+
+fn scan_datetime_line(line: &str) -> Option<ParsedDateString> {
+    let (date, time) = match line.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (line, None),
+    };
+    if date.len() != 8 || !is_digits(date) {
+        return None;
+    }
+
+    let (time, zulu_timezone_set) = match time {
+        Some(time) => {
+            let (time, zulu) = match time.strip_suffix('Z') {
+                Some(time) => (time, true),
+                None => (time, false),
+            };
+            if time.len() != 6 || !is_digits(time) {
+                return None;
+            }
+            let time = ParsedDateStringTime {
+                hour: time[0..2].parse().ok()?,
+                min: time[2..4].parse().ok()?,
+                sec: time[4..6].parse().ok()?,
+            };
+            (Some(time), zulu)
+        }
+        None => (None, false),
+    };
+
+    Some(ParsedDateString {
+        year: date[0..4].parse().ok()?,
+        month: date[4..6].parse().ok()?,
+        day: date[6..8].parse().ok()?,
+        time,
+        flags: ParsedDateStringFlags { zulu_timezone_set },
+    })
+}
+
+/// Scans the property name at the start of a line, which is followed by `:` or
+/// `;`. It is either uppercase, or an experimental name starting with `X-`.
+pub(crate) fn scan_property_name(val: &str) -> Option<&str> {
+    val.split('\n').find_map(|line| {
+        let bytes = line.as_bytes();
+        let is_extension =
+            bytes.len() > 2 && bytes[0].eq_ignore_ascii_case(&b'X') && bytes[1] == b'-';
+        if is_extension {
+            let end = 2 + prefix_len(&line[2..], |byte| {
+                byte.is_ascii_alphanumeric() || byte == b'-'
+            });
+            if end > 2 && is_separator(bytes.get(end)) {
+                return Some(&line[..end]);
+            }
+        }
+        let end = prefix_len(line, |byte| byte.is_ascii_uppercase());
+        (end > 0 && is_separator(bytes.get(end))).then(|| &line[..end])
+    })
+}
+
+fn is_digits(text: &str) -> bool {
+    text.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn is_separator(byte: Option<&u8>) -> bool {
+    matches!(byte, Some(b':' | b';'))
+}
+
+/// Returns the length of the longest prefix of `text` whose bytes match `accept`.
+fn prefix_len(text: &str, accept: impl Fn(u8) -> bool) -> usize {
+    text.bytes().take_while(|&byte| accept(byte)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_datetimes() {
+        let parsed = scan_datetime("20101017T120049Z").unwrap();
+        assert_eq!((parsed.year, parsed.month, parsed.day), (2010, 10, 17));
+        assert_eq!(
+            parsed.time,
+            Some(ParsedDateStringTime {
+                hour: 12,
+                min: 0,
+                sec: 49
+            })
+        );
+        assert!(parsed.flags.zulu_timezone_set);
+        assert_eq!(scan_datetime("garbage\n20101017").unwrap().time, None);
+        for input in [
+            "",
+            "2010101",
+            "20101017T",
+            "20101017T1200",
+            "20101017T120000ZZ",
+            "2010-10-17",
+        ] {
+            assert_eq!(scan_datetime(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn scans_property_names() {
+        assert_eq!(scan_property_name("RRULE:FREQ=DAILY"), Some("RRULE"));
+        assert_eq!(
+            scan_property_name("DTSTART;TZID=UTC:20120201T023000"),
+            Some("DTSTART")
+        );
+        assert_eq!(
+            scan_property_name("x-wr-name;X-PARAM=1:value"),
+            Some("x-wr-name")
+        );
+        assert_eq!(
+            scan_property_name("FREQ=DAILY\nEXDATE:20120201"),
+            Some("EXDATE")
+        );
+        for input in ["", "FREQ=DAILY", "rrule:FREQ=DAILY", "X-:value", ":value"] {
+            assert_eq!(scan_property_name(input), None, "{input}");
+        }
+    }
+
+    /// The scanner has to accept exactly what the regexes accept.
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches_the_regexes() {
+        use super::super::regex::get_property_name;
+
+        let inputs = [
+            "20101017T120000Z",
+            "20101017T120000",
+            "20101017",
+            "a\n20101017T120000Z\nb",
+            "20101017T120000Z\r",
+            "２0101017",
+            "RRULE:FREQ=DAILY",
+            "RRULES:FREQ=DAILY",
+            "X-NAME;X-PARAM=1:value",
+            "x-name-2:value",
+            "X-na_me:value",
+            "Xylophone:value",
+            "DTSTART\nRDATE:20101017",
+            "rdate;VALUE=DATE:20101017",
+        ];
+        for input in inputs {
+            assert_eq!(
+                scan_datetime(input),
+                ParsedDateString::from_ical_datetime(input).ok(),
+                "{input}"
+            );
+            let scanned = scan_property_name(input)
+                .map(|name| name.parse::<super::super::content_line::PropertyName>())
+                .transpose();
+            assert_eq!(scanned, get_property_name(input), "{input}");
+        }
+    }
+}
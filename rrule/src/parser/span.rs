@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+/// The position of an error in the input.
+///
+/// The fields are `u32` to keep [`crate::RRuleError`] small, inputs with longer
+/// lines are saturated at `u32::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The line, starting at `1`.
+    pub line: u32,
+    /// The byte offset in the line where the span starts, starting at `0`.
+    pub start: u32,
+    /// The byte offset in the line where the span ends, exclusive.
+    pub end: u32,
+}
+
+impl Span {
+    /// Creates the span of the byte `range` on `line`.
+    pub(crate) fn new(line: usize, range: Range<usize>) -> Self {
+        let saturate = |value: usize| u32::try_from(value).unwrap_or(u32::MAX);
+        Self {
+            line: saturate(line),
+            start: saturate(range.start),
+            end: saturate(range.end),
+        }
+    }
+
+    /// Returns the span of `text` in the value of `rule_part` on `line_text`.
+    ///
+    /// Falls back to the whole value of the rule part if `text` isn't found in it,
+    /// and searches the whole line if the rule part isn't found.
+    pub(crate) fn locate(
+        line_text: &str,
+        line: usize,
+        rule_part: Option<&str>,
+        text: Option<&str>,
+    ) -> Self {
+        let scope = rule_part
+            .and_then(|rule_part| rule_part_value(line_text, rule_part))
+            .unwrap_or(0..line_text.len());
+        let range = text
+            .filter(|text| !text.is_empty())
+            .and_then(|text| {
+                line_text[scope.clone()]
+                    .find(text)
+                    .map(|offset| scope.start + offset..scope.start + offset + text.len())
+            })
+            .unwrap_or(scope);
+
+        Self::new(line, range)
+    }
+}
+
+/// Returns the byte range of the value of `rule_part` (e.g. `24` of `BYHOUR=24`) in `line_text`.
+fn rule_part_value(line_text: &str, rule_part: &str) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for segment in line_text.split([';', ':']) {
+        let end = offset + segment.len();
+        if let Some((key, _)) = segment.split_once('=') {
+            if key.eq_ignore_ascii_case(rule_part)
+                || (rule_part == "BYDAY" && key.eq_ignore_ascii_case("BYWEEKDAY"))
+            {
+                return Some(offset + key.len() + 1..end);
+            }
+        }
+        offset = end + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_text_in_rule_part() {
+        let line = "RRULE:FREQ=DAILY;BYMONTH=1;BYHOUR=1,25";
+        let span = Span::locate(line, 2, Some("BYHOUR"), Some("1,25"));
+        assert_eq!(&line[span.start as usize..span.end as usize], "1,25");
+        assert_eq!(span.line, 2);
+
+        let span = Span::locate(line, 2, Some("BYDAY"), Some("XX"));
+        assert_eq!(&line[span.start as usize..span.end as usize], line);
+
+        let line = "RRULE:FREQ=WEEKLY;byweekday=MO,XX";
+        let span = Span::locate(line, 1, Some("BYDAY"), Some("XX"));
+        assert_eq!(&line[span.start as usize..span.end as usize], "XX");
+        let span = Span::locate(line, 1, Some("FREQ"), None);
+        assert_eq!(&line[span.start as usize..span.end as usize], "WEEKLY");
+    }
+
+    #[test]
+    fn locates_text_in_line() {
+        let line = "EXDATE;TZID=Europe/Nowhere:20120202T130000";
+        let span = Span::locate(line, 1, None, Some("Europe/Nowhere"));
+        assert_eq!(
+            &line[span.start as usize..span.end as usize],
+            "Europe/Nowhere"
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! Resolving `TZID` parameter values to a [`Tz`].
+//!
+//! Besides IANA names and UTC offsets, real-world input often contains Windows
+//! timezone names (`W. Europe Standard Time`), short Java-style ids (`PST`) or
+//! globally unique ids with a registry prefix (`/mozilla.org/20050126_1/America/New_York`).
+#[cfg(feature = "wasm")]
+use std::cell::RefCell;
+#[cfg(feature = "wasm")]
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::FixedOffset;
+
+use super::ParseError;
+use crate::Tz;
+
+/// Describes how a `TZID` value was resolved to a [`Tz`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TzidMapping {
+    /// The value is a timezone name known to the IANA timezone database,
+    /// including its backward compatible links like `US/Eastern` or `EST5EDT`.
+    Iana,
+    /// The value is an offset from UTC or GMT, like `UTC+0100`.
+    FixedOffset,
+    /// The value is a Windows timezone name, mapped with the CLDR `windowsZones` table.
+    Windows,
+    /// The value is a short alias, like `PST`, which is not part of the IANA database.
+    Alias,
+    /// The value is a globally unique id, like `/mozilla.org/20050126_1/America/New_York`,
+    /// of which the registry prefix was removed.
+    Prefixed,
+}
+
+impl Display for TzidMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Iana => "IANA",
+            Self::FixedOffset => "fixed offset",
+            Self::Windows => "Windows",
+            Self::Alias => "alias",
+            Self::Prefixed => "prefixed",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Windows timezone names and the IANA timezone of the `001` territory,
+/// as defined by the CLDR `windowsZones` supplemental data.
+static WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu"),
+    ("Marquesas Standard Time", "Pacific/Marquesas"),
+    ("Alaskan Standard Time", "America/Anchorage"),
+    ("UTC-09", "Etc/GMT+9"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana"),
+    ("UTC-08", "Etc/GMT+8"),
+    ("Pacific Standard Time", "America/Los_Angeles"),
+    ("US Mountain Standard Time", "America/Phoenix"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver"),
+    ("Yukon Standard Time", "America/Whitehorse"),
+    ("Central America Standard Time", "America/Guatemala"),
+    ("Central Standard Time", "America/Chicago"),
+    ("Easter Island Standard Time", "Pacific/Easter"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City"),
+    ("Canada Central Standard Time", "America/Regina"),
+    ("SA Pacific Standard Time", "America/Bogota"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York"),
+    ("Haiti Standard Time", "America/Port-au-Prince"),
+    ("Cuba Standard Time", "America/Havana"),
+    ("US Eastern Standard Time", "America/Indiana/Indianapolis"),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Central Brazilian Standard Time", "America/Cuiaba"),
+    ("SA Western Standard Time", "America/La_Paz"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("Tocantins Standard Time", "America/Araguaina"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne"),
+    ("Argentina Standard Time", "America/Argentina/Buenos_Aires"),
+    ("Greenland Standard Time", "America/Nuuk"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("Magallanes Standard Time", "America/Punta_Arenas"),
+    ("Saint Pierre Standard Time", "America/Miquelon"),
+    ("Bahia Standard Time", "America/Bahia"),
+    ("UTC-02", "Etc/GMT+2"),
+    ("Mid-Atlantic Standard Time", "Etc/GMT+2"),
+    ("Azores Standard Time", "Atlantic/Azores"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde"),
+    ("UTC", "Etc/UTC"),
+    ("GMT Standard Time", "Europe/London"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik"),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome"),
+    ("Morocco Standard Time", "Africa/Casablanca"),
+    ("W. Europe Standard Time", "Europe/Berlin"),
+    ("Central Europe Standard Time", "Europe/Budapest"),
+    ("Romance Standard Time", "Europe/Paris"),
+    ("Central European Standard Time", "Europe/Warsaw"),
+    ("W. Central Africa Standard Time", "Africa/Lagos"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("West Bank Standard Time", "Asia/Hebron"),
+    ("South Africa Standard Time", "Africa/Johannesburg"),
+    ("FLE Standard Time", "Europe/Kiev"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Sudan Standard Time", "Africa/Juba"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Sudan Standard Time", "Africa/Khartoum"),
+    ("Libya Standard Time", "Africa/Tripoli"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow"),
+    ("E. Africa Standard Time", "Africa/Nairobi"),
+    ("Volgograd Standard Time", "Europe/Volgograd"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai"),
+    ("Astrakhan Standard Time", "Europe/Astrakhan"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius"),
+    ("Saratov Standard Time", "Europe/Saratov"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda"),
+    ("India Standard Time", "Asia/Kolkata"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Kathmandu"),
+    ("Central Asia Standard Time", "Asia/Almaty"),
+    ("Bangladesh Standard Time", "Asia/Dhaka"),
+    ("Omsk Standard Time", "Asia/Omsk"),
+    ("Myanmar Standard Time", "Asia/Yangon"),
+    ("SE Asia Standard Time", "Asia/Bangkok"),
+    ("Altai Standard Time", "Asia/Barnaul"),
+    ("W. Mongolia Standard Time", "Asia/Hovd"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("Tomsk Standard Time", "Asia/Tomsk"),
+    ("China Standard Time", "Asia/Shanghai"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Singapore Standard Time", "Asia/Singapore"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar"),
+    ("Aus Central W. Standard Time", "Australia/Eucla"),
+    ("Transbaikal Standard Time", "Asia/Chita"),
+    ("Tokyo Standard Time", "Asia/Tokyo"),
+    ("North Korea Standard Time", "Asia/Pyongyang"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane"),
+    ("AUS Eastern Standard Time", "Australia/Sydney"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby"),
+    ("Tasmania Standard Time", "Australia/Hobart"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok"),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe"),
+    ("Bougainville Standard Time", "Pacific/Bougainville"),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk"),
+    ("Magadan Standard Time", "Asia/Magadan"),
+    ("Norfolk Standard Time", "Pacific/Norfolk"),
+    ("Sakhalin Standard Time", "Asia/Sakhalin"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal"),
+    ("Russia Time Zone 11", "Asia/Kamchatka"),
+    ("New Zealand Standard Time", "Pacific/Auckland"),
+    ("UTC+12", "Etc/GMT-12"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Chatham Islands Standard Time", "Pacific/Chatham"),
+    ("UTC+13", "Etc/GMT-13"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+    ("Line Islands Standard Time", "Pacific/Kiritimati"),
+];
+
+/// Short timezone ids which are not part of the IANA database, but are commonly
+/// emitted by calendar software. Based on the Java `ZoneId.SHORT_IDS` table and
+/// the US abbreviations, without the ones in [`AMBIGUOUS_ALIASES`].
+static TIMEZONE_ALIASES: &[(&str, &str)] = &[
+    ("ACT", "Australia/Darwin"),
+    ("AET", "Australia/Sydney"),
+    ("AGT", "America/Argentina/Buenos_Aires"),
+    ("BET", "America/Sao_Paulo"),
+    ("CAT", "Africa/Harare"),
+    ("CNT", "America/St_Johns"),
+    ("CDT", "America/Chicago"),
+    ("CTT", "Asia/Shanghai"),
+    ("EAT", "Africa/Addis_Ababa"),
+    ("EDT", "America/New_York"),
+    ("IET", "America/Indiana/Indianapolis"),
+    ("JST", "Asia/Tokyo"),
+    ("MDT", "America/Denver"),
+    ("MIT", "Pacific/Apia"),
+    ("NET", "Asia/Yerevan"),
+    ("PDT", "America/Los_Angeles"),
+    ("PLT", "Asia/Karachi"),
+    ("PNT", "America/Phoenix"),
+    ("PRT", "America/Puerto_Rico"),
+    ("PST", "America/Los_Angeles"),
+    ("VST", "Asia/Ho_Chi_Minh"),
+    ("AKST", "America/Anchorage"),
+    ("AKDT", "America/Anchorage"),
+    ("HST", "Pacific/Honolulu"),
+    ("AEST", "Australia/Sydney"),
+    ("AEDT", "Australia/Sydney"),
+];
+
+/// Abbreviations which are used for several unrelated timezones, e.g. `IST` for
+/// India, Irish and Israel Standard Time, or whose Java `SHORT_IDS` mapping differs
+/// from their common meaning, e.g. `AST` for Atlantic Standard Time and not Alaska.
+/// These are rejected rather than guessed.
+static AMBIGUOUS_ALIASES: &[&str] = &["ART", "AST", "BST", "CST", "ECT", "IST", "NST", "SST"];
+
+/// Attempts to convert a `TZID` value to a [`Tz`], and returns which mapping was used.
+///
+/// The lookups are done in the following order:
+/// 1. An offset from UTC or GMT, like `UTC+0100`.
+/// 2. An IANA timezone name, like `Europe/Berlin` or `US/Eastern`.
+/// 3. A Windows timezone name, like `W. Europe Standard Time`.
+/// 4. A short alias, like `PST`. Ambiguous ones, like `IST`, are rejected.
+/// 5. A globally unique id, like `/mozilla.org/20050126_1/America/New_York`.
+pub(crate) fn resolve_tzid(tzid: &str) -> Result<(Tz, TzidMapping), ParseError> {
+    #[cfg(feature = "wasm")]
+    if let Some(resolved) = TZID_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let cache = cache.as_mut()?;
+        Some(
+            cache
+                .entry(tzid.into())
+                .or_insert_with(|| resolve_uncached(tzid))
+                .clone(),
+        )
+    }) {
+        return resolved;
+    }
+    resolve_uncached(tzid)
+}
+
+#[cfg(feature = "wasm")]
+type ResolvedTzids = HashMap<String, Result<(Tz, TzidMapping), ParseError>>;
+
+#[cfg(feature = "wasm")]
+thread_local! {
+    /// The `TZID` values resolved while [`with_tzid_cache`] runs.
+    static TZID_CACHE: RefCell<Option<ResolvedTzids>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` while keeping the `TZID` values resolved by [`resolve_tzid`], so that
+/// a timezone which occurs in many rule sets is only resolved once.
+#[cfg(feature = "wasm")]
+pub(crate) fn with_tzid_cache<T>(f: impl FnOnce() -> T) -> T {
+    TZID_CACHE.with(|cache| *cache.borrow_mut() = Some(HashMap::new()));
+    let result = f();
+    TZID_CACHE.with(|cache| *cache.borrow_mut() = None);
+    result
+}
+
+fn resolve_uncached(tzid: &str) -> Result<(Tz, TzidMapping), ParseError> {
+    let tzid = tzid.trim();
+
+    if tzid.len() > 3 && (tzid.starts_with("GMT") || tzid.starts_with("UTC")) {
+        // Remove "UTC" or "GMT"
+        if let Ok(offset) = FixedOffset::from_str(&tzid[3..]) {
+            return Ok((Tz::FixedOffset(offset), TzidMapping::FixedOffset));
+        }
+    }
+
+    if let Ok(tz) = chrono_tz::Tz::from_str(tzid) {
+        return Ok((Tz::Tz(tz), TzidMapping::Iana));
+    }
+
+    if let Some(tz) = lookup(WINDOWS_ZONES, tzid) {
+        return Ok((Tz::Tz(tz), TzidMapping::Windows));
+    }
+
+    if AMBIGUOUS_ALIASES
+        .iter()
+        .any(|alias| alias.eq_ignore_ascii_case(tzid))
+    {
+        return Err(ParseError::AmbiguousTimezone(tzid.into()));
+    }
+
+    if let Some(tz) = lookup(TIMEZONE_ALIASES, tzid) {
+        return Ok((Tz::Tz(tz), TzidMapping::Alias));
+    }
+
+    // https://icalendar.org/iCalendar-RFC-5545/3-2-19-time-zone-identifier.html
+    // A leading solidus indicates a globally unique id, the registry specific prefix
+    // is removed until a known IANA name remains.
+    if let Some(unique_id) = tzid.strip_prefix('/') {
+        let mut candidate = Some(unique_id);
+        while let Some(name) = candidate {
+            if let Ok(tz) = chrono_tz::Tz::from_str(name) {
+                return Ok((Tz::Tz(tz), TzidMapping::Prefixed));
+            }
+            candidate = name.split_once('/').map(|(_, suffix)| suffix);
+        }
+    }
+
+    Err(ParseError::InvalidTimezone(tzid.into()))
+}
+
+/// Looks up `name` in `table`. The IANA names are only resolved here, so that zones
+/// left out of the build by the `tz-subset` feature are skipped.
+fn lookup(table: &[(&str, &str)], name: &str) -> Option<chrono_tz::Tz> {
+    table
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .and_then(|(_, iana_name)| chrono_tz::Tz::from_str(iana_name).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn resolves_iana_names_and_links() {
+        let tests = [
+            ("Europe/Berlin", Tz::Europe__Berlin),
+            ("US/Eastern", Tz::US__Eastern),
+            ("EST5EDT", Tz::EST5EDT),
+            ("Etc/GMT+5", Tz::Etc__GMTPlus5),
+            ("UTC", Tz::UTC),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(resolve_tzid(input), Ok((expected, TzidMapping::Iana)));
+        }
+    }
+
+    #[test]
+    fn resolves_fixed_offsets() {
+        let tests = [("UTC+0100", 3600), ("GMT-05:30", -19800)];
+        for (input, expected_offset) in tests {
+            let (tz, mapping) = resolve_tzid(input).unwrap();
+            assert_eq!(mapping, TzidMapping::FixedOffset);
+            assert!(
+                matches!(tz, Tz::FixedOffset(offset) if offset.local_minus_utc() == expected_offset)
+            );
+        }
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn resolves_windows_zone_names() {
+        let tests = [
+            ("W. Europe Standard Time", Tz::Europe__Berlin),
+            ("GMT Standard Time", Tz::Europe__London),
+            ("Eastern Standard Time", Tz::America__New_York),
+            ("Pacific Standard Time (Mexico)", Tz::America__Tijuana),
+            ("AUS Eastern Standard Time", Tz::Australia__Sydney),
+            ("india standard time", Tz::Asia__Kolkata),
+            ("UTC-11", Tz::Etc__GMTPlus11),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(resolve_tzid(input), Ok((expected, TzidMapping::Windows)));
+        }
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn table_names_are_iana_names() {
+        for (name, iana_name) in WINDOWS_ZONES.iter().chain(TIMEZONE_ALIASES) {
+            assert!(chrono_tz::Tz::from_str(iana_name).is_ok(), "{name}");
+        }
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn resolves_aliases() {
+        let tests = [
+            ("PST", Tz::America__Los_Angeles),
+            ("JST", Tz::Asia__Tokyo),
+            ("cdt", Tz::America__Chicago),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(resolve_tzid(input), Ok((expected, TzidMapping::Alias)));
+        }
+    }
+
+    #[test]
+    fn rejects_ambiguous_aliases() {
+        for input in [
+            "BST", "IST", "ist", "AST", "CST", "SST", "ART", "NST", "ECT",
+        ] {
+            assert_eq!(
+                resolve_tzid(input),
+                Err(ParseError::AmbiguousTimezone(input.into()))
+            );
+        }
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn resolves_prefixed_unique_ids() {
+        let tests = [
+            (
+                "/mozilla.org/20050126_1/America/New_York",
+                Tz::America__New_York,
+            ),
+            (
+                "/softwarestudio.org/Olson_20011030_5/Europe/Paris",
+                Tz::Europe__Paris,
+            ),
+            ("/Europe/Berlin", Tz::Europe__Berlin),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(resolve_tzid(input), Ok((expected, TzidMapping::Prefixed)));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_timezones() {
+        let tests = [
+            "",
+            "America/Everywhere",
+            "Nowhere Standard Time",
+            "/a/b/c",
+            "UTC+abc",
+        ];
+        for input in tests {
+            assert_eq!(
+                resolve_tzid(input),
+                Err(ParseError::InvalidTimezone(input.into()))
+            );
+        }
+    }
+
+    #[cfg(all(feature = "wasm", not(tz_subset)))]
+    #[test]
+    fn keeps_resolved_tzids_while_the_cache_is_enabled() {
+        let resolved = with_tzid_cache(|| {
+            let first = resolve_tzid("W. Europe Standard Time");
+            let cached = TZID_CACHE.with(|cache| cache.borrow().as_ref().map(HashMap::len));
+            assert_eq!(cached, Some(1));
+            assert_eq!(resolve_tzid("W. Europe Standard Time"), first);
+            assert_eq!(
+                resolve_tzid("IST"),
+                Err(ParseError::AmbiguousTimezone("IST".into()))
+            );
+            first
+        });
+        assert_eq!(resolved, Ok((Tz::Europe__Berlin, TzidMapping::Windows)));
+        assert!(TZID_CACHE.with(|cache| cache.borrow().is_none()));
+    }
+}
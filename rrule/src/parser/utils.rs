@@ -27,9 +27,47 @@ pub(crate) fn parse_str_to_vec<T: FromStr + Ord + PartialEq + Copy, F: Fn(T) ->
     Ok(parsed_vals)
 }
 
+/// Whether `name` is an experimental name, like `X-APPLE-TRAVEL-ADVISORY-BEHAVIOR`,
+/// which is kept as is instead of being interpreted.
+pub(crate) fn is_extension_name(name: &str) -> bool {
+    name.len() > 2 && name[..2].eq_ignore_ascii_case("X-")
+}
+
+/// Returns the position of the first `separator` in `text` which is not inside a
+/// quoted (`DQUOTE`) parameter value, like the `:` in `X-LOC="geo:1,2"`.
+pub(crate) fn find_unquoted(text: &str, separator: char) -> Option<usize> {
+    let mut quoted = false;
+    text.char_indices().find_map(|(index, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == separator && !quoted).then_some(index)
+    })
+}
+
+/// Splits `text` at every `separator` which is not inside a quoted (`DQUOTE`)
+/// parameter value.
+pub(crate) fn split_unquoted(text: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    text.split(move |c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == separator && !quoted
+    })
+}
+
+/// Removes the quotes (`DQUOTE`) around a parameter value, if it has them.
+pub(crate) fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_str_to_vec;
+    use super::{find_unquoted, parse_str_to_vec, split_unquoted, unquote};
 
     #[test]
     fn parses_str_to_vec() {
@@ -51,4 +89,17 @@ mod tests {
             assert_eq!(output, expected_output);
         }
     }
+
+    #[test]
+    fn separators_in_quoted_values_are_skipped() {
+        let line = r#"RDATE;X-LOC="geo:1,2;3":20210301T090000Z"#;
+        assert_eq!(find_unquoted(line, ':'), Some(line.len() - 17));
+        assert_eq!(find_unquoted("X-A=\"a:b\"", ':'), None);
+        assert_eq!(
+            split_unquoted(r#"X-A="a;b";TZID=UTC;X-B=",""#, ';').collect::<Vec<_>>(),
+            vec![r#"X-A="a;b""#, "TZID=UTC", r#"X-B=",""#]
+        );
+        assert_eq!(unquote(r#""Europe/Berlin""#), "Europe/Berlin");
+        assert_eq!(unquote("Europe/Berlin"), "Europe/Berlin");
+    }
 }
@@ -27,8 +27,8 @@ pub fn test_recurring_rrule(
     let rrule_set = rrule
         .build(dt_start)
         .map_err(|e| match e {
-            RRuleError::ParserError(e) => e.to_string(),
-            RRuleError::ValidationError(e) => e.to_string(),
+            RRuleError::ParserError(e, _) => e.to_string(),
+            RRuleError::ValidationError(e, _) => e.to_string(),
             RRuleError::IterError(e) => e,
         })
         .unwrap();
@@ -1,4 +1,7 @@
-use crate::{tests::common::check_occurrences, RRuleSet};
+use crate::{
+    tests::common::{check_occurrences, ymd_hms},
+    DstPolicy, GapPolicy, OverlapPolicy, ParseError, RRuleError, RRuleSet,
+};
 
 #[test]
 fn daylight_savings_1() {
@@ -65,3 +68,258 @@ fn daylight_savings_2() {
         ],
     );
 }
+
+#[cfg(not(tz_subset))]
+/// A daylight saving time transition of a timezone, with the expected resolution
+/// of a local time inside of it for each policy.
+struct Transition {
+    tzid: &'static str,
+    /// Local date and time in the gap or overlap, like `20210328T023000`.
+    local: &'static str,
+    /// Same local time on the day before.
+    day_before: &'static str,
+    /// First occurrence of a daily rule after the transition day.
+    day_after: &'static str,
+    /// Expected resolution for `GapPolicy::ShiftForward` or `OverlapPolicy::Earlier`.
+    first: &'static str,
+    /// Expected resolution for `GapPolicy::NextValidTime` or `OverlapPolicy::Later`.
+    second: &'static str,
+}
+
+#[cfg(not(tz_subset))]
+const GAPS: [Transition; 4] = [
+    Transition {
+        tzid: "Europe/Berlin",
+        local: "20210328T023000",
+        day_before: "2021-03-27T02:30:00+01:00",
+        day_after: "2021-03-29T02:30:00+02:00",
+        first: "2021-03-28T03:30:00+02:00",
+        second: "2021-03-28T03:00:00+02:00",
+    },
+    Transition {
+        tzid: "America/New_York",
+        local: "20210314T021500",
+        day_before: "2021-03-13T02:15:00-05:00",
+        day_after: "2021-03-15T02:15:00-04:00",
+        first: "2021-03-14T03:15:00-04:00",
+        second: "2021-03-14T03:00:00-04:00",
+    },
+    Transition {
+        tzid: "Australia/Lord_Howe",
+        local: "20211003T021500",
+        day_before: "2021-10-02T02:15:00+10:30",
+        day_after: "2021-10-04T02:15:00+11:00",
+        first: "2021-10-03T02:45:00+11:00",
+        second: "2021-10-03T02:30:00+11:00",
+    },
+    Transition {
+        tzid: "America/Santiago",
+        local: "20210905T003000",
+        day_before: "2021-09-04T00:30:00-04:00",
+        day_after: "2021-09-06T00:30:00-03:00",
+        first: "2021-09-05T01:30:00-03:00",
+        second: "2021-09-05T01:00:00-03:00",
+    },
+];
+
+#[cfg(not(tz_subset))]
+const OVERLAPS: [Transition; 4] = [
+    Transition {
+        tzid: "Europe/Berlin",
+        local: "20211031T023000",
+        day_before: "2021-10-30T02:30:00+02:00",
+        day_after: "2021-11-01T02:30:00+01:00",
+        first: "2021-10-31T02:30:00+02:00",
+        second: "2021-10-31T02:30:00+01:00",
+    },
+    Transition {
+        tzid: "America/New_York",
+        local: "20211107T013000",
+        day_before: "2021-11-06T01:30:00-04:00",
+        day_after: "2021-11-08T01:30:00-05:00",
+        first: "2021-11-07T01:30:00-04:00",
+        second: "2021-11-07T01:30:00-05:00",
+    },
+    Transition {
+        tzid: "Australia/Lord_Howe",
+        local: "20210404T014500",
+        day_before: "2021-04-03T01:45:00+11:00",
+        day_after: "2021-04-05T01:45:00+10:30",
+        first: "2021-04-04T01:45:00+11:00",
+        second: "2021-04-04T01:45:00+10:30",
+    },
+    Transition {
+        tzid: "America/Santiago",
+        local: "20210403T233000",
+        day_before: "2021-04-02T23:30:00-03:00",
+        day_after: "2021-04-04T23:30:00-04:00",
+        first: "2021-04-03T23:30:00-03:00",
+        second: "2021-04-03T23:30:00-04:00",
+    },
+];
+
+#[cfg(not(tz_subset))]
+/// Parses a DTSTART at the local time of the transition, and iterates a daily rule
+/// which starts on the day before and crosses it.
+fn resolve(
+    transition: &Transition,
+    policy: DstPolicy,
+) -> (Result<String, RRuleError>, Vec<String>) {
+    let parse = |input: &str| {
+        RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+            .dst_policy(policy)
+            .set_from_string(input)
+    };
+    let parsed = parse(&format!(
+        "DTSTART;TZID={}:{}\nRRULE:FREQ=DAILY;COUNT=1",
+        transition.tzid, transition.local
+    ))
+    .map(|set| set.get_dt_start().to_rfc3339());
+
+    let day_before = chrono::DateTime::parse_from_rfc3339(transition.day_before)
+        .unwrap()
+        .format("%Y%m%dT%H%M%S");
+    let iterated = parse(&format!(
+        "DTSTART;TZID={}:{day_before}\nRRULE:FREQ=DAILY;COUNT=3",
+        transition.tzid
+    ))
+    .unwrap()
+    .all(u16::MAX)
+    .dates
+    .iter()
+    .map(chrono::DateTime::to_rfc3339)
+    .collect();
+
+    (parsed, iterated)
+}
+
+#[cfg(not(tz_subset))]
+/// The RFC 3339 form of the local time of the gap with the UTC offset in effect
+/// before it, which is the offset of the day before.
+fn with_offset_before(transition: &Transition) -> String {
+    let day_before = chrono::DateTime::parse_from_rfc3339(transition.day_before).unwrap();
+    chrono::NaiveDateTime::parse_from_str(transition.local, "%Y%m%dT%H%M%S")
+        .unwrap()
+        .and_local_timezone(*day_before.offset())
+        .unwrap()
+        .to_rfc3339()
+}
+
+#[cfg(not(tz_subset))]
+#[test]
+fn dst_policy_in_gaps() {
+    for transition in &GAPS {
+        let wall_clock = with_offset_before(transition);
+        // The wall-clock time is kept, but it is the same instant as when shifted forward.
+        assert_eq!(
+            chrono::DateTime::parse_from_rfc3339(&wall_clock),
+            chrono::DateTime::parse_from_rfc3339(transition.first),
+            "{}",
+            transition.tzid
+        );
+        // The expected resolution when parsing and when iterating.
+        for (gap, parsed_expected, iterated_expected) in [
+            (GapPolicy::Reject, None, Some(transition.first)),
+            (
+                GapPolicy::ShiftForward,
+                Some(transition.first),
+                Some(transition.first),
+            ),
+            (
+                GapPolicy::NextValidTime,
+                Some(transition.second),
+                Some(transition.second),
+            ),
+            (
+                GapPolicy::KeepWallClock,
+                Some(wall_clock.as_str()),
+                Some(wall_clock.as_str()),
+            ),
+            (GapPolicy::Skip, None, None),
+        ] {
+            // The overlap policy doesn't matter for gaps.
+            for overlap in [OverlapPolicy::Earlier, OverlapPolicy::Later] {
+                let (parsed, iterated) = resolve(transition, DstPolicy::new(gap, overlap));
+                let context = format!("{} {gap:?}", transition.tzid);
+                match parsed_expected {
+                    Some(expected) => assert_eq!(parsed.unwrap(), expected, "{context}"),
+                    None => assert!(parsed.is_err(), "{context}"),
+                }
+                match iterated_expected {
+                    Some(expected) => assert_eq!(
+                        iterated,
+                        [transition.day_before, expected, transition.day_after],
+                        "{context}"
+                    ),
+                    None => assert_eq!(
+                        iterated[..2],
+                        [transition.day_before, transition.day_after],
+                        "{context}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(tz_subset))]
+#[test]
+fn dst_policy_in_overlaps() {
+    for transition in &OVERLAPS {
+        for (overlap, expected) in [
+            (OverlapPolicy::Earlier, Some(transition.first)),
+            (OverlapPolicy::Later, Some(transition.second)),
+            (OverlapPolicy::Skip, None),
+        ] {
+            // The gap policy doesn't matter for overlaps.
+            for gap in [GapPolicy::ShiftForward, GapPolicy::Skip] {
+                let (parsed, iterated) = resolve(transition, DstPolicy::new(gap, overlap));
+                let context = format!("{} {overlap:?}", transition.tzid);
+                match expected {
+                    Some(expected) => {
+                        assert_eq!(parsed.unwrap(), expected, "{context}");
+                        assert_eq!(
+                            iterated,
+                            [transition.day_before, expected, transition.day_after],
+                            "{context}"
+                        );
+                    }
+                    None => {
+                        assert!(parsed.is_err(), "{context}");
+                        assert_eq!(iterated[..2], [transition.day_before, transition.day_after]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn default_dst_policy_rejects_parsed_dates_in_gaps() {
+    assert_eq!(
+        RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0)).get_dst_policy(),
+        DstPolicy::new(GapPolicy::Reject, OverlapPolicy::Earlier)
+    );
+    let err = "DTSTART;TZID=Europe/Berlin:20210328T023000\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse::<RRuleSet>()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        RRuleError::ParserError(ParseError::InvalidDateTimeInLocalTimezone { .. }, _)
+    ));
+
+    // Occurrences in a gap are still shifted forward.
+    let dates = "DTSTART;TZID=Europe/Berlin:20210327T023000\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse::<RRuleSet>()
+        .unwrap()
+        .all(u16::MAX)
+        .dates;
+    check_occurrences(
+        &dates,
+        &[
+            "2021-03-27T02:30:00+01:00",
+            "2021-03-28T03:30:00+02:00",
+            "2021-03-29T02:30:00+02:00",
+        ],
+    );
+}
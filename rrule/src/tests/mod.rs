@@ -3,6 +3,7 @@
 mod common;
 mod datetime;
 mod daylight_saving;
+#[allow(clippy::bool_assert_comparison, clippy::zero_prefixed_literal)]
 mod regression;
 mod rfc_tests;
 mod rrule;
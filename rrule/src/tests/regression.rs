@@ -70,6 +70,23 @@ fn issue_111() {
     assert!(rrule_str.contains("WKST=SU"));
 }
 
+#[test]
+fn negative_month_days_are_kept_on_round_trip() {
+    let input = "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;COUNT=3;BYMONTHDAY=15,-1;BYHOUR=9;BYMINUTE=0;BYSECOND=0";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(rrule_set.to_string(), input);
+    let reparsed: RRuleSet = rrule_set.to_string().parse().unwrap();
+    assert_eq!(reparsed.all(10).dates, rrule_set.all(10).dates);
+
+    let rrule_set: RRuleSet = "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rrule_set.to_string(),
+        "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+    );
+}
+
 #[test]
 fn issue_119_return_correct_number_of_instances() {
     let dates = "DTSTART;TZID=Europe/Berlin:20240530T200000\nRDATE;TZID=Europe/Berlin:20240530T200000\nRRULE:FREQ=WEEKLY;COUNT=3;INTERVAL=1;BYDAY=WE"
@@ -509,6 +526,7 @@ fn gmt_tz() {
     common::check_occurrences(&dates, &["2020-11-01T01:00:00+00:00"]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_monthday() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRDATE;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=20"
@@ -528,6 +546,7 @@ fn yearly_by_monthday() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn monthly_by_monthday() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRDATE;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=MONTHLY;BYMONTHDAY=20"
@@ -548,6 +567,7 @@ fn monthly_by_monthday() {
 }
 
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_month() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRDATE;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=YEARLY;BYMONTH=4"
@@ -567,6 +587,7 @@ fn yearly_by_month() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_monthday_multiple() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRDATE;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=20,30"
@@ -586,6 +607,7 @@ fn yearly_by_monthday_multiple() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_month_multiple() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRDATE;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=YEARLY;BYMONTH=4,6"
@@ -605,6 +627,7 @@ fn yearly_by_month_multiple() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_monthday_and_weekday() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20210420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=1,3;BYDAY=TU,TH;COUNT=5"
@@ -624,6 +647,7 @@ fn yearly_by_monthday_and_weekday() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_monthday_and_weekday_2() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20240420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=1,3;BYDAY=TU,TH;COUNT=5"
@@ -644,6 +668,7 @@ fn yearly_by_monthday_and_weekday_2() {
 }
 
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_setpos() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20240420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=20;BYSETPOS=3,-3"
@@ -657,6 +682,7 @@ fn yearly_by_setpos() {
     assert_eq!(dates.len(), 0, "List sizes don't match");
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_setpos_2() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20240420T080000\nRRULE:FREQ=YEARLY;BYMONTHDAY=20,21,22,23;BYSETPOS=2,-2"
@@ -676,6 +702,7 @@ fn yearly_by_setpos_2() {
     ]);
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn yearly_by_setpos_3() {
     let rrule_set = "DTSTART;TZID=Asia/Kolkata:20240420T080000\nRRULE:FREQ=MONTHLY;COUNT=3;BYDAY=MO;BYSETPOS=-1"
@@ -1,7 +1,5 @@
-use crate::core::Tz;
 use crate::tests::common::{test_recurring_rrule, ymd_hms};
 use crate::{Frequency, NWeekday, RRule, RRuleSet, Weekday};
-use chrono::{Datelike, TimeZone};
 
 #[test]
 fn yearly() {
@@ -3775,9 +3773,12 @@ fn week_start_interval_su() {
     );
 }
 
+#[cfg(not(tz_subset))]
 #[test]
 fn test_timezones_weekly() {
+    use crate::core::Tz;
     use chrono::Weekday::Sat;
+    use chrono::{Datelike, TimeZone};
 
     const LOS_ANGELES: Tz = Tz::America__Los_Angeles;
     const NEW_YORK: Tz = Tz::America__New_York;
@@ -1,5 +1,10 @@
 use crate::tests::common::{check_occurrences, test_recurring_rrule_set, ymd_hms};
-use crate::{Frequency, NWeekday, RRule, RRuleSet, Weekday};
+use crate::{
+    CancellationToken, Deadline, Frequency, LimitReason, NWeekday, RRule, RRuleError, RRuleSet,
+    Severity, Span, Tz, Unvalidated, ValidationError, ValidatorLimits, Weekday,
+};
+use chrono::TimeZone;
+use std::time::Duration;
 
 #[test]
 #[cfg(feature = "exrule")]
@@ -658,3 +663,687 @@ fn yearly_with_interval_2() {
         &[ymd_hms(1960, 1, 1, 9, 0, 0), ymd_hms(1962, 1, 1, 9, 0, 0)],
     );
 }
+
+#[test]
+fn windows_and_prefixed_tzid() {
+    let dates = "DTSTART;TZID=W. Europe Standard Time:20210328T013000\n\
+        RRULE:FREQ=DAILY;COUNT=2\n\
+        RDATE;TZID=/mozilla.org/20050126_1/America/New_York:20210330T090000"
+        .parse::<RRuleSet>()
+        .unwrap()
+        .all(u16::MAX)
+        .dates;
+    check_occurrences(
+        &dates,
+        &[
+            "2021-03-28T01:30:00+01:00",
+            "2021-03-29T01:30:00+02:00",
+            "2021-03-30T09:00:00-04:00",
+        ],
+    );
+}
+
+#[test]
+fn display_keeps_timezone_of_rdates_and_exdates() {
+    let input = "DTSTART;TZID=Europe/Stockholm:20210301T090000\n\
+        RRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0\n\
+        RDATE;VALUE=DATE-TIME;TZID=Europe/Stockholm:20210310T090000,20210311T090000\n\
+        RDATE;VALUE=DATE-TIME:20210312T080000Z\n\
+        RDATE;VALUE=DATE:20210313\n\
+        EXDATE;VALUE=DATE-TIME;TZID=UTC+0530:20210302T133000";
+    let set: RRuleSet = input.parse().unwrap();
+    assert_eq!(set.to_string(), input);
+
+    let dates = set.all(u16::MAX).dates;
+    check_occurrences(
+        &dates,
+        &[
+            "2021-03-01T09:00:00+01:00",
+            "2021-03-03T09:00:00+01:00",
+            "2021-03-10T09:00:00+01:00",
+            "2021-03-11T09:00:00+01:00",
+            "2021-03-12T08:00:00+00:00",
+            &Tz::LOCAL
+                .with_ymd_and_hms(2021, 3, 13, 0, 0, 0)
+                .unwrap()
+                .to_rfc3339(),
+        ],
+    );
+}
+
+#[test]
+fn value_type_is_kept_for_each_rdate_and_exdate() {
+    let input = "DTSTART:20210301T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0\n\
+        RDATE;VALUE=DATE:20210313";
+    let set: RRuleSet = input.parse().unwrap();
+    // The same instant, added as a `DATE-TIME`.
+    let date = set.get_rdate()[0];
+    let mut set = set.rdate(date).exdate(date);
+    assert_eq!(
+        set.to_string(),
+        format!(
+            "{input}\n\
+            RDATE;VALUE=DATE-TIME:20210313T000000\n\
+            EXDATE;VALUE=DATE-TIME:20210313T000000"
+        )
+    );
+
+    assert!(set.remove_rdate(&date));
+    assert_eq!(
+        set.to_string(),
+        "DTSTART:20210301T090000Z\n\
+        RRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0\n\
+        EXDATE;VALUE=DATE-TIME:20210313T000000"
+    );
+}
+
+#[cfg(not(tz_subset))]
+#[test]
+fn display_roundtrips_rdates_and_exdates_in_all_timezone_kinds() {
+    use chrono::{DateTime, FixedOffset};
+
+    let timezones = [
+        Tz::LOCAL,
+        Tz::UTC,
+        Tz::Europe__Stockholm,
+        Tz::America__New_York,
+        Tz::Australia__Lord_Howe,
+        Tz::FixedOffset(FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()),
+        Tz::FixedOffset(FixedOffset::west_opt(8 * 3600).unwrap()),
+    ];
+    // A small xorshift generator keeps the cases reproducible.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |bound: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        u32::try_from(state % bound).unwrap()
+    };
+
+    let mut random_date = || loop {
+        let tz = timezones[next(7) as usize];
+        let date = tz
+            .with_ymd_and_hms(
+                1970 + i32::try_from(next(100)).unwrap(),
+                1 + next(12),
+                1 + next(28),
+                next(24),
+                next(60),
+                next(60),
+            )
+            .single();
+        if let Some(date) = date {
+            return date;
+        }
+    };
+
+    for case in 0..200 {
+        let dt_start = random_date();
+        let mut set = RRuleSet::new(dt_start).rrule(
+            RRule::new(Frequency::Daily)
+                .count(2)
+                .validate(dt_start)
+                .unwrap(),
+        );
+        for _ in 0..=case % 4 {
+            set = set.rdate(random_date());
+        }
+        for _ in 0..case % 3 {
+            set = set.exdate(random_date());
+        }
+
+        let output = set.to_string();
+        let parsed: RRuleSet = output.parse().unwrap();
+        assert_eq!(parsed.to_string(), output);
+        // Dates are grouped by timezone, so only their order may change.
+        let key = |dates: &[DateTime<Tz>]| {
+            let mut dates = dates
+                .iter()
+                .map(|date| (date.timestamp(), date.timezone().name()))
+                .collect::<Vec<_>>();
+            dates.sort();
+            dates
+        };
+        assert_eq!(
+            key(&[*set.get_dt_start()]),
+            key(&[*parsed.get_dt_start()]),
+            "{output}"
+        );
+        assert_eq!(key(set.get_rdate()), key(parsed.get_rdate()), "{output}");
+        assert_eq!(key(set.get_exdate()), key(parsed.get_exdate()), "{output}");
+    }
+}
+
+#[cfg(not(tz_subset))]
+#[test]
+fn floating_dates_keep_wall_clock_time() {
+    let input = "DTSTART:20210326T090000\nRRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0\nRDATE;VALUE=DATE-TIME:20210401T120000";
+    let set = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .floating_timezone(Tz::FLOATING)
+        .set_from_string(input)
+        .unwrap();
+    assert_eq!(set.get_dt_start().timezone(), Tz::FLOATING);
+    assert_eq!(set.to_string(), input);
+
+    let dates = set.all(u16::MAX).dates;
+    assert!(dates.iter().all(|date| date.timezone().is_floating()));
+    let berlin = dates
+        .iter()
+        .map(|date| {
+            Tz::Europe__Berlin
+                .resolve_floating(date)
+                .single()
+                .unwrap()
+                .to_rfc3339()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        berlin,
+        [
+            "2021-03-26T09:00:00+01:00",
+            "2021-03-27T09:00:00+01:00",
+            "2021-03-28T09:00:00+02:00",
+            "2021-04-01T12:00:00+02:00",
+        ]
+    );
+}
+
+#[test]
+fn floating_until_is_read_in_dtstart_timezone() {
+    let dates = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .floating_timezone(Tz::FLOATING)
+        .set_from_string(
+            "DTSTART;TZID=Europe/Berlin:20210326T090000\nRRULE:FREQ=DAILY;UNTIL=20210328T090000",
+        )
+        .unwrap()
+        .all(u16::MAX)
+        .dates;
+    check_occurrences(
+        &dates,
+        &[
+            "2021-03-26T09:00:00+01:00",
+            "2021-03-27T09:00:00+01:00",
+            "2021-03-28T09:00:00+02:00",
+        ],
+    );
+}
+
+#[test]
+fn limits_are_used_to_validate_parsed_rules() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;INTERVAL=500";
+    assert!(input.parse::<RRuleSet>().is_ok());
+
+    let res = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .limits(ValidatorLimits::STRICT)
+        .set_from_string(input);
+    assert_eq!(
+        res.unwrap_err(),
+        RRuleError::ValidationError(
+            ValidationError::TooBigInterval(500),
+            Some(Span::new(2, 27..30))
+        )
+    );
+}
+
+#[test]
+fn limit_reason_is_reported() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30"
+        .parse()
+        .unwrap();
+    let result = rrule_set
+        .clone()
+        .limits(ValidatorLimits::DEFAULT.max_iterations(100))
+        .all(10);
+    assert!(result.dates.is_empty());
+    assert!(result.limited);
+    assert_eq!(result.limit_reason, Some(LimitReason::MaxIterations));
+
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY"
+        .parse()
+        .unwrap();
+    let result = rrule_set
+        .clone()
+        .limits(ValidatorLimits::DEFAULT.max_results(5))
+        .all(10);
+    assert_eq!(result.dates.len(), 5);
+    assert_eq!(result.limit_reason, Some(LimitReason::MaxResults));
+
+    let result = rrule_set
+        .limits(ValidatorLimits::DEFAULT.year_range(2000..=2021))
+        .all(1000);
+    assert_eq!(result.dates.len(), 365);
+    assert_eq!(result.limit_reason, Some(LimitReason::YearRange));
+
+    let result = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3"
+        .parse::<RRuleSet>()
+        .unwrap()
+        .all(10);
+    assert!(!result.limited);
+    assert_eq!(result.limit_reason, None);
+}
+
+#[test]
+fn year_range_is_only_widened_when_requested() {
+    let rrule_set: RRuleSet = "DTSTART:20000101T090000Z\nRRULE:FREQ=YEARLY;INTERVAL=1000"
+        .parse()
+        .unwrap();
+    assert_eq!(rrule_set.clone().into_iter().count(), 9);
+    let result = rrule_set.limits(ValidatorLimits::UNCHECKED).all(20);
+    assert_eq!(result.dates.len(), 20);
+
+    let dt_start = Tz::UTC.with_ymd_and_hms(12_000, 1, 1, 9, 0, 0).unwrap();
+    let rrule = "FREQ=DAILY".parse::<RRule<Unvalidated>>().unwrap();
+    assert!(matches!(
+        rrule.clone().validate(dt_start),
+        Err(RRuleError::ValidationError(
+            ValidationError::StartYearOutOfRange(12_000),
+            None
+        ))
+    ));
+    assert!(rrule
+        .validate_with_limits(dt_start, &ValidatorLimits::UNCHECKED)
+        .is_ok());
+}
+
+#[test]
+fn deadline_stops_unbounded_expansion() {
+    let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=SECONDLY"
+        .parse()
+        .unwrap();
+    let dates = rrule_set
+        .clone()
+        .deadline(Deadline::after(Duration::from_millis(20)))
+        .all_unchecked();
+    assert!(!dates.is_empty());
+
+    let result = rrule_set
+        .limits(ValidatorLimits::UNCHECKED)
+        .deadline(Deadline::after(Duration::from_millis(20)))
+        .all_within_limits();
+    assert!(result.limited);
+    assert_eq!(result.limit_reason, Some(LimitReason::DeadlineExceeded));
+}
+
+#[test]
+fn cancellation_token_stops_expansion_from_other_thread() {
+    let token = CancellationToken::new();
+    let rrule_set: RRuleSet =
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=SECONDLY;BYMONTH=2;BYMONTHDAY=30"
+            .parse()
+            .unwrap();
+    let rrule_set = rrule_set.cancellation_token(token.clone());
+
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        token.cancel();
+    });
+    let mut iter = rrule_set.into_iter();
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.limit_reason(), Some(LimitReason::Cancelled));
+    canceller.join().unwrap();
+}
+
+#[test]
+fn rules_that_never_occur_are_only_rejected_on_request() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=31;BYMONTH=4,6";
+    let result = input
+        .parse::<RRuleSet>()
+        .unwrap()
+        .limits(ValidatorLimits::DEFAULT.max_iterations(100))
+        .all(10);
+    assert!(result.dates.is_empty());
+    assert_eq!(result.limit_reason, Some(LimitReason::MaxIterations));
+
+    let err = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .reject_never_occurring()
+        .set_from_string(input)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "RRule validation error: The rule never occurs, because no day matches all of `BYMONTH`, `BYMONTHDAY`."
+    );
+}
+
+#[test]
+fn all_errors_are_collected_with_their_line_and_rule_part() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY;BYWEEKNO=0;BYYEARDAY=0\nRDATE:20210105T090000Z\nRRULE:FREQ=MONTHLY;BYDAY=+6MO;BYMINUTE=61\nRRULE:FREQ=MONTHLY;BYDAY=+6MO";
+    let errors = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .set_from_string_all_errors(input)
+        .unwrap_err();
+    let tags: Vec<_> = errors
+        .iter()
+        .map(|err| (err.line, err.rule_part()))
+        .collect();
+    assert_eq!(
+        tags,
+        vec![
+            (Some(2), Some("BYYEARDAY")),
+            (Some(2), Some("BYWEEKNO")),
+            (Some(4), Some("BYMINUTE")),
+            (Some(5), Some("BYDAY")),
+        ]
+    );
+    assert_eq!(
+        errors[2].to_string(),
+        "Line 4: RRule parsing error: `61` is not a valid BYMINUTE value. Expected a comma-separated list of values in range 0..=59, e.g. `0,15,30,45`"
+    );
+
+    // The first error is returned when stopping at the first one.
+    let err = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .set_from_string(input)
+        .unwrap_err();
+    assert_eq!(err, errors[0].error);
+}
+
+#[test]
+fn errors_have_spans_and_codes() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYSECOND=1;BYMINUTE=75";
+    let err = input.parse::<RRuleSet>().unwrap_err();
+    assert_eq!(err.code(), "parse.invalid_by_minute");
+    let errors = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .set_from_string_all_errors(input)
+        .unwrap_err();
+    assert_eq!(errors[0].error, err);
+    let span = errors[0].span.unwrap();
+    assert_eq!(span.line, 2);
+    let line = input.lines().nth(1).unwrap();
+    assert_eq!(&line[span.start as usize..span.end as usize], "75");
+    assert_eq!(err.span(), Some(span));
+
+    let errors = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .set_from_string_all_errors("RRULE:FREQ=YEARLY;BYWEEKNO=0")
+        .unwrap_err();
+    assert_eq!(errors[0].code(), "validation.invalid_field_value");
+    assert_eq!(
+        errors[0].span,
+        Some(Span {
+            line: 1,
+            start: 27,
+            end: 28
+        })
+    );
+
+    let err = "FREQ=DAILY;BYMINUTE=75"
+        .parse::<RRule<Unvalidated>>()
+        .unwrap_err();
+    assert_eq!(err.code(), "parse.invalid_by_minute");
+    assert_eq!(err.span(), Some(Span::new(1, 20..22)));
+}
+
+#[test]
+fn lenient_parse_repairs_input_and_reports_diagnostics() {
+    let input = "dtstart:20210101T090000Z\nRRULE:freq=DAILY;COUNT=2;\nRDATE;VALUE=PERIOD:20210301T090000Z/PT1H\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30\nRRULE:FREQ=DAILY;BYHOUR=24";
+    let (rrule_set, diagnostics) =
+        RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0)).set_from_string_lenient(input);
+    assert_eq!(
+        rrule_set.all(10).dates,
+        vec![
+            ymd_hms(2021, 1, 1, 9, 0, 0),
+            ymd_hms(2021, 1, 2, 9, 0, 0),
+            ymd_hms(2021, 3, 1, 9, 0, 0),
+        ]
+    );
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.severity,
+                diagnostic.error.line,
+                diagnostic.code(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Severity::Warning, Some(1), "parse.not_uppercase"),
+            (Severity::Warning, Some(2), "parse.not_uppercase"),
+            (Severity::Warning, Some(2), "parse.empty_part"),
+            (Severity::Warning, Some(3), "parse.unsupported_value_type"),
+            (Severity::Warning, Some(4), "validation.never_occurs"),
+            (Severity::Error, Some(5), "parse.invalid_by_hour"),
+        ]
+    );
+    let span = diagnostics[1].span().unwrap();
+    assert_eq!(
+        &input.lines().nth(1).unwrap()[span.start as usize..span.end as usize],
+        "freq"
+    );
+}
+
+#[test]
+fn lenient_parse_leaves_out_lines_it_cannot_repair() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2;COUNT=3\nRRULE:FREQ=DAILY;BYWEEKDAY=FR;COUNT=1;COUNT=1\nRDATE;VALUE=TEXT:20210301T090000Z";
+    let (rrule_set, diagnostics) =
+        RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0)).set_from_string_lenient(input);
+    assert_eq!(rrule_set.all(10).dates, vec![ymd_hms(2021, 1, 1, 9, 0, 0)]);
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.severity,
+                diagnostic.error.line,
+                diagnostic.code(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (Severity::Error, Some(2), "parse.duplicate_property"),
+            (Severity::Warning, Some(3), "parse.non_standard_name"),
+            (Severity::Warning, Some(3), "parse.duplicate_property"),
+            (Severity::Error, Some(4), "parse.unsupported_value_type"),
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "Warning: Line 3: `BYWEEKDAY` is not defined by RFC 5545, it is read as `BYDAY`."
+    );
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "Error: Line 2: RRule parsing error: Found duplicate property for `COUNT`, properties and parameters need to be unique."
+    );
+}
+
+#[cfg(not(tz_subset))]
+#[test]
+fn lenient_parse_reports_mapped_timezones() {
+    use crate::{ParseError, TzidMapping};
+
+    let input = "DTSTART;TZID=W. Europe Standard Time:20210101T090000\nRRULE:FREQ=DAILY;COUNT=2\nEXDATE;TZID=Europe/Berlin:20210102T090000";
+    let (rrule_set, diagnostics) =
+        RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0)).set_from_string_lenient(input);
+    assert_eq!(rrule_set.get_dt_start().timezone(), Tz::Europe__Berlin);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.code(), "parse.mapped_timezone");
+    assert_eq!(
+        diagnostic.error.error,
+        RRuleError::ParserError(
+            ParseError::MappedTimezone {
+                tzid: "W. Europe Standard Time".into(),
+                timezone: "Europe/Berlin".into(),
+                mapping: TzidMapping::Windows,
+            },
+            Some(Span::new(1, 13..36))
+        )
+    );
+    assert_eq!(diagnostic.span(), Some(Span::new(1, 13..36)));
+    assert_eq!(
+        diagnostic.to_string(),
+        "Warning: Line 1: `W. Europe Standard Time` is not an IANA timezone name, it is read as `Europe/Berlin` using the Windows mapping."
+    );
+}
+
+#[test]
+fn strict_mode_rejects_non_rfc_constructs() {
+    let rrule_set = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0)).strict();
+    assert!(rrule_set
+        .clone()
+        .set_from_string("DTSTART;TZID=Europe/Berlin:20210101T090000\nRRULE:FREQ=DAILY;COUNT=2")
+        .is_ok());
+
+    let errors = rrule_set
+        .set_from_string_all_errors("FREQ=DAILY;COUNT=2\nRRULE:FREQ=WEEKLY;BYWEEKDAY=MO;BYDAY=TU")
+        .unwrap_err();
+    let codes: Vec<_> = errors.iter().map(|err| (err.line, err.code())).collect();
+    assert_eq!(
+        codes,
+        vec![
+            (Some(1), "parse.missing_property"),
+            (Some(2), "parse.non_standard_name"),
+            (Some(2), "parse.duplicate_property"),
+        ]
+    );
+}
+
+#[test]
+fn extensions_are_kept_on_round_trip() {
+    let input = "DTSTART;X-SOURCE=import:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2;BYHOUR=9;BYMINUTE=0;BYSECOND=0;X-NAME=Some Value\nRDATE;VALUE=DATE-TIME;X-APPLE-STRUCTURED=loc:20210301T090000Z\nX-WR-TIMEZONE;X-PARAM=1:Europe/Berlin";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(
+        rrule_set.get_rrule()[0].get_extensions(),
+        [("X-NAME".to_string(), "Some Value".to_string())]
+    );
+    assert_eq!(
+        rrule_set.get_rdate_extensions(0),
+        [("X-APPLE-STRUCTURED".to_string(), "loc".to_string())]
+    );
+    assert_eq!(
+        rrule_set.get_extensions(),
+        [(
+            "X-WR-TIMEZONE;X-PARAM=1".to_string(),
+            "Europe/Berlin".to_string()
+        )]
+    );
+    assert_eq!(rrule_set.to_string(), input);
+    assert_eq!(rrule_set.all(10).dates.len(), 3);
+
+    let rrule_set = RRuleSet::new(ymd_hms(2021, 1, 1, 9, 0, 0))
+        .rrule(
+            RRule::new(Frequency::Daily)
+                .count(1)
+                .extension("X-ID", "42")
+                .validate(ymd_hms(2021, 1, 1, 9, 0, 0))
+                .unwrap(),
+        )
+        .extension("X-NOTE", "text");
+    assert_eq!(
+        rrule_set.to_string(),
+        "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=1;BYHOUR=9;BYMINUTE=0;BYSECOND=0;X-ID=42\nX-NOTE:text"
+    );
+}
+
+#[test]
+fn quoted_parameter_values_are_kept_on_round_trip() {
+    let input = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2;BYHOUR=9;BYMINUTE=0;BYSECOND=0\nRDATE;VALUE=DATE-TIME;X-APPLE-LOC=\"geo:1,2;3\":20210301T090000Z";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(rrule_set.get_rdate(), &vec![ymd_hms(2021, 3, 1, 9, 0, 0)]);
+    assert_eq!(
+        rrule_set.get_rdate_extensions(0),
+        [("X-APPLE-LOC".to_string(), "\"geo:1,2;3\"".to_string())]
+    );
+    assert_eq!(rrule_set.to_string(), input);
+}
+
+#[test]
+fn extensions_are_kept_for_each_rdate_and_exdate() {
+    let input = "DTSTART:20210101T090000Z\nRDATE;VALUE=DATE-TIME;X-A=2:20210301T090000Z\nEXDATE;VALUE=DATE-TIME;X-A=1:20210301T090000Z";
+    let rrule_set: RRuleSet = input.parse().unwrap();
+    assert_eq!(
+        rrule_set.get_rdate_extensions(0),
+        [("X-A".to_string(), "2".to_string())]
+    );
+    assert_eq!(
+        rrule_set.get_exdate_extensions(0),
+        [("X-A".to_string(), "1".to_string())]
+    );
+    assert_eq!(rrule_set.to_string(), input);
+
+    // Dates added later don't take the parameters of a parsed date at the same instant.
+    let rrule_set = rrule_set.rdate(ymd_hms(2021, 3, 1, 9, 0, 0));
+    assert!(rrule_set.get_rdate_extensions(1).is_empty());
+    assert_eq!(
+        rrule_set.to_string(),
+        "DTSTART:20210101T090000Z\nRDATE;VALUE=DATE-TIME;X-A=2:20210301T090000Z\nRDATE;VALUE=DATE-TIME:20210301T090000Z\nEXDATE;VALUE=DATE-TIME;X-A=1:20210301T090000Z"
+    );
+}
+
+#[test]
+fn rules_and_dates_can_be_edited_in_place() {
+    let mut rrule_set: RRuleSet = "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;COUNT=3;BYMONTHDAY=-1,15\nRRULE:FREQ=YEARLY;COUNT=2\nRDATE:20210201T120000Z,20210202T120000Z\nEXDATE:20210215T090000Z"
+        .parse()
+        .unwrap();
+    let dt_start = *rrule_set.get_dt_start();
+
+    // Converting back to the builder keeps the negative month days.
+    let rrule = rrule_set.get_rrule()[0].clone().into_builder();
+    assert_eq!(rrule.get_by_month_day(), [15, -1]);
+    let rrule = rrule.count(2).validate(dt_start).unwrap();
+    assert!(rrule_set.replace_rrule(0, rrule).is_some());
+    assert!(rrule_set
+        .replace_rrule(5, rrule_set.get_rrule()[1].clone())
+        .is_none());
+
+    assert!(rrule_set.remove_rdate(&ymd_hms(2021, 2, 1, 12, 0, 0)));
+    assert!(!rrule_set.remove_rdate(&ymd_hms(2021, 2, 1, 12, 0, 0)));
+    assert!(rrule_set.remove_exdate(&ymd_hms(2021, 2, 15, 9, 0, 0)));
+    assert_eq!(
+        rrule_set.clone().all(10).dates,
+        vec![
+            ymd_hms(2021, 1, 31, 9, 0, 0),
+            ymd_hms(2021, 2, 2, 12, 0, 0),
+            ymd_hms(2021, 2, 15, 9, 0, 0),
+            ymd_hms(2022, 1, 31, 9, 0, 0),
+        ]
+    );
+
+    // The rule parts derived from the start date follow it, explicit ones are kept.
+    rrule_set
+        .set_dt_start(ymd_hms(2021, 3, 10, 8, 0, 0))
+        .unwrap();
+    assert_eq!(
+        rrule_set.all(10).dates,
+        vec![
+            ymd_hms(2021, 2, 2, 12, 0, 0),
+            ymd_hms(2021, 3, 10, 8, 0, 0),
+            ymd_hms(2021, 3, 15, 8, 0, 0),
+            ymd_hms(2021, 3, 31, 8, 0, 0),
+            ymd_hms(2022, 3, 10, 8, 0, 0),
+        ]
+    );
+
+    // Rule parts which were given keep their value, even if it is the one of the start date.
+    let mut rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=9;COUNT=3"
+        .parse()
+        .unwrap();
+    rrule_set
+        .set_dt_start(ymd_hms(2021, 1, 1, 14, 0, 0))
+        .unwrap();
+    assert_eq!(
+        rrule_set.to_string(),
+        "DTSTART:20210101T140000Z\nRRULE:FREQ=DAILY;COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+    );
+    assert_eq!(
+        rrule_set.all(10).dates,
+        vec![
+            ymd_hms(2021, 1, 2, 9, 0, 0),
+            ymd_hms(2021, 1, 3, 9, 0, 0),
+            ymd_hms(2021, 1, 4, 9, 0, 0),
+        ]
+    );
+
+    // An invalid start date leaves the set unchanged.
+    let mut rrule_set = RRuleSet::new(ymd_hms(2000, 1, 1, 0, 0, 0))
+        .reject_never_occurring()
+        .set_from_string("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;UNTIL=20210110T090000Z")
+        .unwrap();
+    let before = rrule_set.clone();
+    assert!(rrule_set
+        .set_dt_start(ymd_hms(2021, 2, 1, 9, 0, 0))
+        .is_err());
+    assert_eq!(rrule_set, before);
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Datelike, NaiveDate};
+
+use crate::iter::matching_days;
+use crate::{RRule, Tz};
+
+use super::ValidationError;
+
+/// The Gregorian calendar repeats itself, including the weekdays, every 400 years.
+const CALENDAR_CYCLE_YEARS: i32 = 400;
+
+/// Checks that the rule can produce at least one occurrence.
+///
+/// Rules like `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30` are allowed by the RFC, but the
+/// iterator would search until it hits the validation limits before returning nothing.
+///
+/// A full calendar cycle is scanned for a day that passes all the `BYxxx` filters,
+/// which proves that the rule is empty when there is none. `INTERVAL` and `BYSETPOS`
+/// are not taken into account, so not every empty rule is detected.
+pub(crate) fn validate_not_empty(
+    rrule: &RRule,
+    dt_start: &DateTime<Tz>,
+) -> Result<(), ValidationError> {
+    // Easter doesn't follow the calendar cycle.
+    #[cfg(feature = "by-easter")]
+    if rrule.by_easter.is_some() {
+        return Ok(());
+    }
+    // The cycle can't be scanned near the end of the supported dates.
+    if dt_start.year() >= NaiveDate::MAX.year() - CALENDAR_CYCLE_YEARS {
+        return Ok(());
+    }
+
+    let last_year = dt_start.year() + CALENDAR_CYCLE_YEARS;
+    let Some(first_day) = matching_days(rrule, dt_start, last_year).next() else {
+        return Err(ValidationError::NeverOccurs {
+            reason: format!("no day matches all of {}", day_by_rules(rrule).join(", ")),
+        });
+    };
+
+    if let Some(until) = rrule.until {
+        let until_day = until.with_timezone(&dt_start.timezone()).date_naive();
+        if until_day < first_day {
+            return Err(ValidationError::NeverOccurs {
+                reason: format!(
+                    "`UNTIL` is `{}`, which is before the first day it can occur on (`{first_day}`)",
+                    until.to_rfc3339()
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the names of the rule parts which filter days.
+fn day_by_rules(rrule: &RRule) -> Vec<&'static str> {
+    [
+        ("`BYMONTH`", rrule.by_month.is_empty()),
+        ("`BYWEEKNO`", rrule.by_week_no.is_empty()),
+        ("`BYYEARDAY`", rrule.by_year_day.is_empty()),
+        (
+            "`BYMONTHDAY`",
+            rrule.by_month_day.is_empty() && rrule.by_n_month_day.is_empty(),
+        ),
+        ("`BYDAY`", rrule.by_weekday.is_empty()),
+    ]
+    .into_iter()
+    .filter(|(_, is_empty)| !is_empty)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Unvalidated;
+    use chrono::TimeZone;
+
+    fn validate(rule: &str, dt_start: DateTime<Tz>) -> Result<(), ValidationError> {
+        let rrule = rule
+            .parse::<RRule<Unvalidated>>()
+            .unwrap()
+            .validate(dt_start)
+            .unwrap();
+        validate_not_empty(&rrule, &dt_start)
+    }
+
+    #[test]
+    fn detects_rules_without_matching_day() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        for rule in [
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=MONTHLY;BYMONTHDAY=31;BYMONTH=4,6",
+            "FREQ=DAILY;BYMONTHDAY=-30;BYMONTH=2",
+            "FREQ=YEARLY;BYWEEKNO=20;BYMONTH=1",
+            "FREQ=YEARLY;BYWEEKNO=-1;BYMONTH=6",
+            "FREQ=YEARLY;BYYEARDAY=100;BYMONTH=1",
+            "FREQ=YEARLY;BYYEARDAY=-1;BYMONTH=11",
+            "FREQ=MONTHLY;BYDAY=5MO;BYMONTH=2;BYMONTHDAY=1",
+        ] {
+            let err = validate(rule, dt_start).unwrap_err();
+            assert!(
+                matches!(&err, ValidationError::NeverOccurs { reason } if reason.starts_with("no day matches")),
+                "{rule}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_rules_with_rare_matching_day() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        for rule in [
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29",
+            "FREQ=YEARLY;BYYEARDAY=366",
+            "FREQ=YEARLY;BYWEEKNO=53",
+            "FREQ=YEARLY;BYWEEKNO=1;BYMONTH=12;BYDAY=MO",
+            "FREQ=YEARLY;BYWEEKNO=53;BYMONTH=1",
+            "FREQ=MONTHLY;BYDAY=5MO;BYMONTH=2",
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;BYDAY=MO",
+            "FREQ=MONTHLY;BYMONTHDAY=31",
+        ] {
+            assert_eq!(validate(rule, dt_start), Ok(()), "{rule}");
+        }
+    }
+
+    #[test]
+    fn detects_until_before_first_matching_day() {
+        let dt_start = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap();
+        let err = validate(
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;UNTIL=20231231T000000Z",
+            dt_start,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ValidationError::NeverOccurs { .. }), "{err}");
+        assert_eq!(
+            validate(
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;UNTIL=20240301T000000Z",
+                dt_start
+            ),
+            Ok(())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::module_name_repetitions, missing_docs)]
 
 use thiserror::Error;
 
@@ -59,4 +59,58 @@ pub enum ValidationError {
         until_tz: String,
         expected: Vec<String>,
     },
+    /// The rule is valid, but can never produce an occurrence.
+    #[error("The rule never occurs, because {reason}.")]
+    NeverOccurs {
+        /// Why no occurrence is possible.
+        reason: String,
+    },
+}
+
+impl ValidationError {
+    /// Returns the name of the rule part the error concerns, e.g. `BYMONTH`,
+    /// or `None` if it concerns the rule as a whole.
+    #[must_use]
+    pub fn rule_part(&self) -> Option<&str> {
+        match self {
+            Self::BySetPosWithoutByRule => Some("BYSETPOS"),
+            Self::InvalidFieldValue { field, .. }
+            | Self::InvalidFieldValueRange { field, .. }
+            | Self::InvalidFieldValueRangeWithFreq { field, .. } => Some(field),
+            Self::InvalidByRuleAndFrequency { by_rule, .. } => Some(by_rule),
+            Self::UntilBeforeStart { .. } | Self::DtStartUntilMismatchTimezone { .. } => {
+                Some("UNTIL")
+            }
+            Self::TooBigInterval(_) => Some("INTERVAL"),
+            Self::StartYearOutOfRange(_) => Some("DTSTART"),
+            #[cfg(feature = "by-easter")]
+            Self::InvalidByRuleWithByEaster => Some("BYEASTER"),
+            Self::UnableToGenerateTimeset | Self::NeverOccurs { .. } => None,
+        }
+    }
+
+    /// Returns a stable, machine-readable code for the kind of error,
+    /// e.g. `validation.never_occurs`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BySetPosWithoutByRule => "validation.by_set_pos_without_by_rule",
+            Self::InvalidFieldValue { .. } => "validation.invalid_field_value",
+            Self::InvalidFieldValueRange { .. } => "validation.invalid_field_value_range",
+            Self::InvalidFieldValueRangeWithFreq { .. } => {
+                "validation.invalid_field_value_range_with_freq"
+            }
+            Self::InvalidByRuleAndFrequency { .. } => "validation.invalid_by_rule_and_frequency",
+            Self::UntilBeforeStart { .. } => "validation.until_before_start",
+            Self::TooBigInterval(_) => "validation.too_big_interval",
+            Self::StartYearOutOfRange(_) => "validation.start_year_out_of_range",
+            Self::UnableToGenerateTimeset => "validation.unable_to_generate_timeset",
+            #[cfg(feature = "by-easter")]
+            Self::InvalidByRuleWithByEaster => "validation.invalid_by_rule_with_by_easter",
+            Self::DtStartUntilMismatchTimezone { .. } => {
+                "validation.dt_start_until_mismatch_timezone"
+            }
+            Self::NeverOccurs { .. } => "validation.never_occurs",
+        }
+    }
 }
@@ -0,0 +1,159 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::Frequency;
+
+use super::validate_rrule::YEAR_RANGE;
+
+/// Limits which protect against rules that take too long to iterate,
+/// see 'validator limits' in the docs.
+///
+/// The limits are enforced by [`crate::RRule::validate_with_limits`], by
+/// [`crate::RRuleSet::all`] and by the iterator of a [`crate::RRuleSet`] that has its
+/// limits enabled with [`crate::RRuleSet::limit`] or [`crate::RRuleSet::limits`].
+///
+/// # Usage
+///
+/// ```
+/// use rrule::{Frequency, LimitReason, RRuleSet, ValidatorLimits};
+///
+/// let limits = ValidatorLimits::DEFAULT
+///     .year_range(2000..=2030)
+///     .max_interval(Frequency::Daily, 365);
+/// let result = "DTSTART:20210101T090000Z\nRRULE:FREQ=YEARLY"
+///     .parse::<RRuleSet>()
+///     .unwrap()
+///     .limits(limits)
+///     .all(100);
+/// assert_eq!(result.dates.len(), 10);
+/// assert_eq!(result.limit_reason, Some(LimitReason::YearRange));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidatorLimits {
+    min_year: i32,
+    max_year: i32,
+    /// Max interval per frequency, indexed by the value of [`Frequency`].
+    max_intervals: [u16; 7],
+    max_iterations: u32,
+    max_results: u32,
+}
+
+impl ValidatorLimits {
+    /// The limits used when nothing else is configured.
+    pub const DEFAULT: Self = Self {
+        min_year: -10_000,
+        max_year: 10_000,
+        max_intervals: [10_000, 1_000, 1_000, 10_000, 10_000, 10_000, 50_000],
+        max_iterations: 100_000,
+        max_results: u16::MAX as u32,
+    };
+    /// Tighter limits, meant for rules from untrusted input.
+    pub const STRICT: Self = Self {
+        min_year: 1_000,
+        max_year: 3_000,
+        max_intervals: [100, 1_200, 520, 3_650, 1_000, 1_000, 1_000],
+        max_iterations: 10_000,
+        max_results: 1_000,
+    };
+    /// Only the limits of the crate itself.
+    pub const UNCHECKED: Self = Self {
+        min_year: -262_000,
+        max_year: 262_000,
+        max_intervals: [u16::MAX; 7],
+        max_iterations: u32::MAX,
+        max_results: u32::MAX,
+    };
+    /// The checks done when no limits are enabled: any `INTERVAL`, but only the
+    /// years of [`YEAR_RANGE`].
+    pub(crate) const UNLIMITED: Self = Self::UNCHECKED.year_range(YEAR_RANGE);
+
+    /// Sets the range of years that `DTSTART` and the occurrences have to be in.
+    #[must_use]
+    pub const fn year_range(mut self, range: RangeInclusive<i32>) -> Self {
+        self.min_year = *range.start();
+        self.max_year = *range.end();
+        self
+    }
+
+    /// Sets the highest `INTERVAL` allowed for `freq`.
+    #[must_use]
+    pub const fn max_interval(mut self, freq: Frequency, interval: u16) -> Self {
+        self.max_intervals[freq as usize] = interval;
+        self
+    }
+
+    /// Sets the number of iterations after which the search for the next
+    /// occurrence is given up.
+    #[must_use]
+    pub const fn max_iterations(mut self, iterations: u32) -> Self {
+        self.max_iterations = iterations;
+        self
+    }
+
+    /// Sets the highest number of occurrences that [`crate::RRuleSet::all`] returns.
+    #[must_use]
+    pub const fn max_results(mut self, results: u32) -> Self {
+        self.max_results = results;
+        self
+    }
+
+    /// Returns the range of years that `DTSTART` and the occurrences have to be in.
+    #[must_use]
+    pub fn get_year_range(&self) -> RangeInclusive<i32> {
+        self.min_year..=self.max_year
+    }
+
+    /// Returns the highest `INTERVAL` allowed for `freq`.
+    #[must_use]
+    pub fn get_max_interval(&self, freq: Frequency) -> u16 {
+        self.max_intervals[freq as usize]
+    }
+
+    /// Returns the number of iterations after which the search for the next
+    /// occurrence is given up.
+    #[must_use]
+    pub fn get_max_iterations(&self) -> u32 {
+        self.max_iterations
+    }
+
+    /// Returns the highest number of occurrences that [`crate::RRuleSet::all`] returns.
+    #[must_use]
+    pub fn get_max_results(&self) -> u32 {
+        self.max_results
+    }
+}
+
+impl Default for ValidatorLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The limit that stopped an iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitReason {
+    /// No occurrence was found within the max number of iterations.
+    MaxIterations,
+    /// The max number of results was returned.
+    MaxResults,
+    /// The next occurrence is outside the year range.
+    YearRange,
+    /// The [`crate::Deadline`] passed.
+    DeadlineExceeded,
+    /// The [`crate::CancellationToken`] was cancelled.
+    Cancelled,
+}
+
+impl Display for LimitReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Self::MaxIterations => "no occurrence was found within the max number of iterations",
+            Self::MaxResults => "the max number of results was returned",
+            Self::YearRange => "the next occurrence is outside the year range",
+            Self::DeadlineExceeded => "the deadline passed",
+            Self::Cancelled => "the iteration was cancelled",
+        };
+        write!(f, "{reason}")
+    }
+}
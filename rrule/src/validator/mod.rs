@@ -1,8 +1,10 @@
 //! This module includes everything needed to validate an [`crate::core::RRule<Unvalidated>`].
 //! And in turn create a [`crate::core::RRule<Validated>`].
 
+mod emptiness;
 mod error;
+mod limits;
 pub(crate) mod validate_rrule;
+pub(crate) use emptiness::validate_not_empty;
 pub use error::ValidationError;
-
-pub(crate) use validate_rrule::YEAR_RANGE;
+pub use limits::{LimitReason, ValidatorLimits};
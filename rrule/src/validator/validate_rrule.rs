@@ -1,16 +1,18 @@
 use std::ops::RangeInclusive;
 
+use chrono::Datelike;
+
 use crate::{Frequency, NWeekday, RRule, Tz, Unvalidated};
 
-use super::ValidationError;
+use super::{ValidationError, ValidatorLimits};
 
 /// Range of values that a month can be.
 /// Range: `1..=12`
 pub(crate) static MONTH_RANGE: RangeInclusive<u8> = 1..=12;
 
-/// Range of values that a year can be.
+/// Range of values that a year can be, unless other limits are enabled.
 /// Range: `-10_000..=10_000`
-pub(crate) static YEAR_RANGE: RangeInclusive<i32> = -10_000..=10_000;
+pub(crate) const YEAR_RANGE: RangeInclusive<i32> = -10_000..=10_000;
 
 type Validator =
     &'static dyn Fn(&RRule<Unvalidated>, &chrono::DateTime<Tz>) -> Result<(), ValidationError>;
@@ -31,17 +33,35 @@ const VALIDATION_PIPELINE: [Validator; 11] = [
 
 /// Check if rules are valid as defined by the RFC and crate limitations.
 /// It checks all values in the [`RRule`] and makes sure that they are in
-/// the accepted ranges. If the function returns `Ok`, no errors where found.
+/// the accepted ranges. Returns the errors of every rule part, so if the list
+/// is empty, no errors where found.
 ///
 /// This check should always be done and just enforces limits set by the standard.
 /// Validation will always be enforced and can not be disabled using feature flags.
-pub(crate) fn validate_rrule_forced(
+pub(crate) fn validate_rrule_forced_all(
     rrule: &RRule<Unvalidated>,
     dt_start: &chrono::DateTime<Tz>,
-) -> Result<(), ValidationError> {
+) -> Vec<ValidationError> {
     VALIDATION_PIPELINE
         .into_iter()
-        .try_for_each(|validator| validator(rrule, dt_start))
+        .filter_map(|validator| validator(rrule, dt_start).err())
+        .collect()
+}
+
+/// Check if the `INTERVAL` and the year of `dt_start` are within `limits`.
+pub(crate) fn validate_rrule_limits(
+    rrule: &RRule<Unvalidated>,
+    dt_start: &chrono::DateTime<Tz>,
+    limits: &ValidatorLimits,
+) -> Result<(), ValidationError> {
+    if rrule.interval > limits.get_max_interval(rrule.freq) {
+        return Err(ValidationError::TooBigInterval(rrule.interval));
+    }
+    let year = dt_start.year();
+    if !limits.get_year_range().contains(&year) {
+        return Err(ValidationError::StartYearOutOfRange(year));
+    }
+    Ok(())
 }
 
 // Until:
@@ -54,15 +74,15 @@ fn validate_until(
     match rrule.until {
         Some(until) => {
             match dt_start.timezone() {
-                Tz::Local(_) => {
-                    let allowed_timezones = vec![Tz::LOCAL, Tz::UTC];
+                Tz::Local(_) | Tz::Floating => {
+                    let allowed_timezones = vec![Tz::LOCAL, Tz::FLOATING, Tz::UTC];
                     if !allowed_timezones.contains(&until.timezone()) {
                         return Err(ValidationError::DtStartUntilMismatchTimezone {
-                            dt_start_tz: dt_start.timezone().name().into(),
-                            until_tz: until.timezone().name().into(),
+                            dt_start_tz: dt_start.timezone().name(),
+                            until_tz: until.timezone().name(),
                             expected: allowed_timezones
                                 .into_iter()
-                                .map(|tz| tz.name().into())
+                                .map(|tz| tz.name())
                                 .collect(),
                         });
                     }
@@ -70,8 +90,8 @@ fn validate_until(
                 Tz::Tz(_) => {
                     if until.timezone() != Tz::UTC {
                         return Err(ValidationError::DtStartUntilMismatchTimezone {
-                            dt_start_tz: dt_start.timezone().name().into(),
-                            until_tz: until.timezone().name().into(),
+                            dt_start_tz: dt_start.timezone().name(),
+                            until_tz: until.timezone().name(),
                             expected: vec!["UTC".into()],
                         });
                     }
@@ -79,8 +99,8 @@ fn validate_until(
                 Tz::FixedOffset(_) => {
                     if until.timezone() != Tz::UTC {
                         return Err(ValidationError::DtStartUntilMismatchTimezone {
-                            dt_start_tz: dt_start.timezone().name().into(),
-                            until_tz: until.timezone().name().into(),
+                            dt_start_tz: dt_start.timezone().name(),
+                            until_tz: until.timezone().name(),
                             expected: vec!["UTC".into()],
                         });
                     }
@@ -382,6 +402,16 @@ mod tests {
 
     const UTC: Tz = Tz::UTC;
 
+    fn validate_rrule_forced(
+        rrule: &RRule<Unvalidated>,
+        dt_start: &chrono::DateTime<Tz>,
+    ) -> Result<(), ValidationError> {
+        validate_rrule_forced_all(rrule, dt_start)
+            .into_iter()
+            .next()
+            .map_or(Ok(()), Err)
+    }
+
     #[test]
     fn rejects_by_set_pos_without_byxxx_rule() {
         let rrule = RRule {
@@ -626,6 +656,7 @@ mod tests {
         }
     }
 
+    #[cfg(not(tz_subset))]
     #[test]
     fn rejects_until_with_incompatible_timezone() {
         fn t(start_tz: Tz, until_tz: Tz) -> (chrono::DateTime<Tz>, chrono::DateTime<Tz>) {
@@ -655,4 +686,43 @@ mod tests {
             ));
         }
     }
+
+    #[test]
+    fn rejects_rrule_outside_limits() {
+        let start_date = UTC.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let rrule = RRule {
+            freq: Frequency::Yearly,
+            interval: 500,
+            ..Default::default()
+        };
+        assert!(validate_rrule_limits(&rrule, &start_date, &ValidatorLimits::DEFAULT).is_ok());
+        assert_eq!(
+            validate_rrule_limits(&rrule, &start_date, &ValidatorLimits::STRICT),
+            Err(ValidationError::TooBigInterval(500))
+        );
+
+        let start_date = UTC.with_ymd_and_hms(200, 1, 1, 0, 0, 0).unwrap();
+        let rrule = RRule::default();
+        assert_eq!(
+            validate_rrule_limits(&rrule, &start_date, &ValidatorLimits::STRICT),
+            Err(ValidationError::StartYearOutOfRange(200))
+        );
+        assert!(validate_rrule_limits(&rrule, &start_date, &ValidatorLimits::UNCHECKED).is_ok());
+    }
+
+    #[test]
+    fn collects_errors_of_every_rule_part() {
+        let rrule = RRule {
+            by_month: vec![13],
+            by_hour: vec![24],
+            by_second: vec![60],
+            ..Default::default()
+        };
+        let errors = validate_rrule_forced_all(&rrule, &UTC.timestamp_opt(0, 0).unwrap());
+        let rule_parts: Vec<_> = errors.iter().map(ValidationError::rule_part).collect();
+        assert_eq!(
+            rule_parts,
+            vec![Some("BYMONTH"), Some("BYHOUR"), Some("BYSECOND")]
+        );
+    }
 }
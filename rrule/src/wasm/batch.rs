@@ -0,0 +1,141 @@
+use super::{
+    options::{ExpandOptions, Recurrences},
+    set_panic_hook, to_js_located_error,
+};
+use crate::{core::Tz, parser::with_tzid_cache, LocatedError, RRuleSet};
+use chrono::DateTime;
+use js_sys::{Array, Map, Reflect};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+export interface BatchEntry {
+  id: string | number;
+  rules: string;
+}
+"#;
+
+/// Get the recurrences of many rule sets between `after` and `before`, in a single
+/// call.
+///
+/// `entries` is an array of `{ id, rules }`, and `options` is the same as for
+/// `getRecurrencesBetween`. Returns a `Map` from the `id` of each entry to its
+/// `{ dates, limited, reason }`, or to the `Error` of its rules, so that one
+/// invalid entry doesn't fail the others.
+///
+/// Entries which are not objects, or whose `id` is not a string or a number, are
+/// reported with an `Error` under their index in `entries`.
+///
+/// The window and the options are parsed once, rule sets which occur several
+/// times in `entries` are only parsed and expanded once, and each `TZID` is only
+/// resolved once.
+///
+/// # Errors
+///
+/// Returns an `Error` if `after`, `before` or `options` are invalid.
+#[wasm_bindgen(js_name = getRecurrencesBetweenBatch, unchecked_return_type = "Map<string | number, Recurrences | Error>")]
+pub fn get_recurrences_between_batch(
+    #[wasm_bindgen(unchecked_param_type = "BatchEntry[]")] entries: Array,
+    after: &str,
+    before: &str,
+    options: JsValue,
+) -> Result<Map, JsValue> {
+    set_panic_hook();
+
+    let options = ExpandOptions::from_js(&options)?;
+    let after = options.parse_date("after", after)?;
+    let before = options.parse_date("before", before)?;
+    let mut batch = Batch::new(options, after, before);
+
+    let results = Map::new();
+    with_tzid_cache(|| {
+        for (index, entry) in entries.iter().enumerate() {
+            let (id, result) = match read_entry(&entry) {
+                Ok((id, rules)) => match batch.expand(&rules) {
+                    Ok(recurrences) => (id, recurrences.clone().into()),
+                    Err(err) => (id, to_js_located_error(err)),
+                },
+                Err((id, err)) => (id.unwrap_or_else(|| (index as f64).into()), err.into()),
+            };
+            results.set(&id, &result);
+        }
+    });
+    Ok(results)
+}
+
+/// Returns the `id` and `rules` of `entry`, or the error of the entry together
+/// with its `id` if it has a valid one.
+fn read_entry(entry: &JsValue) -> Result<(JsValue, String), (Option<JsValue>, JsError)> {
+    if !entry.is_object() {
+        return Err((None, JsError::new("an entry must be an object")));
+    }
+    // Reading a property of an object only fails for proxies and getters that throw.
+    let id = Reflect::get(entry, &"id".into()).unwrap_or(JsValue::UNDEFINED);
+    if id.as_string().is_none() && id.as_f64().is_none() {
+        return Err((None, JsError::new("`id` must be a string or a number")));
+    }
+    match Reflect::get(entry, &"rules".into())
+        .ok()
+        .and_then(|rules| rules.as_string())
+    {
+        Some(rules) => Ok((id, rules)),
+        None => Err((Some(id), JsError::new("`rules` must be a string"))),
+    }
+}
+
+/// Expands rule sets within the same window and with the same options, and keeps
+/// the results of the rule sets that were already expanded.
+struct Batch {
+    options: ExpandOptions,
+    after: DateTime<Tz>,
+    before: DateTime<Tz>,
+    results: HashMap<String, Result<Recurrences, LocatedError>>,
+}
+
+impl Batch {
+    fn new(options: ExpandOptions, after: DateTime<Tz>, before: DateTime<Tz>) -> Self {
+        Self {
+            options,
+            after,
+            before,
+            results: HashMap::new(),
+        }
+    }
+
+    fn expand(&mut self, rules: &str) -> &Result<Recurrences, LocatedError> {
+        if !self.results.contains_key(rules) {
+            let result = RRuleSet::parse_all_errors(rules)
+                .map(|rrule_set| self.options.expand(rrule_set, self.after, self.before))
+                .map_err(|mut errors| errors.swap_remove(0));
+            self.results.insert(rules.into(), result);
+        }
+        &self.results[rules]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn keeps_errors_per_entry() {
+        let after = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let before = Tz::UTC.with_ymd_and_hms(2021, 1, 3, 23, 0, 0).unwrap();
+        let mut batch = Batch::new(ExpandOptions::default(), after, before);
+
+        let rules = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY";
+        assert_eq!(batch.expand(rules).as_ref().unwrap().dates().len(), 3);
+        assert_eq!(
+            batch
+                .expand("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24")
+                .as_ref()
+                .unwrap_err()
+                .code(),
+            "parse.invalid_by_hour"
+        );
+        assert_eq!(batch.expand(rules).as_ref().unwrap().dates().len(), 3);
+        assert_eq!(batch.results.len(), 2);
+    }
+}
@@ -0,0 +1,443 @@
+use super::{
+    recurrence_set::RecurrenceSet,
+    timezone::{parse_date_in, parse_time_zone},
+    to_js_error,
+};
+use crate::{core::Tz, Frequency, NWeekday, ParseError, RRule, Unvalidated};
+use chrono::{DateTime, Month, Weekday};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+export type Frequency = "YEARLY" | "MONTHLY" | "WEEKLY" | "DAILY" | "HOURLY" | "MINUTELY" | "SECONDLY";
+export type Weekday = "MO" | "TU" | "WE" | "TH" | "FR" | "SA" | "SU";
+/** A weekday, optionally preceded by its ordinal in the month or year, like `-1FR`. */
+export type NWeekday = Weekday | `${number}${Weekday}`;
+"#;
+
+/// Builds a rule from its parts, like [`RRule<Unvalidated>`], so that the rule
+/// doesn't have to be assembled as a string.
+///
+/// Every method returns the builder, so calls can be chained. The rule is only
+/// validated by `build()`.
+#[wasm_bindgen]
+pub struct RuleBuilder {
+    rrule: RRule<Unvalidated>,
+}
+
+#[wasm_bindgen]
+impl RuleBuilder {
+    /// Starts a rule with the frequency `freq`, like `"WEEKLY"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `freq` is not a valid frequency.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        #[wasm_bindgen(unchecked_param_type = "Frequency")] freq: &str,
+    ) -> Result<Self, JsValue> {
+        Ok(Self {
+            rrule: RRule::new(parse(freq)?),
+        })
+    }
+
+    /// Sets the frequency of the rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `freq` is not a valid frequency.
+    pub fn freq(
+        self,
+        #[wasm_bindgen(unchecked_param_type = "Frequency")] freq: &str,
+    ) -> Result<Self, JsValue> {
+        let freq: Frequency = parse(freq)?;
+        Ok(self.map(|rrule| rrule.freq(freq)))
+    }
+
+    /// Sets the interval between each iteration of the frequency.
+    pub fn interval(self, interval: u16) -> Self {
+        self.map(|rrule| rrule.interval(interval))
+    }
+
+    /// Sets the number of occurrences.
+    pub fn count(self, count: u32) -> Self {
+        self.map(|rrule| rrule.count(count))
+    }
+
+    /// Sets the last date of the occurrences, an RFC 3339 date or a wall-clock
+    /// time in UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `until` is not a valid date.
+    pub fn until(self, until: &str) -> Result<Self, JsValue> {
+        let until = parse_date_in("UNTIL", until, &Tz::UTC)?;
+        Ok(self.map(|rrule| rrule.until(until)))
+    }
+
+    /// Sets the first day of the week, like `"SU"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `week_start` is not a valid weekday.
+    #[wasm_bindgen(js_name = weekStart)]
+    pub fn week_start(
+        self,
+        #[wasm_bindgen(unchecked_param_type = "Weekday")] week_start: &str,
+    ) -> Result<Self, JsValue> {
+        let week_start = match parse::<NWeekday>(week_start)? {
+            NWeekday::Every(weekday) => weekday,
+            NWeekday::Nth(..) => {
+                return Err(js_error(ParseError::InvalidWeekdayStart(week_start.into())))
+            }
+        };
+        Ok(self.map(|rrule| rrule.week_start(week_start)))
+    }
+
+    /// Sets the occurrences within the set of each interval, like `-1` for the last.
+    #[wasm_bindgen(js_name = bySetPos)]
+    pub fn by_set_pos(self, by_set_pos: Vec<i32>) -> Self {
+        self.map(|rrule| rrule.by_set_pos(by_set_pos))
+    }
+
+    /// Sets the months, from `1` to `12`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if a month is not within `1..=12`.
+    #[wasm_bindgen(js_name = byMonth)]
+    pub fn by_month(self, by_month: Vec<u8>) -> Result<Self, JsValue> {
+        let months = by_month
+            .iter()
+            .map(|&month| {
+                Month::try_from(month)
+                    .map_err(|_| js_error(ParseError::InvalidByMonth(month.to_string())))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.map(|rrule| rrule.by_month(&months)))
+    }
+
+    /// Sets the days of the month, negative days count from the end of the month.
+    #[wasm_bindgen(js_name = byMonthDay)]
+    pub fn by_month_day(self, by_month_day: Vec<i8>) -> Self {
+        self.map(|rrule| rrule.by_month_day(by_month_day))
+    }
+
+    /// Sets the days of the year, negative days count from the end of the year.
+    #[wasm_bindgen(js_name = byYearDay)]
+    pub fn by_year_day(self, by_year_day: Vec<i16>) -> Self {
+        self.map(|rrule| rrule.by_year_day(by_year_day))
+    }
+
+    /// Sets the ISO weeks of the year, negative weeks count from the end of the year.
+    #[wasm_bindgen(js_name = byWeekNo)]
+    pub fn by_week_no(self, by_week_no: Vec<i8>) -> Self {
+        self.map(|rrule| rrule.by_week_no(by_week_no))
+    }
+
+    /// Sets the weekdays, like `["MO", "WE"]`, or `["-1FR"]` for the last Friday.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if a value is not a valid weekday.
+    #[wasm_bindgen(js_name = byWeekday)]
+    pub fn by_weekday(
+        self,
+        #[wasm_bindgen(unchecked_param_type = "NWeekday[]")] by_weekday: Vec<String>,
+    ) -> Result<Self, JsValue> {
+        let weekdays = by_weekday
+            .iter()
+            .map(|weekday| parse(weekday))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.map(|rrule| rrule.by_weekday(weekdays)))
+    }
+
+    /// Sets the hours, from `0` to `23`.
+    #[wasm_bindgen(js_name = byHour)]
+    pub fn by_hour(self, by_hour: Vec<u8>) -> Self {
+        self.map(|rrule| rrule.by_hour(by_hour))
+    }
+
+    /// Sets the minutes, from `0` to `59`.
+    #[wasm_bindgen(js_name = byMinute)]
+    pub fn by_minute(self, by_minute: Vec<u8>) -> Self {
+        self.map(|rrule| rrule.by_minute(by_minute))
+    }
+
+    /// Sets the seconds, from `0` to `59`.
+    #[wasm_bindgen(js_name = bySecond)]
+    pub fn by_second(self, by_second: Vec<u8>) -> Self {
+        self.map(|rrule| rrule.by_second(by_second))
+    }
+
+    /// Sets the offset in days from Easter Sunday.
+    #[cfg(feature = "by-easter")]
+    #[wasm_bindgen(js_name = byEaster)]
+    pub fn by_easter(self, by_easter: i16) -> Self {
+        self.map(|rrule| rrule.by_easter(by_easter))
+    }
+
+    /// Returns the rule as an `RRULE` value, like `FREQ=WEEKLY;COUNT=3`.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_rrule_string(&self) -> String {
+        self.rrule.to_string()
+    }
+
+    /// Validates the rule and returns a rule set with it, starting at `dt_start`.
+    /// `dt_start` is an RFC 3339 date, or a wall-clock time in `time_zone`, an IANA
+    /// name or a fixed offset, which defaults to UTC. The occurrences are in
+    /// `time_zone` if it is given.
+    ///
+    /// The builder can still be used afterwards, e.g. to build the rule again
+    /// after a change.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` with the `code` of the problem if the rule is invalid.
+    pub fn build(
+        &self,
+        dt_start: &str,
+        time_zone: Option<String>,
+    ) -> Result<RecurrenceSet, JsValue> {
+        let tz = time_zone.as_deref().map(parse_time_zone).transpose()?;
+        let dt_start = parse_date_in("DTSTART", dt_start, &tz.unwrap_or(Tz::UTC))?;
+        let rrule_set = self
+            .rrule
+            .clone()
+            .build(dt_start)
+            .map_err(|err| to_js_error(&err))?;
+        Ok(RecurrenceSet::from_rrule_set(rrule_set))
+    }
+}
+
+impl RuleBuilder {
+    fn map(self, change: impl FnOnce(RRule<Unvalidated>) -> RRule<Unvalidated>) -> Self {
+        Self {
+            rrule: change(self.rrule),
+        }
+    }
+}
+
+/// A rule of a parsed rule set, whose parts can be read.
+#[wasm_bindgen]
+pub struct Rule {
+    rrule: RRule,
+}
+
+#[wasm_bindgen]
+impl Rule {
+    /// The frequency, like `"WEEKLY"`.
+    #[wasm_bindgen(getter, unchecked_return_type = "Frequency")]
+    pub fn freq(&self) -> String {
+        self.rrule.get_freq().to_string()
+    }
+
+    /// The interval between each iteration of the frequency.
+    #[wasm_bindgen(getter)]
+    pub fn interval(&self) -> u16 {
+        self.rrule.get_interval()
+    }
+
+    /// The number of occurrences, if it is set.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> Option<u32> {
+        self.rrule.get_count()
+    }
+
+    /// The last date of the occurrences as an RFC 3339 string, if it is set.
+    #[wasm_bindgen(getter)]
+    pub fn until(&self) -> Option<String> {
+        self.rrule.get_until().map(DateTime::to_rfc3339)
+    }
+
+    /// The first day of the week, like `"MO"`.
+    #[wasm_bindgen(getter, js_name = weekStart, unchecked_return_type = "Weekday")]
+    pub fn week_start(&self) -> String {
+        weekday_name(self.rrule.get_week_start())
+    }
+
+    /// The occurrences within the set of each interval.
+    #[wasm_bindgen(getter, js_name = bySetPos)]
+    pub fn by_set_pos(&self) -> Vec<i32> {
+        self.rrule.get_by_set_pos().to_vec()
+    }
+
+    /// The months, from `1` to `12`.
+    #[wasm_bindgen(getter, js_name = byMonth)]
+    pub fn by_month(&self) -> Vec<u8> {
+        self.rrule.get_by_month().to_vec()
+    }
+
+    /// The days of the month, negative days count from the end of the month.
+    #[wasm_bindgen(getter, js_name = byMonthDay)]
+    pub fn by_month_day(&self) -> Vec<i8> {
+        // Validation moves the negative month days to `by_n_month_day`.
+        let rrule = &self.rrule;
+        rrule
+            .by_month_day
+            .iter()
+            .chain(&rrule.by_n_month_day)
+            .copied()
+            .collect()
+    }
+
+    /// The days of the year.
+    #[wasm_bindgen(getter, js_name = byYearDay)]
+    pub fn by_year_day(&self) -> Vec<i16> {
+        self.rrule.get_by_year_day().to_vec()
+    }
+
+    /// The ISO weeks of the year.
+    #[wasm_bindgen(getter, js_name = byWeekNo)]
+    pub fn by_week_no(&self) -> Vec<i8> {
+        self.rrule.get_by_week_no().to_vec()
+    }
+
+    /// The weekdays, with their ordinal if they have one, like `["MO", "-1FR"]`.
+    #[wasm_bindgen(getter, js_name = byWeekday, unchecked_return_type = "NWeekday[]")]
+    pub fn by_weekday(&self) -> Vec<String> {
+        self.rrule
+            .get_by_weekday()
+            .iter()
+            .map(|weekday| match weekday {
+                NWeekday::Every(weekday) => weekday_name(*weekday),
+                NWeekday::Nth(number, weekday) => format!("{number}{}", weekday_name(*weekday)),
+            })
+            .collect()
+    }
+
+    /// The hours.
+    #[wasm_bindgen(getter, js_name = byHour)]
+    pub fn by_hour(&self) -> Vec<u8> {
+        self.rrule.get_by_hour().to_vec()
+    }
+
+    /// The minutes.
+    #[wasm_bindgen(getter, js_name = byMinute)]
+    pub fn by_minute(&self) -> Vec<u8> {
+        self.rrule.get_by_minute().to_vec()
+    }
+
+    /// The seconds.
+    #[wasm_bindgen(getter, js_name = bySecond)]
+    pub fn by_second(&self) -> Vec<u8> {
+        self.rrule.get_by_second().to_vec()
+    }
+
+    /// The offset in days from Easter Sunday, if it is set.
+    #[cfg(feature = "by-easter")]
+    #[wasm_bindgen(getter, js_name = byEaster)]
+    pub fn by_easter(&self) -> Option<i16> {
+        self.rrule.get_by_easter().copied()
+    }
+
+    /// Returns a builder with the parts of the rule, to change it.
+    #[wasm_bindgen(js_name = toBuilder)]
+    pub fn to_builder(&self) -> RuleBuilder {
+        RuleBuilder {
+            rrule: self.rrule.clone().into_builder(),
+        }
+    }
+
+    /// Returns the rule as an `RRULE` value, like `FREQ=WEEKLY;COUNT=3`.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_rrule_string(&self) -> String {
+        self.rrule.to_string()
+    }
+}
+
+impl From<RRule> for Rule {
+    fn from(rrule: RRule) -> Self {
+        Self { rrule }
+    }
+}
+
+fn parse<T: FromStr<Err = ParseError>>(value: &str) -> Result<T, JsValue> {
+    value.parse().map_err(js_error)
+}
+
+fn js_error(err: ParseError) -> JsValue {
+    to_js_error(&err.into())
+}
+
+fn weekday_name(weekday: Weekday) -> String {
+    NWeekday::Every(weekday).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_rules_from_their_parts() {
+        let builder = RuleBuilder::new("MONTHLY")
+            .unwrap()
+            .interval(2)
+            .count(3)
+            .by_weekday(vec!["-1FR".into(), "2MO".into()])
+            .unwrap();
+        assert_eq!(
+            builder.to_rrule_string(),
+            "FREQ=MONTHLY;COUNT=3;INTERVAL=2;BYDAY=-1FR,2MO"
+        );
+
+        let mut recurrence_set = builder
+            .build("2021-01-01T09:00", Some("Europe/Berlin".into()))
+            .unwrap();
+        assert_eq!(
+            recurrence_set.take(3),
+            vec![
+                "2021-01-11T09:00:00+01:00",
+                "2021-01-29T09:00:00+01:00",
+                "2021-03-08T09:00:00+01:00",
+            ]
+        );
+
+        let rule = &recurrence_set.rules()[0];
+        assert_eq!(rule.freq(), "MONTHLY");
+        assert_eq!(rule.by_weekday(), vec!["-1FR", "2MO"]);
+        assert_eq!(rule.week_start(), "MO");
+        assert_eq!(rule.by_hour(), vec![9]);
+        assert_eq!(
+            rule.to_builder().count(5).to_rrule_string(),
+            "FREQ=MONTHLY;COUNT=5;INTERVAL=2;BYHOUR=9;BYMINUTE=0;BYSECOND=0;BYDAY=-1FR,2MO"
+        );
+    }
+
+    #[test]
+    fn reads_negative_month_days_and_easter() {
+        let mut recurrence_set = RuleBuilder::new("MONTHLY")
+            .unwrap()
+            .by_month_day(vec![-1, 15])
+            .build("2021-01-31T09:00:00Z", None)
+            .unwrap();
+        assert_eq!(
+            recurrence_set.take(3),
+            vec![
+                "2021-01-31T09:00:00+00:00",
+                "2021-02-15T09:00:00+00:00",
+                "2021-02-28T09:00:00+00:00",
+            ]
+        );
+        let rule = &recurrence_set.rules()[0];
+        assert_eq!(rule.by_month_day(), vec![15, -1]);
+        assert_eq!(
+            rule.to_rrule_string(),
+            "FREQ=MONTHLY;BYMONTHDAY=15,-1;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+        );
+
+        #[cfg(feature = "by-easter")]
+        {
+            let rule = RuleBuilder::new("YEARLY")
+                .unwrap()
+                .by_easter(-2)
+                .build("2021-01-01T09:00:00Z", None)
+                .unwrap()
+                .rules()
+                .remove(0);
+            assert_eq!(rule.by_easter(), Some(-2));
+            assert_eq!(rule.by_month_day(), Vec::<i8>::new());
+        }
+    }
+}
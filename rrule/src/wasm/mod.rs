@@ -1,9 +1,18 @@
+use crate::{LimitReason, LocatedError, RRuleError, RRuleResult, RRuleSet, Span};
+use js_sys::{Object, Reflect};
 use wasm_bindgen::prelude::*;
-use chrono::{DateTime};
-use crate::{RRuleSet, RRuleError};
-use crate::{core::Tz};
 
-const MAX_OCCURRENCES_COUNT: u16 = 730;
+mod batch;
+mod builder;
+mod options;
+mod recurrence_set;
+mod timestamps;
+mod timezone;
+mod validate;
+
+use options::{ExpandOptions, Recurrences};
+use timestamps::{to_timestamps, Timestamps};
+
 const MAX_RESULT_LIMIT: u16 = 1000;
 
 /// When the `console_error_panic_hook` feature is enabled, we can call the
@@ -18,62 +27,139 @@ pub fn set_panic_hook() {
 }
 
 /// Get all recurrences of the rrule
+///
+/// The recurrences are expanded like `getRecurrencesBetween` does with its default
+/// options, so the `COUNT` of the rules is applied, and at most `count` recurrences
+/// are returned, or `1000` if `count` is not given or larger. Use
+/// `getRecurrencesBetween` to know whether the recurrences were truncated.
 #[wasm_bindgen(js_name = getAllRecurrencesBetween)]
-pub fn get_all_recurrences_between(rules: &str, after: &str, before: &str, count: Option<u32>) -> Result<Vec<JsValue>, JsError> {
-    set_panic_hook();
+pub fn get_all_recurrences_between(
+    rules: &str,
+    after: &str,
+    before: &str,
+    count: Option<u32>,
+) -> Result<Vec<JsValue>, JsValue> {
+    let dates = get_recurrences_between(rules, after, before, count)?.dates;
 
-    let after = parse_date(after);
-    let before = parse_date(before);
+    Ok(dates
+        .iter()
+        .map(|dt| JsValue::from_str(&dt.to_rfc3339()))
+        .collect())
+}
 
-    match (parser_rrule_set(rules), after, before) {
-        (Ok(rrule_set), Ok(after), Ok(before)) => {
-            let mut cloned_rrules = rrule_set.get_rrule().clone();
-            let max_count: u32 = MAX_OCCURRENCES_COUNT.into();
+/// Get all recurrences of the rrule as milliseconds since the epoch, which can be
+/// passed to `new Date()` without formatting and parsing strings.
+#[wasm_bindgen(js_name = getAllTimestampsBetween)]
+pub fn get_all_timestamps_between(
+    rules: &str,
+    after: &str,
+    before: &str,
+    count: Option<u32>,
+) -> Result<Vec<f64>, JsValue> {
+    let dates = get_recurrences_between(rules, after, before, count)?.dates;
 
-            cloned_rrules.iter_mut().for_each(|rrule| {
-                // Handle the limit of the number of recurrences
-                if let Some(count) = count {
-                    // If count is provided, use it.
-                    rrule.count = Some(count);
-                } else if rrule.count.is_none() || rrule.count.unwrap() > max_count {
-                    // Otherwise, use the max count.
-                    rrule.count = Some(max_count);
-                }
-            });
+    Ok(to_timestamps(&dates))
+}
 
-            let final_rrule_set = rrule_set.set_rrules(cloned_rrules).after(after).before(before);
+/// Get all recurrences of the rrule as milliseconds since the epoch, together with
+/// their offsets from UTC, and whether they were truncated.
+#[wasm_bindgen(js_name = getAllTimestampsWithOffsetsBetween)]
+pub fn get_all_timestamps_with_offsets_between(
+    rules: &str,
+    after: &str,
+    before: &str,
+    count: Option<u32>,
+) -> Result<Timestamps, JsValue> {
+    Ok(get_recurrences_between(rules, after, before, count)?.into())
+}
 
-            Ok(get_all_recurrences_for(final_rrule_set))
-        }
-        (Err(e), _, _) => Err(e),
-        (_, Err(e), _) => Err(e),
-        (_, _, Err(e)) => Err(e)
-    }
+/// Get the recurrences of the rrule between `after` and `before`, as
+/// `{ dates, limited, reason }`.
+///
+/// `options` is an optional object with the fields `maxResults` (defaults to
+/// `1000`), `maxIterations` and `honorCount` (whether the `COUNT` of the rules is
+/// applied, defaults to `true`) and `timeZone`, an IANA name or a fixed offset like
+/// `+05:30`, in which the dates are returned.
+///
+/// `after` and `before` are RFC 3339 dates, or wall-clock times like
+/// `2021-03-14T09:00` in `timeZone`, or in UTC if it isn't set. `limited` is `true`
+/// when a limit stopped the expansion, and `reason` is then the name of the limit,
+/// like `"MaxResults"`.
+#[wasm_bindgen(js_name = getRecurrencesBetween)]
+pub fn get_recurrences_between_with_options(
+    rules: &str,
+    after: &str,
+    before: &str,
+    options: JsValue,
+) -> Result<Recurrences, JsValue> {
+    set_panic_hook();
+
+    let options = ExpandOptions::from_js(&options)?;
+    let rrule_set = parser_rrule_set(rules)?;
+    let after = options.parse_date("after", after)?;
+    let before = options.parse_date("before", before)?;
+
+    Ok(options.expand(rrule_set, after, before))
 }
 
-fn parse_date(date: &str) -> Result<DateTime<Tz>, JsError> {
-    match DateTime::parse_from_rfc3339(date) {
-        Ok(datetime) => Ok(datetime.with_timezone(&Tz::UTC)),
-        Err(e) => Err(JsError::from(e)),
-    }
+/// Expands `rules` for the functions which take a `count` instead of an options object.
+fn get_recurrences_between(
+    rules: &str,
+    after: &str,
+    before: &str,
+    count: Option<u32>,
+) -> Result<RRuleResult, JsValue> {
+    set_panic_hook();
+
+    let options = ExpandOptions::with_max_results(count);
+    let rrule_set = parser_rrule_set(rules)?;
+    let after = options.parse_date("after", after)?;
+    let before = options.parse_date("before", before)?;
+
+    Ok(options.expand_dates(rrule_set, after, before))
 }
 
-fn parser_rrule_set(rules: &str) -> Result<RRuleSet, JsError> {
-    let rrule_set_result: Result<RRuleSet, RRuleError> = rules.parse();
+fn parser_rrule_set(rules: &str) -> Result<RRuleSet, JsValue> {
+    RRuleSet::parse_all_errors(rules).map_err(|errors| to_js_located_error(&errors[0]))
+}
 
-    match rrule_set_result {
-        Ok(rrule_set) => Ok(rrule_set),
-        Err(e) => Err(JsError::from(e))
+/// Returns the name of `reason` that is given to JavaScript, like `"MaxResults"`.
+fn limit_reason_name(reason: LimitReason) -> &'static str {
+    match reason {
+        LimitReason::MaxIterations => "MaxIterations",
+        LimitReason::MaxResults => "MaxResults",
+        LimitReason::YearRange => "YearRange",
+        LimitReason::DeadlineExceeded => "DeadlineExceeded",
+        LimitReason::Cancelled => "Cancelled",
     }
 }
 
-fn get_all_recurrences_for(rrule_set: RRuleSet) -> Vec<JsValue> {
-    let rrule_set_collection = rrule_set.all(MAX_RESULT_LIMIT);
-    let mut result = Vec::new();
+/// Converts the error to a JavaScript `Error` with the stable `code` of the error,
+/// and its `span` if its position in the input is known.
+fn to_js_error(error: &RRuleError) -> JsValue {
+    js_error_at(error, error.span())
+}
 
-    for dt in rrule_set_collection.dates {
-        result.push(JsValue::from_str(&dt.to_rfc3339()));
-    }
+/// Same as [`to_js_error`], with the `span` (`{ line, start, end }`) the error was
+/// located at in the input.
+fn to_js_located_error(error: &LocatedError) -> JsValue {
+    js_error_at(&error.error, error.span)
+}
 
-    result
+fn js_error_at(error: &RRuleError, span: Option<Span>) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    // Setting a property of a new object can't fail.
+    let _ = Reflect::set(&js_error, &"code".into(), &error.code().into());
+    if let Some(span) = span {
+        let js_span = Object::new();
+        for (key, value) in [
+            ("line", span.line),
+            ("start", span.start),
+            ("end", span.end),
+        ] {
+            let _ = Reflect::set(&js_span, &key.into(), &value.into());
+        }
+        let _ = Reflect::set(&js_error, &"span".into(), &js_span);
+    }
+    js_error.into()
 }
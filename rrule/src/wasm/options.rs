@@ -0,0 +1,267 @@
+use super::{
+    limit_reason_name,
+    timezone::{in_time_zone, parse_date_in, parse_time_zone},
+    MAX_RESULT_LIMIT,
+};
+use crate::{core::Tz, RRuleResult, RRuleSet, ValidatorLimits};
+use chrono::DateTime;
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+/// The options object of `getRecurrencesBetween`, all of its fields are optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ExpandOptions {
+    /// `maxResults`: the max number of occurrences returned. Defaults to `1000`.
+    max_results: u32,
+    /// `maxIterations`: the max number of iterations without an occurrence before
+    /// the expansion is stopped. Defaults to the one of [`ValidatorLimits::DEFAULT`].
+    max_iterations: u32,
+    /// `honorCount`: whether the `COUNT` of the rules is applied. When `false`, the
+    /// rules are expanded as if they had no `COUNT`. Defaults to `true`.
+    honor_count: bool,
+    /// `timeZone`: the timezone the occurrences are returned in, and in which
+    /// wall-clock `after` and `before` dates are placed. By default, the occurrences
+    /// keep the timezone of the rule set.
+    time_zone: Option<Tz>,
+}
+
+impl Default for ExpandOptions {
+    fn default() -> Self {
+        Self {
+            max_results: MAX_RESULT_LIMIT.into(),
+            max_iterations: ValidatorLimits::DEFAULT.get_max_iterations(),
+            honor_count: true,
+            time_zone: None,
+        }
+    }
+}
+
+impl ExpandOptions {
+    /// The default options, with at most `max_results` occurrences if it is given
+    /// and lower than the default.
+    pub(super) fn with_max_results(max_results: Option<u32>) -> Self {
+        let default = Self::default();
+        Self {
+            max_results: max_results.map_or(default.max_results, |max_results| {
+                max_results.min(default.max_results)
+            }),
+            ..default
+        }
+    }
+
+    /// Reads the options from the JavaScript object `options`, which may be
+    /// `undefined` or `null` to use the defaults.
+    pub(super) fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut parsed = Self::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(parsed);
+        }
+        if let Some(max_results) = get_u32(options, "maxResults")? {
+            parsed.max_results = max_results;
+        }
+        if let Some(max_iterations) = get_u32(options, "maxIterations")? {
+            parsed.max_iterations = max_iterations;
+        }
+        let honor_count = Reflect::get(options, &"honorCount".into())?;
+        if !honor_count.is_undefined() {
+            parsed.honor_count = honor_count
+                .as_bool()
+                .ok_or_else(|| JsError::new("`honorCount` must be a boolean"))?;
+        }
+        let time_zone = Reflect::get(options, &"timeZone".into())?;
+        if !time_zone.is_undefined() {
+            let name = time_zone
+                .as_string()
+                .ok_or_else(|| JsError::new("`timeZone` must be a string"))?;
+            parsed.time_zone = Some(parse_time_zone(&name)?);
+        }
+        Ok(parsed)
+    }
+
+    /// Parses the `property` date, in the `timeZone` if it's a wall-clock time.
+    pub(super) fn parse_date(&self, property: &str, date: &str) -> Result<DateTime<Tz>, JsValue> {
+        parse_date_in(property, date, &self.time_zone.unwrap_or(Tz::UTC))
+    }
+
+    /// Returns the occurrences of `rrule_set` between `after` and `before`, inclusive.
+    pub(super) fn expand(
+        &self,
+        rrule_set: RRuleSet,
+        after: DateTime<Tz>,
+        before: DateTime<Tz>,
+    ) -> Recurrences {
+        self.expand_dates(rrule_set, after, before).into()
+    }
+
+    /// Same as [`ExpandOptions::expand`], but returns the dates themselves.
+    pub(super) fn expand_dates(
+        &self,
+        mut rrule_set: RRuleSet,
+        after: DateTime<Tz>,
+        before: DateTime<Tz>,
+    ) -> RRuleResult {
+        if !self.honor_count {
+            let mut rrules = rrule_set.get_rrule().clone();
+            for rrule in &mut rrules {
+                rrule.count = None;
+            }
+            rrule_set = rrule_set.set_rrules(rrules);
+        }
+        let limits = ValidatorLimits::DEFAULT
+            .max_results(self.max_results)
+            .max_iterations(self.max_iterations);
+        let mut result = rrule_set
+            .limits(limits)
+            .after(after)
+            .before(before)
+            .all_within_limits();
+        if let Some(tz) = &self.time_zone {
+            for date in &mut result.dates {
+                *date = in_time_zone(date, tz);
+            }
+        }
+        result
+    }
+}
+
+/// Reads the non-negative integer `key` of `options`, if it is set.
+fn get_u32(options: &JsValue, key: &str) -> Result<Option<u32>, JsValue> {
+    let value = Reflect::get(options, &key.into())?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    let range = 0.0..=f64::from(u32::MAX);
+    match value.as_f64() {
+        Some(number) if number.fract() == 0.0 && range.contains(&number) => Ok(Some(number as u32)),
+        _ => Err(JsError::new(&format!("`{key}` must be a non-negative integer")).into()),
+    }
+}
+
+/// The occurrences found by `getRecurrencesBetween`, and whether more of them
+/// might exist.
+#[derive(Debug, Clone)]
+#[wasm_bindgen]
+pub struct Recurrences {
+    dates: Vec<String>,
+    limited: bool,
+    reason: Option<&'static str>,
+}
+
+#[wasm_bindgen]
+impl Recurrences {
+    /// The occurrences as RFC 3339 strings.
+    #[wasm_bindgen(getter)]
+    pub fn dates(&self) -> Vec<String> {
+        self.dates.clone()
+    }
+
+    /// Whether the expansion was stopped by a limit, in which case there may be
+    /// more occurrences than `dates`.
+    #[wasm_bindgen(getter)]
+    pub fn limited(&self) -> bool {
+        self.limited
+    }
+
+    /// The limit that stopped the expansion, like `"MaxResults"`, or `undefined`.
+    #[wasm_bindgen(getter)]
+    pub fn reason(&self) -> Option<String> {
+        self.reason.map(str::to_owned)
+    }
+}
+
+impl From<RRuleResult> for Recurrences {
+    fn from(result: RRuleResult) -> Self {
+        Self {
+            dates: result.dates.iter().map(DateTime::to_rfc3339).collect(),
+            limited: result.limited,
+            reason: result.limit_reason.map(limit_reason_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn expand(options: ExpandOptions, rules: &str) -> Recurrences {
+        let after = Tz::UTC.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let before = Tz::UTC.with_ymd_and_hms(2021, 12, 31, 0, 0, 0).unwrap();
+        options.expand(rules.parse().unwrap(), after, before)
+    }
+
+    #[test]
+    fn reports_the_truncation() {
+        let options = ExpandOptions {
+            max_results: 2,
+            ..ExpandOptions::default()
+        };
+        let recurrences = expand(options, "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY");
+        assert_eq!(
+            recurrences.dates(),
+            vec!["2021-01-01T09:00:00+00:00", "2021-01-02T09:00:00+00:00"]
+        );
+        assert!(recurrences.limited());
+        assert_eq!(recurrences.reason().as_deref(), Some("MaxResults"));
+
+        let recurrences = expand(
+            ExpandOptions::default(),
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2",
+        );
+        assert_eq!(recurrences.dates().len(), 2);
+        assert!(!recurrences.limited());
+        assert_eq!(recurrences.reason(), None);
+    }
+
+    #[test]
+    fn count_of_legacy_functions_only_caps_the_results() {
+        let recurrences = expand(
+            ExpandOptions::with_max_results(Some(5)),
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=3",
+        );
+        assert_eq!(recurrences.dates().len(), 3);
+        assert!(!recurrences.limited());
+
+        let recurrences = expand(
+            ExpandOptions::with_max_results(Some(5_000)),
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=HOURLY",
+        );
+        assert_eq!(recurrences.dates().len(), 1000);
+        assert_eq!(recurrences.reason().as_deref(), Some("MaxResults"));
+    }
+
+    #[test]
+    fn can_ignore_the_count_of_rules() {
+        let options = ExpandOptions {
+            honor_count: false,
+            ..ExpandOptions::default()
+        };
+        let recurrences = expand(
+            options,
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=MONTHLY;COUNT=2",
+        );
+        assert_eq!(recurrences.dates().len(), 12);
+        assert!(!recurrences.limited());
+    }
+
+    #[cfg(not(tz_subset))]
+    #[test]
+    fn returns_the_dates_in_the_time_zone() {
+        let options = ExpandOptions {
+            time_zone: Some(Tz::America__Chicago),
+            ..ExpandOptions::default()
+        };
+        let recurrences = expand(
+            options,
+            "DTSTART;TZID=Europe/Berlin:20210301T090000\nRRULE:FREQ=WEEKLY;COUNT=3",
+        );
+        assert_eq!(
+            recurrences.dates(),
+            vec![
+                "2021-03-01T02:00:00-06:00",
+                "2021-03-08T02:00:00-06:00",
+                "2021-03-15T03:00:00-05:00",
+            ]
+        );
+    }
+}
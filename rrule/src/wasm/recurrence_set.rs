@@ -0,0 +1,254 @@
+use super::{
+    builder::Rule, limit_reason_name, parser_rrule_set, set_panic_hook, to_timestamps,
+    ExpandOptions, Recurrences, Timestamps,
+};
+use crate::{core::Tz, RRuleResult, RRuleSet, RRuleSetIter, ValidatorLimits};
+use chrono::DateTime;
+use wasm_bindgen::prelude::*;
+
+/// A rule set that is parsed once, and whose occurrences are then read a few at a
+/// time, e.g. for infinite scrolling. The position of the iteration is kept
+/// between calls, so every call continues where the previous one stopped.
+///
+/// The memory of the set has to be released with `free()` once it's not used
+/// anymore.
+#[wasm_bindgen]
+pub struct RecurrenceSet {
+    rrule_set: RRuleSet,
+    iter: RRuleSetIter,
+    /// The last occurrence returned, to skip the same date generated by several rules.
+    last: Option<DateTime<Tz>>,
+}
+
+#[wasm_bindgen]
+impl RecurrenceSet {
+    /// Parses the rule set `rules`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` with the `code` and `span` of the problem if the rules
+    /// can't be parsed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(rules: &str) -> Result<Self, JsValue> {
+        set_panic_hook();
+
+        Ok(Self::from_rrule_set(parser_rrule_set(rules)?))
+    }
+
+    /// The start date of the set as an RFC 3339 string.
+    #[wasm_bindgen(getter, js_name = dtStart)]
+    pub fn dt_start(&self) -> String {
+        self.rrule_set.get_dt_start().to_rfc3339()
+    }
+
+    /// The rules of the set.
+    #[wasm_bindgen(getter)]
+    pub fn rules(&self) -> Vec<Rule> {
+        self.rrule_set
+            .get_rrule()
+            .iter()
+            .cloned()
+            .map(Rule::from)
+            .collect()
+    }
+
+    /// The exclusion rules of the set.
+    #[cfg(feature = "exrule")]
+    #[wasm_bindgen(getter)]
+    pub fn exrules(&self) -> Vec<Rule> {
+        self.rrule_set
+            .get_exrule()
+            .iter()
+            .cloned()
+            .map(Rule::from)
+            .collect()
+    }
+
+    /// The additional dates of the set as RFC 3339 strings.
+    #[wasm_bindgen(getter)]
+    pub fn rdates(&self) -> Vec<String> {
+        self.rrule_set
+            .get_rdate()
+            .iter()
+            .map(DateTime::to_rfc3339)
+            .collect()
+    }
+
+    /// The excluded dates of the set as RFC 3339 strings.
+    #[wasm_bindgen(getter)]
+    pub fn exdates(&self) -> Vec<String> {
+        self.rrule_set
+            .get_exdate()
+            .iter()
+            .map(DateTime::to_rfc3339)
+            .collect()
+    }
+
+    /// Returns the rule set as a string, which can be parsed again.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_rules_string(&self) -> String {
+        self.rrule_set.to_string()
+    }
+
+    /// Returns the next occurrence as an RFC 3339 string, or `undefined` once
+    /// there are no more occurrences.
+    #[wasm_bindgen(js_name = next)]
+    pub fn next_occurrence(&mut self) -> Option<String> {
+        self.next_date().map(|date| date.to_rfc3339())
+    }
+
+    /// Returns the next `n` occurrences as RFC 3339 strings, or fewer if there are
+    /// no more occurrences.
+    pub fn take(&mut self, n: u32) -> Vec<String> {
+        (0..n).map_while(|_| self.next_occurrence()).collect()
+    }
+
+    /// Returns the occurrences between the dates `after` and `before`, inclusive,
+    /// as `{ dates, limited, reason }`, without moving the position of `next()`
+    /// and `take()`. `options` is the optional options object of
+    /// `getRecurrencesBetween`, and `limited` is `true` when a limit, like the
+    /// default `maxResults` of `1000`, stopped the expansion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `after`, `before` or `options` is not valid.
+    pub fn between(
+        &self,
+        after: &str,
+        before: &str,
+        options: JsValue,
+    ) -> Result<Recurrences, JsValue> {
+        let options = ExpandOptions::from_js(&options)?;
+        Ok(self.dates_between(after, before, &options)?.into())
+    }
+
+    /// Returns the next `n` occurrences as milliseconds since the epoch, like
+    /// `take()`, but without formatting them as strings.
+    #[wasm_bindgen(js_name = takeTimestamps)]
+    pub fn take_timestamps(&mut self, n: u32) -> Vec<f64> {
+        let dates: Vec<_> = (0..n).map_while(|_| self.next_date()).collect();
+        to_timestamps(&dates)
+    }
+
+    /// Returns the occurrences between `after` and `before` as milliseconds since
+    /// the epoch, like `between()`, but without formatting them as strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` if `after`, `before` or `options` is not valid.
+    #[wasm_bindgen(js_name = betweenTimestamps)]
+    pub fn between_timestamps(
+        &self,
+        after: &str,
+        before: &str,
+        options: JsValue,
+    ) -> Result<Timestamps, JsValue> {
+        let options = ExpandOptions::from_js(&options)?;
+        Ok(self.dates_between(after, before, &options)?.into())
+    }
+
+    /// The limit that stopped the iteration early, like `"MaxIterations"`, or
+    /// `undefined` if the iteration wasn't stopped.
+    #[wasm_bindgen(getter, js_name = limitReason)]
+    pub fn limit_reason(&self) -> Option<String> {
+        self.iter
+            .limit_reason()
+            .map(|reason| limit_reason_name(reason).to_owned())
+    }
+}
+
+impl RecurrenceSet {
+    pub(super) fn from_rrule_set(rrule_set: RRuleSet) -> Self {
+        // The number of occurrences isn't limited, only the iterations spent looking
+        // for the next one, which stops the iteration of rules that never occur.
+        let limits = ValidatorLimits::UNLIMITED
+            .max_iterations(ValidatorLimits::DEFAULT.get_max_iterations());
+        let iter = rrule_set.clone().limits(limits).into_iter();
+        Self {
+            rrule_set,
+            iter,
+            last: None,
+        }
+    }
+
+    fn dates_between(
+        &self,
+        after: &str,
+        before: &str,
+        options: &ExpandOptions,
+    ) -> Result<RRuleResult, JsValue> {
+        let after = options.parse_date("after", after)?;
+        let before = options.parse_date("before", before)?;
+        Ok(options.expand_dates(self.rrule_set.clone(), after, before))
+    }
+
+    fn next_date(&mut self) -> Option<DateTime<Tz>> {
+        let date = self.iter.find(|date| self.last.as_ref() != Some(date))?;
+        self.last = Some(date);
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LimitReason;
+
+    const RULES: &str = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY\nRDATE:20210102T090000Z";
+
+    #[test]
+    fn continues_where_the_previous_call_stopped() {
+        let mut recurrence_set = RecurrenceSet::new(RULES).unwrap();
+        assert_eq!(
+            recurrence_set.next_occurrence().as_deref(),
+            Some("2021-01-01T09:00:00+00:00")
+        );
+        assert_eq!(
+            recurrence_set.take(2),
+            vec!["2021-01-02T09:00:00+00:00", "2021-01-03T09:00:00+00:00"]
+        );
+
+        let between: Recurrences = recurrence_set
+            .dates_between(
+                "2021-01-02T00:00:00Z",
+                "2021-01-03T09:00:00Z",
+                &ExpandOptions::default(),
+            )
+            .unwrap()
+            .into();
+        assert_eq!(
+            between.dates(),
+            vec!["2021-01-02T09:00:00+00:00", "2021-01-03T09:00:00+00:00"]
+        );
+        assert!(!between.limited());
+        assert_eq!(
+            recurrence_set.next_occurrence().as_deref(),
+            Some("2021-01-04T09:00:00+00:00")
+        );
+        assert_eq!(
+            recurrence_set.take_timestamps(2),
+            vec![1_609_837_200_000.0, 1_609_923_600_000.0]
+        );
+        assert_eq!(recurrence_set.limit_reason(), None);
+    }
+
+    #[test]
+    fn reports_truncation_but_does_not_limit_the_iteration() {
+        let mut recurrence_set = RecurrenceSet::new(RULES).unwrap();
+        let between = recurrence_set
+            .dates_between(
+                "2021-01-01T00:00:00Z",
+                "2030-01-01T00:00:00Z",
+                &ExpandOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(between.dates.len(), 1000);
+        assert!(between.limited);
+        assert_eq!(between.limit_reason, Some(LimitReason::MaxResults));
+
+        // More than the default `maxResults` of the limits can be read.
+        let count = u32::from(u16::MAX) + 10;
+        assert_eq!(recurrence_set.take_timestamps(count).len(), count as usize);
+        assert_eq!(recurrence_set.limit_reason(), None);
+    }
+}
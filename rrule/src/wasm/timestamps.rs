@@ -0,0 +1,101 @@
+use super::limit_reason_name;
+use crate::{core::Tz, RRuleResult};
+use chrono::{DateTime, Offset};
+use wasm_bindgen::prelude::*;
+
+/// Occurrences as milliseconds since the epoch, with the offset from UTC that each
+/// of them has in the timezone of the rule set, and whether more of them might exist.
+#[wasm_bindgen]
+pub struct Timestamps {
+    timestamps: Vec<f64>,
+    offsets: Vec<i32>,
+    limited: bool,
+    reason: Option<&'static str>,
+}
+
+#[wasm_bindgen]
+impl Timestamps {
+    /// The milliseconds since the epoch of the occurrences, as a `Float64Array`.
+    #[wasm_bindgen(getter)]
+    pub fn timestamps(&self) -> Vec<f64> {
+        self.timestamps.clone()
+    }
+
+    /// The offsets from UTC of the occurrences in seconds, as an `Int32Array`.
+    /// Positive offsets are east of UTC, unlike `Date.getTimezoneOffset()`.
+    #[wasm_bindgen(getter)]
+    pub fn offsets(&self) -> Vec<i32> {
+        self.offsets.clone()
+    }
+
+    /// Whether the expansion was stopped by a limit, in which case there may be
+    /// more occurrences than `timestamps`.
+    #[wasm_bindgen(getter)]
+    pub fn limited(&self) -> bool {
+        self.limited
+    }
+
+    /// The limit that stopped the expansion, like `"MaxResults"`, or `undefined`.
+    #[wasm_bindgen(getter)]
+    pub fn reason(&self) -> Option<String> {
+        self.reason.map(str::to_owned)
+    }
+}
+
+impl Timestamps {
+    pub(super) fn new(dates: &[DateTime<Tz>]) -> Self {
+        Self {
+            timestamps: to_timestamps(dates),
+            offsets: dates
+                .iter()
+                .map(|date| date.offset().fix().local_minus_utc())
+                .collect(),
+            limited: false,
+            reason: None,
+        }
+    }
+}
+
+impl From<RRuleResult> for Timestamps {
+    fn from(result: RRuleResult) -> Self {
+        Self {
+            limited: result.limited,
+            reason: result.limit_reason.map(limit_reason_name),
+            ..Self::new(&result.dates)
+        }
+    }
+}
+
+/// Returns the milliseconds since the epoch of `dates`, which are exact in a `f64`
+/// for all the years that are supported.
+pub(super) fn to_timestamps(dates: &[DateTime<Tz>]) -> Vec<f64> {
+    dates
+        .iter()
+        .map(|date| date.timestamp_millis() as f64)
+        .collect()
+}
+
+#[cfg(test)]
+#[cfg(not(tz_subset))]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn keeps_the_offsets_of_the_timezone() {
+        let dates = [
+            Tz::Europe__Berlin
+                .with_ymd_and_hms(2021, 3, 27, 9, 0, 0)
+                .unwrap(),
+            Tz::Europe__Berlin
+                .with_ymd_and_hms(2021, 3, 28, 9, 0, 0)
+                .unwrap(),
+        ];
+        let timestamps = Timestamps::new(&dates);
+        assert_eq!(
+            timestamps.timestamps(),
+            vec![1_616_832_000_000.0, 1_616_914_800_000.0]
+        );
+        assert_eq!(timestamps.offsets(), vec![3600, 7200]);
+    }
+}
@@ -0,0 +1,106 @@
+use super::to_js_error;
+use crate::{core::Tz, ParseError, RRuleError};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// The formats of wall-clock times, which are placed in a timezone given separately.
+const WALL_CLOCK_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+
+/// Parses a timezone given by JavaScript: an IANA name like `America/Chicago`, or a
+/// fixed offset like `+05:30` or `UTC-0600`.
+pub(super) fn parse_time_zone(name: &str) -> Result<Tz, JsValue> {
+    if name.starts_with(['+', '-']) {
+        if let Ok(offset) = FixedOffset::from_str(name) {
+            return Ok(Tz::FixedOffset(offset));
+        }
+    }
+    Tz::from_tzid(name)
+        .map(|(tz, _)| tz)
+        .map_err(|err| to_js_error(&err.into()))
+}
+
+/// Parses the `property` date, either as an RFC 3339 date with an offset, or as a
+/// wall-clock time like `2021-03-14T09:00` in `tz`. The date is returned in `tz`.
+///
+/// A wall-clock time which occurs twice in `tz` is resolved to the earlier of the
+/// two, and one which doesn't exist, in a daylight saving time gap, is an error.
+pub(super) fn parse_date_in(property: &str, date: &str, tz: &Tz) -> Result<DateTime<Tz>, JsValue> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(date) {
+        return Ok(datetime.with_timezone(tz));
+    }
+    let error = |err: ParseError| to_js_error(&RRuleError::from(err));
+    let naive = WALL_CLOCK_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .ok_or_else(|| {
+            error(ParseError::InvalidDateTime {
+                value: date.into(),
+                property: property.into(),
+            })
+        })?;
+    tz.from_local_datetime(&naive).earliest().ok_or_else(|| {
+        error(ParseError::InvalidDateTimeInLocalTimezone {
+            value: date.into(),
+            property: property.into(),
+        })
+    })
+}
+
+/// Returns `date` as it appears in `tz`. Floating dates are given the same
+/// wall-clock time in `tz`.
+pub(super) fn in_time_zone(date: &DateTime<Tz>, tz: &Tz) -> DateTime<Tz> {
+    tz.resolve_floating(date)
+        .earliest()
+        .unwrap_or_else(|| date.with_timezone(tz))
+}
+
+#[cfg(test)]
+#[cfg(not(tz_subset))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_offsets() {
+        assert_eq!(
+            parse_time_zone("America/Chicago").unwrap(),
+            Tz::America__Chicago
+        );
+        let offset = FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+        assert_eq!(parse_time_zone("+05:30").unwrap(), Tz::FixedOffset(offset));
+        assert_eq!(
+            parse_time_zone("UTC+0530").unwrap(),
+            Tz::FixedOffset(offset)
+        );
+    }
+
+    #[test]
+    fn parses_rfc_3339_and_wall_clock_times() {
+        let chicago = Tz::America__Chicago;
+        let expected = chicago.with_ymd_and_hms(2021, 3, 14, 9, 0, 0).unwrap();
+        for date in [
+            "2021-03-14T09:00",
+            "2021-03-14T09:00:00",
+            "2021-03-14T14:00:00Z",
+        ] {
+            let parsed = parse_date_in("after", date, &chicago).unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.to_rfc3339(), "2021-03-14T09:00:00-05:00");
+        }
+    }
+
+    #[test]
+    fn places_floating_dates_in_the_time_zone() {
+        let floating = Tz::FLOATING.with_ymd_and_hms(2021, 7, 1, 9, 0, 0).unwrap();
+        let utc = Tz::UTC.with_ymd_and_hms(2021, 7, 1, 9, 0, 0).unwrap();
+        let chicago = Tz::America__Chicago;
+        assert_eq!(
+            in_time_zone(&floating, &chicago).to_rfc3339(),
+            "2021-07-01T09:00:00-05:00"
+        );
+        assert_eq!(
+            in_time_zone(&utc, &chicago).to_rfc3339(),
+            "2021-07-01T04:00:00-05:00"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use super::set_panic_hook;
+use crate::{core::Tz, LocatedError, RRuleError, RRuleSet};
+use chrono::TimeZone;
+use wasm_bindgen::prelude::*;
+
+/// Validates the rule set `rules` without expanding it, and returns every problem
+/// found in it, e.g. to validate a form as the user types.
+#[wasm_bindgen]
+pub fn validate(rules: &str) -> Validation {
+    set_panic_hook();
+
+    let parsed = RRuleSet::new(Tz::UTC.timestamp_nanos(0)).set_from_lines_checked(rules, true);
+    match parsed {
+        Ok(rrule_set) => Validation {
+            problems: vec![],
+            normalized: Some(rrule_set.to_string()),
+        },
+        Err(mut errors) => {
+            errors.sort_by_key(|err| err.line.unwrap_or(usize::MAX));
+            Validation {
+                problems: errors.into_iter().map(Problem::from).collect(),
+                normalized: None,
+            }
+        }
+    }
+}
+
+/// The result of `validate()`.
+#[wasm_bindgen]
+pub struct Validation {
+    problems: Vec<Problem>,
+    normalized: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Validation {
+    /// Whether the rule set is valid.
+    #[wasm_bindgen(getter)]
+    pub fn valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// The problems found, sorted by line. Problems which concern the rule set as
+    /// a whole come last.
+    #[wasm_bindgen(getter)]
+    pub fn problems(&self) -> Vec<Problem> {
+        self.problems.clone()
+    }
+
+    /// The rule set in its normalised form, if it is valid.
+    #[wasm_bindgen(getter)]
+    pub fn normalized(&self) -> Option<String> {
+        self.normalized.clone()
+    }
+}
+
+/// A problem found by `validate()`.
+#[derive(Debug, Clone)]
+#[wasm_bindgen]
+pub struct Problem {
+    code: &'static str,
+    message: String,
+    property: Option<String>,
+    line: Option<usize>,
+    span: Option<ProblemSpan>,
+}
+
+#[wasm_bindgen]
+impl Problem {
+    /// The stable code of the kind of problem, like `"parse.invalid_by_hour"`.
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> String {
+        self.code.into()
+    }
+
+    /// The description of the problem.
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// The property or rule part the problem concerns, like `"BYHOUR"`, if it is
+    /// known.
+    #[wasm_bindgen(getter)]
+    pub fn property(&self) -> Option<String> {
+        self.property.clone()
+    }
+
+    /// The line of the problem, starting at `1`, if it concerns a single line.
+    #[wasm_bindgen(getter)]
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// The position of the problem in its line, if it is known.
+    #[wasm_bindgen(getter)]
+    pub fn span(&self) -> Option<ProblemSpan> {
+        self.span
+    }
+}
+
+impl From<LocatedError> for Problem {
+    fn from(err: LocatedError) -> Self {
+        let message = match &err.error {
+            RRuleError::ParserError(error, _) => error.to_string(),
+            RRuleError::ValidationError(error, _) => error.to_string(),
+            RRuleError::IterError(message) => message.clone(),
+        };
+        Self {
+            code: err.code(),
+            message,
+            property: err.rule_part().map(Into::into),
+            line: err.line,
+            span: err.span.map(|span| ProblemSpan {
+                line: span.line,
+                start: span.start,
+                end: span.end,
+            }),
+        }
+    }
+}
+
+/// The position of a problem in the input, as byte offsets in its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct ProblemSpan {
+    /// The line, starting at `1`.
+    pub line: u32,
+    /// The byte offset in the line where the span starts, starting at `0`.
+    pub start: u32,
+    /// The byte offset in the line where the span ends, exclusive.
+    pub end: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_problem() {
+        let validation =
+            validate("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24\nRDATE:2012");
+        assert!(!validation.valid());
+        assert_eq!(validation.normalized(), None);
+        let problems: Vec<_> = validation
+            .problems()
+            .iter()
+            .map(|problem| (problem.code(), problem.property(), problem.line()))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    "parse.invalid_by_hour".into(),
+                    Some("BYHOUR".into()),
+                    Some(2)
+                ),
+                (
+                    "parse.invalid_date_time".into(),
+                    Some("RDATE".into()),
+                    Some(3)
+                ),
+            ]
+        );
+        assert_eq!(
+            validation.problems()[0].span(),
+            Some(ProblemSpan {
+                line: 2,
+                start: 24,
+                end: 26
+            })
+        );
+        assert_eq!(
+            validation.problems()[0].message(),
+            "`24` is not a valid BYHOUR value. Expected a comma-separated list of values in range 0..=23, e.g. `1,3,4`"
+        );
+    }
+
+    #[test]
+    fn normalizes_valid_rules() {
+        let validation = validate("DTSTART:20210101T090000Z\nrrule:FREQ=DAILY;COUNT=2");
+        assert!(validation.valid());
+        assert_eq!(
+            validation.normalized().as_deref(),
+            Some(
+                "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+            )
+        );
+
+        let rules = "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1";
+        let normalized = validate(rules).normalized().unwrap();
+        assert_eq!(
+            normalized,
+            "DTSTART:20210131T090000Z\nRRULE:FREQ=MONTHLY;BYMONTHDAY=-1;BYHOUR=9;BYMINUTE=0;BYSECOND=0"
+        );
+        assert_eq!(validate(&normalized).normalized(), Some(normalized));
+    }
+}
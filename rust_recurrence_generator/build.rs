@@ -0,0 +1,20 @@
+//! Generates the C header of the C API in `src/c_api.rs` into `OUT_DIR`.
+//!
+//! `src/headers/c_api.h` is checked in for the `.xcframework`, and a test fails
+//! when it differs from the generated one. Building with `UPDATE_C_HEADER` set,
+//! like `make update_header` does, overwrites it with the generated header.
+use std::{env, error::Error, fs, path::PathBuf};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-changed=src/c_api.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=UPDATE_C_HEADER");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
+    let header = PathBuf::from(env::var("OUT_DIR")?).join("c_api.h");
+    cbindgen::generate(&crate_dir)?.write_to_file(&header);
+    if env::var_os("UPDATE_C_HEADER").is_some() {
+        fs::copy(&header, crate_dir.join("src/headers/c_api.h"))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,4 @@
+//! Generates the Kotlin and Swift bindings, see `make generate-bindings`.
+fn main() {
+    uniffi::uniffi_bindgen_main();
+}
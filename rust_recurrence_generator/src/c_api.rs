@@ -0,0 +1,466 @@
+//! The C API of the generator, of which `build.rs` generates `headers/c_api.h`.
+//!
+//! A rule set is parsed once into an opaque [`RecurrenceSet`] handle, which is
+//! then iterated with [`recurrence_generator_next`] or queried with
+//! [`recurrence_generator_between`]. Dates cross the boundary as seconds since the
+//! epoch.
+//!
+//! No function panics across the boundary: invalid arguments are reported with a
+//! [`RecurrenceGeneratorStatus`], and unexpected panics are caught and reported as
+//! [`RecurrenceGeneratorStatus::Panic`].
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use chrono::{DateTime, TimeZone};
+use rrule::{RRuleSet, RRuleSetIter, Tz, ValidatorLimits};
+
+use crate::recurrence_generator::{self, RecurrenceGenerator};
+
+/// The outcome of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceGeneratorStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The rules could not be parsed or are invalid.
+    InvalidRules = 3,
+    /// A date is outside the supported range.
+    InvalidDate = 4,
+    /// An unexpected internal error occurred.
+    Panic = 5,
+    /// [`recurrence_generator_next`] has no more dates.
+    Done = 6,
+}
+
+/// The error of a failed call.
+///
+/// `message` is owned by the library and has to be released with
+/// [`recurrence_generator_error_free`] before the error is passed to another call.
+/// It is null if the call succeeded.
+#[repr(C)]
+pub struct RecurrenceGeneratorError {
+    code: RecurrenceGeneratorStatus,
+    message: *mut c_char,
+}
+
+/// The limits of [`recurrence_generator_between`]. A value of `0` uses the
+/// default.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RecurrenceGeneratorLimits {
+    /// The max number of dates returned, `730` by default.
+    max_results: u32,
+    /// The max number of iterations without finding an occurrence, `100000` by
+    /// default.
+    max_iterations: u32,
+}
+
+/// Dates as seconds since the epoch.
+///
+/// `seconds` is owned by the library and has to be released with
+/// [`recurrence_generator_timestamps_free`].
+#[repr(C)]
+pub struct RecurrenceGeneratorTimestamps {
+    seconds: *mut i64,
+    len: usize,
+    /// Whether a limit stopped the query before all dates were found.
+    limited: bool,
+}
+
+impl RecurrenceGeneratorTimestamps {
+    const EMPTY: Self = Self {
+        seconds: ptr::null_mut(),
+        len: 0,
+        limited: false,
+    };
+}
+
+/// A parsed rule set and the position of its iteration.
+///
+/// Created with [`recurrence_generator_parse`] and released with
+/// [`recurrence_generator_free`].
+pub struct RecurrenceSet {
+    rrule_set: RRuleSet,
+    iter: RRuleSetIter,
+    /// The last date returned, to skip the same date generated by several rules.
+    last: Option<DateTime<Tz>>,
+}
+
+/// A failed call, which is written to the caller's [`RecurrenceGeneratorError`].
+struct Failure {
+    code: RecurrenceGeneratorStatus,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: RecurrenceGeneratorStatus, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Runs `f`, catching its panics. Writes its failure to `error` if it isn't null,
+/// and returns its status.
+///
+/// # Safety
+///
+/// `error` has to be null or valid for writes.
+unsafe fn run<T>(
+    error: *mut RecurrenceGeneratorError,
+    f: impl FnOnce() -> Result<T, Failure>,
+) -> Result<T, RecurrenceGeneratorStatus> {
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        Err(Failure::new(
+            RecurrenceGeneratorStatus::Panic,
+            "unexpected internal error",
+        ))
+    });
+    let (code, message) = match &result {
+        Ok(_) => (RecurrenceGeneratorStatus::Ok, ptr::null_mut()),
+        Err(failure) => (failure.code, to_c_string(&failure.message)),
+    };
+    if let Some(error) = error.as_mut() {
+        *error = RecurrenceGeneratorError { code, message };
+    } else if !message.is_null() {
+        drop(CString::from_raw(message));
+    }
+    result.map_err(|failure| failure.code)
+}
+
+/// Reads the `name` argument as a UTF-8 string.
+///
+/// # Safety
+///
+/// `value` has to be null or a valid NUL-terminated string.
+unsafe fn read_str<'a>(name: &str, value: *const c_char) -> Result<&'a str, Failure> {
+    if value.is_null() {
+        return Err(Failure::new(
+            RecurrenceGeneratorStatus::NullArgument,
+            format!("`{name}` is null"),
+        ));
+    }
+    CStr::from_ptr(value).to_str().map_err(|_| {
+        Failure::new(
+            RecurrenceGeneratorStatus::InvalidUtf8,
+            format!("`{name}` is not valid UTF-8"),
+        )
+    })
+}
+
+fn to_c_string(value: &str) -> *mut c_char {
+    CString::new(value.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+fn to_date(name: &str, seconds: i64) -> Result<DateTime<Tz>, Failure> {
+    Tz::UTC.timestamp_opt(seconds, 0).single().ok_or_else(|| {
+        Failure::new(
+            RecurrenceGeneratorStatus::InvalidDate,
+            format!("`{name}` is out of range"),
+        )
+    })
+}
+
+/// Parses the rule set `rules`, like `DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY`.
+///
+/// Returns null and fills `error`, if it isn't null, when the rules can't be
+/// parsed. The returned set has to be released with
+/// [`recurrence_generator_free`].
+///
+/// # Safety
+///
+/// `rules` has to be null or a valid NUL-terminated string, and `error` has to be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_parse(
+    rules: *const c_char,
+    error: *mut RecurrenceGeneratorError,
+) -> *mut RecurrenceSet {
+    run(error, || {
+        let rules = read_str("rules", rules)?;
+        let rrule_set = rules.parse::<RRuleSet>().map_err(|err| {
+            Failure::new(RecurrenceGeneratorStatus::InvalidRules, err.to_string())
+        })?;
+        // The limits stop the iteration of rules which never occur.
+        let rrule_set = rrule_set.limit();
+        let iter = rrule_set.into_iter();
+        Ok(Box::into_raw(Box::new(RecurrenceSet {
+            rrule_set,
+            iter,
+            last: None,
+        })))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Releases a set returned by [`recurrence_generator_parse`]. Does nothing if
+/// `set` is null.
+///
+/// # Safety
+///
+/// `set` has to be null or a set returned by [`recurrence_generator_parse`] that
+/// wasn't released yet.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_free(set: *mut RecurrenceSet) {
+    if !set.is_null() {
+        drop(Box::from_raw(set));
+    }
+}
+
+/// Writes the next date of `set` to `seconds`. Every call continues where the
+/// previous one stopped.
+///
+/// Returns [`RecurrenceGeneratorStatus::Done`], and leaves `seconds` unchanged,
+/// once there are no more dates. Returns the status of the call otherwise, and
+/// fills `error`, if it isn't null, when it failed.
+///
+/// # Safety
+///
+/// `set` has to be null or a valid set, `seconds` has to be null or valid for
+/// writes, and `error` has to be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_next(
+    set: *mut RecurrenceSet,
+    seconds: *mut i64,
+    error: *mut RecurrenceGeneratorError,
+) -> RecurrenceGeneratorStatus {
+    let result = run(error, || {
+        let (Some(set), false) = (set.as_mut(), seconds.is_null()) else {
+            return Err(Failure::new(
+                RecurrenceGeneratorStatus::NullArgument,
+                "`set` or `seconds` is null",
+            ));
+        };
+        let date = set.iter.find(|date| set.last.as_ref() != Some(date));
+        if date.is_some() {
+            set.last = date;
+        }
+        Ok(date.map(|date| date.timestamp()))
+    });
+    match result {
+        Ok(Some(next)) => {
+            *seconds = next;
+            RecurrenceGeneratorStatus::Ok
+        }
+        Ok(None) => RecurrenceGeneratorStatus::Done,
+        Err(code) => code,
+    }
+}
+
+/// Writes the dates of `set` between `after` and `before`, inclusive, to
+/// `timestamps`, without moving the position of [`recurrence_generator_next`].
+///
+/// Returns the status of the call, and fills `error`, if it isn't null, when it
+/// failed. `timestamps` is left empty on failure, and has to be released with
+/// [`recurrence_generator_timestamps_free`] otherwise.
+///
+/// # Safety
+///
+/// `set` has to be null or a valid set, `timestamps` has to be null or valid for
+/// writes, and `error` has to be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_between(
+    set: *const RecurrenceSet,
+    after: i64,
+    before: i64,
+    limits: RecurrenceGeneratorLimits,
+    timestamps: *mut RecurrenceGeneratorTimestamps,
+    error: *mut RecurrenceGeneratorError,
+) -> RecurrenceGeneratorStatus {
+    let result = run(error, || {
+        let (Some(set), false) = (set.as_ref(), timestamps.is_null()) else {
+            return Err(Failure::new(
+                RecurrenceGeneratorStatus::NullArgument,
+                "`set` or `timestamps` is null",
+            ));
+        };
+        let after = to_date("after", after)?;
+        let before = to_date("before", before)?;
+        Ok(set.between(after, before, limits))
+    });
+    let Some(timestamps) = timestamps.as_mut() else {
+        return RecurrenceGeneratorStatus::NullArgument;
+    };
+    match result {
+        Ok(result) => {
+            *timestamps = result;
+            RecurrenceGeneratorStatus::Ok
+        }
+        Err(code) => {
+            *timestamps = RecurrenceGeneratorTimestamps::EMPTY;
+            code
+        }
+    }
+}
+
+impl RecurrenceSet {
+    fn between(
+        &self,
+        after: DateTime<Tz>,
+        before: DateTime<Tz>,
+        limits: RecurrenceGeneratorLimits,
+    ) -> RecurrenceGeneratorTimestamps {
+        let mut validator_limits = ValidatorLimits::DEFAULT
+            .max_results(u32::from(RecurrenceGenerator::MAX_OCCURRENCES_COUNT));
+        if limits.max_results > 0 {
+            validator_limits = validator_limits.max_results(limits.max_results);
+        }
+        if limits.max_iterations > 0 {
+            validator_limits = validator_limits.max_iterations(limits.max_iterations);
+        }
+        let result = self
+            .rrule_set
+            .clone()
+            .limits(validator_limits)
+            .after(after)
+            .before(before)
+            .all_within_limits();
+
+        let seconds: Box<[i64]> = result.dates.iter().map(DateTime::timestamp).collect();
+        let len = seconds.len();
+        RecurrenceGeneratorTimestamps {
+            seconds: Box::into_raw(seconds).cast(),
+            len,
+            limited: result.limited,
+        }
+    }
+}
+
+/// Releases the dates written by [`recurrence_generator_between`], and leaves
+/// `timestamps` empty. Does nothing if `timestamps` is null.
+///
+/// # Safety
+///
+/// `timestamps` has to be null or filled by [`recurrence_generator_between`].
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_timestamps_free(
+    timestamps: *mut RecurrenceGeneratorTimestamps,
+) {
+    let Some(timestamps) = timestamps.as_mut() else {
+        return;
+    };
+    if !timestamps.seconds.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            timestamps.seconds,
+            timestamps.len,
+        )));
+    }
+    *timestamps = RecurrenceGeneratorTimestamps::EMPTY;
+}
+
+/// Releases the message of `error`, and resets its `code`. Does nothing if
+/// `error` is null.
+///
+/// # Safety
+///
+/// `error` has to be null or filled by a call of this library.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_error_free(error: *mut RecurrenceGeneratorError) {
+    let Some(error) = error.as_mut() else {
+        return;
+    };
+    if !error.message.is_null() {
+        drop(CString::from_raw(error.message));
+    }
+    *error = RecurrenceGeneratorError {
+        code: RecurrenceGeneratorStatus::Ok,
+        message: ptr::null_mut(),
+    };
+}
+
+/// The dates of [`recurrence_generator_generate`] as RFC 3339 strings, or its
+/// error.
+#[repr(C)]
+pub struct StringArray {
+    // Pointer to array of C strings
+    strings: *mut *mut c_char,
+
+    // Number of strings
+    len: usize,
+
+    // Pointer to an error string (optional, can be null)
+    error: *mut c_char,
+}
+
+/// Returns the dates of `rule` between the RFC 3339 dates `after` and `before`,
+/// which has to be released with [`free_string_array`].
+///
+/// Prefer [`recurrence_generator_parse`] and [`recurrence_generator_between`].
+///
+/// # Safety
+///
+/// The arguments have to be null or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn recurrence_generator_generate(
+    rule: *const c_char,
+    after: *const c_char,
+    before: *const c_char,
+) -> *mut StringArray {
+    let mut error = RecurrenceGeneratorError {
+        code: RecurrenceGeneratorStatus::Ok,
+        message: ptr::null_mut(),
+    };
+    let dates = run(&mut error, || {
+        let rule = read_str("rule", rule)?;
+        let after = read_str("after", after)?;
+        let before = read_str("before", before)?;
+        RecurrenceGenerator::recurrence_dates_between(rule, after, before).map_err(
+            |err| match err {
+                recurrence_generator::RecurrenceGeneratorError::RRule(error) => {
+                    Failure::new(RecurrenceGeneratorStatus::InvalidRules, error.to_string())
+                }
+                recurrence_generator::RecurrenceGeneratorError::Parsing => Failure::new(
+                    RecurrenceGeneratorStatus::InvalidDate,
+                    "error parsing before/after dates",
+                ),
+            },
+        )
+    })
+    .unwrap_or_default();
+    let strings: Box<[*mut c_char]> = dates
+        .iter()
+        .map(|date| to_c_string(&date.to_rfc3339()))
+        .collect();
+    let len = strings.len();
+    Box::into_raw(Box::new(StringArray {
+        strings: Box::into_raw(strings).cast(),
+        len,
+        error: error.message,
+    }))
+}
+
+/// Releases an array returned by [`recurrence_generator_generate`]. Does nothing
+/// if `array` is null.
+///
+/// # Safety
+///
+/// `array` has to be null or an array returned by
+/// [`recurrence_generator_generate`] that wasn't released yet.
+#[no_mangle]
+pub unsafe extern "C" fn free_string_array(array: *mut StringArray) {
+    if array.is_null() {
+        return;
+    }
+    let array = Box::from_raw(array);
+    if !array.error.is_null() {
+        drop(CString::from_raw(array.error));
+    }
+    if !array.strings.is_null() {
+        let strings = Box::from_raw(ptr::slice_from_raw_parts_mut(array.strings, array.len));
+        for &string in strings.iter() {
+            if !string.is_null() {
+                drop(CString::from_raw(string));
+            }
+        }
+    }
+}
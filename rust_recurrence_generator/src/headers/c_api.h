@@ -1,16 +1,175 @@
 #ifndef RUST_RECURRENCE_GENERATOR_H
 #define RUST_RECURRENCE_GENERATOR_H
 
-#include <stdio.h>
+/* Generated by cbindgen from `src/c_api.rs`, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
 #include <stdlib.h>
 
-typedef struct {
-    char** strings;
-    size_t len;
-    char* error;
+// The outcome of a call.
+typedef enum RecurrenceGeneratorStatus {
+  // The call succeeded.
+  RECURRENCE_GENERATOR_STATUS_OK = 0,
+  // A required pointer argument was null.
+  RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not valid UTF-8.
+  RECURRENCE_GENERATOR_STATUS_INVALID_UTF8 = 2,
+  // The rules could not be parsed or are invalid.
+  RECURRENCE_GENERATOR_STATUS_INVALID_RULES = 3,
+  // A date is outside the supported range.
+  RECURRENCE_GENERATOR_STATUS_INVALID_DATE = 4,
+  // An unexpected internal error occurred.
+  RECURRENCE_GENERATOR_STATUS_PANIC = 5,
+  // [`recurrence_generator_next`] has no more dates.
+  RECURRENCE_GENERATOR_STATUS_DONE = 6,
+} RecurrenceGeneratorStatus;
+
+// A parsed rule set and the position of its iteration.
+//
+// Created with [`recurrence_generator_parse`] and released with
+// [`recurrence_generator_free`].
+typedef struct RecurrenceSet RecurrenceSet;
+
+// The error of a failed call.
+//
+// `message` is owned by the library and has to be released with
+// [`recurrence_generator_error_free`] before the error is passed to another call.
+// It is null if the call succeeded.
+typedef struct RecurrenceGeneratorError {
+  enum RecurrenceGeneratorStatus code;
+  char *message;
+} RecurrenceGeneratorError;
+
+// The limits of [`recurrence_generator_between`]. A value of `0` uses the
+// default.
+typedef struct RecurrenceGeneratorLimits {
+  // The max number of dates returned, `730` by default.
+  uint32_t max_results;
+  // The max number of iterations without finding an occurrence, `100000` by
+  // default.
+  uint32_t max_iterations;
+} RecurrenceGeneratorLimits;
+
+// Dates as seconds since the epoch.
+//
+// `seconds` is owned by the library and has to be released with
+// [`recurrence_generator_timestamps_free`].
+typedef struct RecurrenceGeneratorTimestamps {
+  int64_t *seconds;
+  size_t len;
+  // Whether a limit stopped the query before all dates were found.
+  bool limited;
+} RecurrenceGeneratorTimestamps;
+
+// The dates of [`recurrence_generator_generate`] as RFC 3339 strings, or its
+// error.
+typedef struct StringArray {
+  char **strings;
+  size_t len;
+  char *error;
 } StringArray;
 
-StringArray* recurrence_generator_generate(const char *rule, const char *after, const char *before);
-void free_string_array(StringArray* array);
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parses the rule set `rules`, like `DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY`.
+//
+// Returns null and fills `error`, if it isn't null, when the rules can't be
+// parsed. The returned set has to be released with
+// [`recurrence_generator_free`].
+//
+// # Safety
+//
+// `rules` has to be null or a valid NUL-terminated string, and `error` has to be
+// null or valid for writes.
+struct RecurrenceSet *recurrence_generator_parse(const char *rules,
+                                                 struct RecurrenceGeneratorError *error);
+
+// Releases a set returned by [`recurrence_generator_parse`]. Does nothing if
+// `set` is null.
+//
+// # Safety
+//
+// `set` has to be null or a set returned by [`recurrence_generator_parse`] that
+// wasn't released yet.
+void recurrence_generator_free(struct RecurrenceSet *set);
+
+// Writes the next date of `set` to `seconds`. Every call continues where the
+// previous one stopped.
+//
+// Returns [`RecurrenceGeneratorStatus::Done`], and leaves `seconds` unchanged,
+// once there are no more dates. Returns the status of the call otherwise, and
+// fills `error`, if it isn't null, when it failed.
+//
+// # Safety
+//
+// `set` has to be null or a valid set, `seconds` has to be null or valid for
+// writes, and `error` has to be null or valid for writes.
+enum RecurrenceGeneratorStatus recurrence_generator_next(struct RecurrenceSet *set,
+                                                         int64_t *seconds,
+                                                         struct RecurrenceGeneratorError *error);
+
+// Writes the dates of `set` between `after` and `before`, inclusive, to
+// `timestamps`, without moving the position of [`recurrence_generator_next`].
+//
+// Returns the status of the call, and fills `error`, if it isn't null, when it
+// failed. `timestamps` is left empty on failure, and has to be released with
+// [`recurrence_generator_timestamps_free`] otherwise.
+//
+// # Safety
+//
+// `set` has to be null or a valid set, `timestamps` has to be null or valid for
+// writes, and `error` has to be null or valid for writes.
+enum RecurrenceGeneratorStatus recurrence_generator_between(const struct RecurrenceSet *set,
+                                                            int64_t after,
+                                                            int64_t before,
+                                                            struct RecurrenceGeneratorLimits limits,
+                                                            struct RecurrenceGeneratorTimestamps *timestamps,
+                                                            struct RecurrenceGeneratorError *error);
+
+// Releases the dates written by [`recurrence_generator_between`], and leaves
+// `timestamps` empty. Does nothing if `timestamps` is null.
+//
+// # Safety
+//
+// `timestamps` has to be null or filled by [`recurrence_generator_between`].
+void recurrence_generator_timestamps_free(struct RecurrenceGeneratorTimestamps *timestamps);
+
+// Releases the message of `error`, and resets its `code`. Does nothing if
+// `error` is null.
+//
+// # Safety
+//
+// `error` has to be null or filled by a call of this library.
+void recurrence_generator_error_free(struct RecurrenceGeneratorError *error);
+
+// Returns the dates of `rule` between the RFC 3339 dates `after` and `before`,
+// which has to be released with [`free_string_array`].
+//
+// Prefer [`recurrence_generator_parse`] and [`recurrence_generator_between`].
+//
+// # Safety
+//
+// The arguments have to be null or valid NUL-terminated strings.
+struct StringArray *recurrence_generator_generate(const char *rule,
+                                                  const char *after,
+                                                  const char *before);
+
+// Releases an array returned by [`recurrence_generator_generate`]. Does nothing
+// if `array` is null.
+//
+// # Safety
+//
+// `array` has to be null or an array returned by
+// [`recurrence_generator_generate`] that wasn't released yet.
+void free_string_array(struct StringArray *array);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
 
-#endif
+#endif  /* RUST_RECURRENCE_GENERATOR_H */
@@ -7,7 +7,7 @@ pub struct RecurrenceGenerator;
 impl RecurrenceGenerator {
     // Google limits their recurrence generations to 730 instances
     // See: https://support.google.com/calendar/thread/51073472/daily-recurring-event-has-stopped-recurring
-    const MAX_OCCURRENCES_COUNT: u16 = 730;
+    pub(crate) const MAX_OCCURRENCES_COUNT: u16 = 730;
 
     pub(crate) fn recurrence_dates_between(
         rules: &str,
@@ -18,8 +18,8 @@ impl RecurrenceGenerator {
 
         match (
             parsed_rule_set,
-            RecurrenceGenerator::parse_date(after),
-            RecurrenceGenerator::parse_date(before),
+            Self::parse_date(after),
+            Self::parse_date(before),
         ) {
             (Ok(rule_set), Ok(after), Ok(before)) => {
                 let dates = rule_set
@@ -27,10 +27,10 @@ impl RecurrenceGenerator {
                     .before(before)
                     .all(Self::MAX_OCCURRENCES_COUNT)
                     .dates;
-                return Ok(dates);
+                Ok(dates)
             }
-            (Ok(_), _, _) => return Err(RecurrenceGeneratorError::Parsing),
-            (Err(error), _, _) => return Err(RecurrenceGeneratorError::RRule(error)),
+            (Ok(_), _, _) => Err(RecurrenceGeneratorError::Parsing),
+            (Err(error), _, _) => Err(RecurrenceGeneratorError::RRule(error)),
         }
     }
 
@@ -0,0 +1,52 @@
+use std::fmt::{self, Display};
+
+use rrule::{ParseError, RRuleError};
+
+/// The error of a rule set that can't be parsed, built or expanded.
+///
+/// `code` is the stable code of the kind of error, like `parse.invalid_by_hour`,
+/// and `detail` describes it. The field isn't named `message`, which is taken by
+/// the exceptions in Kotlin.
+#[derive(Debug, uniffi::Error)]
+pub enum RecurrenceError {
+    /// The rules can't be parsed.
+    Parse { code: String, detail: String },
+    /// The rules are invalid.
+    Validation { code: String, detail: String },
+    /// The rules can't be expanded.
+    Iteration { code: String, detail: String },
+}
+
+impl Display for RecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Self::Parse { code, detail }
+        | Self::Validation { code, detail }
+        | Self::Iteration { code, detail }) = self;
+        write!(f, "{detail} ({code})")
+    }
+}
+
+impl std::error::Error for RecurrenceError {}
+
+impl From<RRuleError> for RecurrenceError {
+    fn from(err: RRuleError) -> Self {
+        let code = err.code().to_string();
+        match err {
+            RRuleError::ParserError(error, _) => Self::Parse {
+                code,
+                detail: error.to_string(),
+            },
+            RRuleError::ValidationError(error, _) => Self::Validation {
+                code,
+                detail: error.to_string(),
+            },
+            RRuleError::IterError(detail) => Self::Iteration { code, detail },
+        }
+    }
+}
+
+impl From<ParseError> for RecurrenceError {
+    fn from(err: ParseError) -> Self {
+        RRuleError::from(err).into()
+    }
+}
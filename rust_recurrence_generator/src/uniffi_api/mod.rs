@@ -0,0 +1,27 @@
+//! The UniFFI bindings of the generator, from which `uniffi-bindgen` generates the
+//! Kotlin and Swift bindings.
+//!
+//! Unlike the C API, rule sets, rules and occurrences are typed objects, and dates
+//! are `java.time.Instant`s in Kotlin and `Date`s in Swift.
+mod error;
+mod recurrence_set;
+mod rule;
+
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use rrule::Tz;
+
+pub use self::error::RecurrenceError;
+pub use self::recurrence_set::{
+    LimitReason, Limits, RecurrenceIterator, RecurrenceSet, Recurrences,
+};
+pub use self::rule::{rule_to_string, Frequency, NWeekday, Rule, Weekday};
+
+fn to_date(time: SystemTime, tz: Tz) -> DateTime<Tz> {
+    DateTime::<Utc>::from(time).with_timezone(&tz)
+}
+
+fn to_system_times(dates: &[DateTime<Tz>]) -> Vec<SystemTime> {
+    dates.iter().map(|&date| date.into()).collect()
+}
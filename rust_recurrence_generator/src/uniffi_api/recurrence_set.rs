@@ -0,0 +1,308 @@
+use std::{
+    fmt::{self, Display},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use chrono::DateTime;
+use rrule::{RRuleSet, RRuleSetIter, Tz, ValidatorLimits};
+
+use super::{to_date, to_system_times, RecurrenceError, Rule};
+
+/// The limits of `RecurrenceSet.between()`.
+#[derive(Debug, Clone, Copy, uniffi::Record)]
+pub struct Limits {
+    /// The max number of occurrences returned, `730` by default like in the C API.
+    #[uniffi(default = 730)]
+    pub max_results: u32,
+    /// The max number of iterations without finding an occurrence.
+    #[uniffi(default = 100000)]
+    pub max_iterations: u32,
+}
+
+/// Why an expansion stopped before all occurrences were found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum LimitReason {
+    MaxIterations,
+    MaxResults,
+    YearRange,
+    DeadlineExceeded,
+    Cancelled,
+    /// A reason added in a later version of `rrule`.
+    Other,
+}
+
+impl From<rrule::LimitReason> for LimitReason {
+    fn from(reason: rrule::LimitReason) -> Self {
+        match reason {
+            rrule::LimitReason::MaxIterations => Self::MaxIterations,
+            rrule::LimitReason::MaxResults => Self::MaxResults,
+            rrule::LimitReason::YearRange => Self::YearRange,
+            rrule::LimitReason::DeadlineExceeded => Self::DeadlineExceeded,
+            rrule::LimitReason::Cancelled => Self::Cancelled,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The occurrences returned by `RecurrenceSet.between()`.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Recurrences {
+    pub dates: Vec<SystemTime>,
+    /// Whether a limit stopped the expansion before all occurrences were found.
+    pub limited: bool,
+    pub reason: Option<LimitReason>,
+}
+
+/// A parsed or built rule set.
+#[derive(Debug, uniffi::Object)]
+#[uniffi::export(Display)]
+pub struct RecurrenceSet {
+    rrule_set: RRuleSet,
+}
+
+#[uniffi::export]
+impl RecurrenceSet {
+    /// Parses the rule set `rules`, like `DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rules can't be parsed.
+    #[uniffi::constructor]
+    pub fn new(rules: &str) -> Result<Self, RecurrenceError> {
+        Ok(Self::from_rrule_set(rules.parse()?))
+    }
+
+    /// Builds a rule set starting at `dt_start` in the timezone `time_zone`, like
+    /// `Europe/Berlin`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timezone is unknown or a rule is invalid.
+    #[uniffi::constructor]
+    pub fn build(
+        dt_start: SystemTime,
+        time_zone: &str,
+        rules: Vec<Rule>,
+        rdates: Vec<SystemTime>,
+        exdates: Vec<SystemTime>,
+    ) -> Result<Self, RecurrenceError> {
+        let (tz, _) = Tz::from_tzid(time_zone)?;
+        let dt_start = to_date(dt_start, tz);
+        let mut rrule_set = RRuleSet::new(dt_start);
+        for rule in &rules {
+            rrule_set = rrule_set.rrule(rule.to_rrule()?.validate(dt_start)?);
+        }
+        for rdate in rdates {
+            rrule_set = rrule_set.rdate(to_date(rdate, tz));
+        }
+        for exdate in exdates {
+            rrule_set = rrule_set.exdate(to_date(exdate, tz));
+        }
+        Ok(Self::from_rrule_set(rrule_set))
+    }
+
+    /// The start date of the set.
+    pub fn dt_start(&self) -> SystemTime {
+        (*self.rrule_set.get_dt_start()).into()
+    }
+
+    /// The name of the timezone of the start date.
+    pub fn time_zone(&self) -> String {
+        self.rrule_set.get_dt_start().timezone().name()
+    }
+
+    /// The rules of the set.
+    pub fn rules(&self) -> Vec<Rule> {
+        self.rrule_set.get_rrule().iter().map(Rule::from).collect()
+    }
+
+    /// The additional dates of the set.
+    pub fn rdates(&self) -> Vec<SystemTime> {
+        to_system_times(self.rrule_set.get_rdate())
+    }
+
+    /// The excluded dates of the set.
+    pub fn exdates(&self) -> Vec<SystemTime> {
+        to_system_times(self.rrule_set.get_exdate())
+    }
+
+    /// Returns the occurrences between `after` and `before`, inclusive.
+    pub fn between(&self, after: SystemTime, before: SystemTime, limits: Limits) -> Recurrences {
+        let validator_limits = ValidatorLimits::DEFAULT
+            .max_results(limits.max_results)
+            .max_iterations(limits.max_iterations);
+        let result = self
+            .rrule_set
+            .clone()
+            .limits(validator_limits)
+            .after(to_date(after, Tz::UTC))
+            .before(to_date(before, Tz::UTC))
+            .all_within_limits();
+        Recurrences {
+            dates: to_system_times(&result.dates),
+            limited: result.limited,
+            reason: result.limit_reason.map(Into::into),
+        }
+    }
+
+    /// Returns an iterator over the occurrences of the set.
+    pub fn iter(&self) -> Arc<RecurrenceIterator> {
+        Arc::new(RecurrenceIterator {
+            state: Mutex::new(IteratorState {
+                iter: self.rrule_set.into_iter(),
+                last: None,
+            }),
+        })
+    }
+}
+
+impl RecurrenceSet {
+    fn from_rrule_set(rrule_set: RRuleSet) -> Self {
+        // The limits stop the iteration of rules which never occur.
+        Self {
+            rrule_set: rrule_set.limit(),
+        }
+    }
+}
+
+impl Display for RecurrenceSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.rrule_set.fmt(f)
+    }
+}
+
+/// Iterates over the occurrences of a `RecurrenceSet`. Every call continues where
+/// the previous one stopped.
+#[derive(Debug, uniffi::Object)]
+pub struct RecurrenceIterator {
+    state: Mutex<IteratorState>,
+}
+
+#[derive(Debug)]
+struct IteratorState {
+    iter: RRuleSetIter,
+    /// The last occurrence returned, to skip the same date generated by several rules.
+    last: Option<DateTime<Tz>>,
+}
+
+#[uniffi::export]
+impl RecurrenceIterator {
+    /// Returns the next occurrence, or nothing once there are no more occurrences.
+    pub fn next(&self) -> Option<SystemTime> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let IteratorState { iter, last } = &mut *state;
+        let date = iter.find(|date| last.as_ref() != Some(date))?;
+        *last = Some(date);
+        Some(date.into())
+    }
+
+    /// Returns the next `n` occurrences, or fewer if there are no more
+    /// occurrences.
+    pub fn take(&self, n: u32) -> Vec<SystemTime> {
+        (0..n).map_while(|_| self.next()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uniffi_api::{Frequency, NWeekday, Weekday};
+    use chrono::{TimeZone, Utc};
+
+    const RULES: &str = "DTSTART;TZID=Europe/Berlin:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3";
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> SystemTime {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn parses_and_expands_rule_sets() {
+        let set = RecurrenceSet::new(RULES).unwrap();
+        assert_eq!(set.time_zone(), "Europe/Berlin");
+        assert_eq!(set.dt_start(), utc(2021, 1, 1, 8));
+        assert_eq!(
+            set.to_string(),
+            RULES.replace(";COUNT=3", ";COUNT=3;BYHOUR=9;BYMINUTE=0;BYSECOND=0")
+        );
+
+        let limits = Limits {
+            max_results: 2,
+            max_iterations: 100_000,
+        };
+        let recurrences = set.between(utc(2021, 1, 1, 0), utc(2021, 12, 31, 0), limits);
+        assert_eq!(
+            recurrences.dates,
+            vec![utc(2021, 1, 1, 8), utc(2021, 1, 2, 8)]
+        );
+        assert_eq!(recurrences.reason, Some(LimitReason::MaxResults));
+
+        let iter = set.iter();
+        assert_eq!(iter.next(), Some(utc(2021, 1, 1, 8)));
+        assert_eq!(iter.take(5), vec![utc(2021, 1, 2, 8), utc(2021, 1, 3, 8)]);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn builds_rule_sets_from_rules() {
+        let rule = Rule {
+            freq: Frequency::Monthly,
+            interval: 2,
+            count: Some(2),
+            until: None,
+            week_start: None,
+            by_set_pos: vec![],
+            by_month: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_week_no: vec![],
+            by_weekday: vec![NWeekday {
+                weekday: Weekday::Friday,
+                n: Some(-1),
+            }],
+            by_hour: vec![],
+            by_minute: vec![],
+            by_second: vec![],
+        };
+        let set = RecurrenceSet::build(
+            utc(2021, 1, 1, 8),
+            "Europe/Berlin",
+            vec![rule],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            set.iter().take(3),
+            vec![utc(2021, 1, 29, 8), utc(2021, 3, 26, 8)]
+        );
+        let rules = set.rules();
+        assert_eq!(
+            rules[0].by_weekday,
+            vec![NWeekday {
+                weekday: Weekday::Friday,
+                n: Some(-1),
+            }]
+        );
+        assert_eq!(rules[0].by_hour, vec![9]);
+        assert_eq!(rules[0].week_start, Some(Weekday::Monday));
+    }
+
+    #[test]
+    fn reports_errors_with_their_code() {
+        let err =
+            RecurrenceSet::new("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24").unwrap_err();
+        assert!(matches!(
+            err,
+            RecurrenceError::Parse { ref code, .. } if code == "parse.invalid_by_hour"
+        ));
+        let err = RecurrenceSet::build(utc(2021, 1, 1, 8), "Mars/Olympus", vec![], vec![], vec![])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RecurrenceError::Parse { ref code, .. } if code == "parse.invalid_timezone"
+        ));
+    }
+}
@@ -0,0 +1,297 @@
+use std::time::SystemTime;
+
+use chrono::Month;
+use rrule::{ParseError, RRule, Tz, Unvalidated};
+
+use super::{to_date, RecurrenceError};
+
+/// How often a rule recurs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Frequency {
+    Yearly,
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    Minutely,
+    Secondly,
+}
+
+impl From<Frequency> for rrule::Frequency {
+    fn from(freq: Frequency) -> Self {
+        match freq {
+            Frequency::Yearly => Self::Yearly,
+            Frequency::Monthly => Self::Monthly,
+            Frequency::Weekly => Self::Weekly,
+            Frequency::Daily => Self::Daily,
+            Frequency::Hourly => Self::Hourly,
+            Frequency::Minutely => Self::Minutely,
+            Frequency::Secondly => Self::Secondly,
+        }
+    }
+}
+
+impl From<rrule::Frequency> for Frequency {
+    fn from(freq: rrule::Frequency) -> Self {
+        match freq {
+            rrule::Frequency::Yearly => Self::Yearly,
+            rrule::Frequency::Monthly => Self::Monthly,
+            rrule::Frequency::Weekly => Self::Weekly,
+            rrule::Frequency::Daily => Self::Daily,
+            rrule::Frequency::Hourly => Self::Hourly,
+            rrule::Frequency::Minutely => Self::Minutely,
+            rrule::Frequency::Secondly => Self::Secondly,
+        }
+    }
+}
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<Weekday> for chrono::Weekday {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Monday => Self::Mon,
+            Weekday::Tuesday => Self::Tue,
+            Weekday::Wednesday => Self::Wed,
+            Weekday::Thursday => Self::Thu,
+            Weekday::Friday => Self::Fri,
+            Weekday::Saturday => Self::Sat,
+            Weekday::Sunday => Self::Sun,
+        }
+    }
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(weekday: chrono::Weekday) -> Self {
+        match weekday {
+            chrono::Weekday::Mon => Self::Monday,
+            chrono::Weekday::Tue => Self::Tuesday,
+            chrono::Weekday::Wed => Self::Wednesday,
+            chrono::Weekday::Thu => Self::Thursday,
+            chrono::Weekday::Fri => Self::Friday,
+            chrono::Weekday::Sat => Self::Saturday,
+            chrono::Weekday::Sun => Self::Sunday,
+        }
+    }
+}
+
+/// A weekday of `BYDAY`, optionally with its ordinal in the month or year, like
+/// `-1FR` for the last Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct NWeekday {
+    pub weekday: Weekday,
+    #[uniffi(default = None)]
+    pub n: Option<i16>,
+}
+
+impl From<NWeekday> for rrule::NWeekday {
+    fn from(weekday: NWeekday) -> Self {
+        Self::new(weekday.n, weekday.weekday.into())
+    }
+}
+
+impl From<rrule::NWeekday> for NWeekday {
+    fn from(weekday: rrule::NWeekday) -> Self {
+        match weekday {
+            rrule::NWeekday::Every(weekday) => Self {
+                weekday: weekday.into(),
+                n: None,
+            },
+            rrule::NWeekday::Nth(n, weekday) => Self {
+                weekday: weekday.into(),
+                n: Some(n),
+            },
+        }
+    }
+}
+
+/// The parts of a `RRULE`, to build a rule set, or returned by
+/// `RecurrenceSet.rules()`. The rule parts which are left empty are filled in
+/// from the start date when the set is built.
+///
+/// The `BYxxx` lists are `i32`s, as UniFFI passes a `Vec<u8>` as bytes.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Rule {
+    pub freq: Frequency,
+    #[uniffi(default = 1)]
+    pub interval: u16,
+    #[uniffi(default = None)]
+    pub count: Option<u32>,
+    #[uniffi(default = None)]
+    pub until: Option<SystemTime>,
+    #[uniffi(default = None)]
+    pub week_start: Option<Weekday>,
+    #[uniffi(default = [])]
+    pub by_set_pos: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_month: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_month_day: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_year_day: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_week_no: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_weekday: Vec<NWeekday>,
+    #[uniffi(default = [])]
+    pub by_hour: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_minute: Vec<i32>,
+    #[uniffi(default = [])]
+    pub by_second: Vec<i32>,
+}
+
+impl Rule {
+    pub(super) fn to_rrule(&self) -> Result<RRule<Unvalidated>, RecurrenceError> {
+        let by_month = self
+            .by_month
+            .iter()
+            .map(|&month| {
+                u8::try_from(month)
+                    .ok()
+                    .and_then(|month| Month::try_from(month).ok())
+                    .ok_or_else(|| ParseError::InvalidByMonth(month.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rrule = RRule::new(self.freq.into())
+            .interval(self.interval)
+            .by_set_pos(self.by_set_pos.clone())
+            .by_month(&by_month)
+            .by_month_day(narrow(&self.by_month_day, ParseError::InvalidByMonthDay)?)
+            .by_year_day(narrow(&self.by_year_day, ParseError::InvalidByYearDay)?)
+            .by_week_no(narrow(&self.by_week_no, ParseError::InvalidByWeekNo)?)
+            .by_weekday(self.by_weekday.iter().copied().map(Into::into).collect())
+            .by_hour(narrow(&self.by_hour, ParseError::InvalidByHour)?)
+            .by_minute(narrow(&self.by_minute, ParseError::InvalidByMinute)?)
+            .by_second(narrow(&self.by_second, ParseError::InvalidBySecond)?);
+        if let Some(count) = self.count {
+            rrule = rrule.count(count);
+        }
+        if let Some(until) = self.until {
+            rrule = rrule.until(to_date(until, Tz::UTC));
+        }
+        if let Some(week_start) = self.week_start {
+            rrule = rrule.week_start(week_start.into());
+        }
+        Ok(rrule)
+    }
+}
+
+impl From<&RRule> for Rule {
+    fn from(rrule: &RRule) -> Self {
+        Self {
+            freq: rrule.get_freq().into(),
+            interval: rrule.get_interval(),
+            count: rrule.get_count(),
+            until: rrule.get_until().map(|until| SystemTime::from(*until)),
+            week_start: Some(rrule.get_week_start().into()),
+            by_set_pos: rrule.get_by_set_pos().to_vec(),
+            by_month: widen(rrule.get_by_month()),
+            by_month_day: widen(rrule.get_by_month_day()),
+            by_year_day: widen(rrule.get_by_year_day()),
+            by_week_no: widen(rrule.get_by_week_no()),
+            by_weekday: rrule
+                .get_by_weekday()
+                .iter()
+                .copied()
+                .map(Into::into)
+                .collect(),
+            by_hour: widen(rrule.get_by_hour()),
+            by_minute: widen(rrule.get_by_minute()),
+            by_second: widen(rrule.get_by_second()),
+        }
+    }
+}
+
+/// Converts the values of a rule part to its type, or returns `error` with the
+/// first value that is out of range.
+fn narrow<T: TryFrom<i32>>(
+    values: &[i32],
+    error: fn(String) -> ParseError,
+) -> Result<Vec<T>, ParseError> {
+    values
+        .iter()
+        .map(|&value| T::try_from(value).map_err(|_| error(value.to_string())))
+        .collect()
+}
+
+fn widen<T: Copy + Into<i32>>(values: &[T]) -> Vec<i32> {
+    values.iter().map(|&value| value.into()).collect()
+}
+
+/// Returns `rule` as the value of a `RRULE`, like `FREQ=DAILY;COUNT=3`.
+///
+/// # Errors
+///
+/// Returns an error if a rule part is out of range.
+#[uniffi::export]
+pub fn rule_to_string(rule: Rule) -> Result<String, RecurrenceError> {
+    Ok(rule.to_rrule()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_rules() {
+        let rule = Rule {
+            freq: Frequency::Monthly,
+            interval: 2,
+            count: Some(2),
+            until: None,
+            week_start: None,
+            by_set_pos: vec![],
+            by_month: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_week_no: vec![],
+            by_weekday: vec![NWeekday {
+                weekday: Weekday::Friday,
+                n: Some(-1),
+            }],
+            by_hour: vec![],
+            by_minute: vec![],
+            by_second: vec![],
+        };
+        assert_eq!(
+            rule_to_string(rule).unwrap(),
+            "FREQ=MONTHLY;COUNT=2;INTERVAL=2;BYDAY=-1FR"
+        );
+    }
+
+    #[test]
+    fn reports_rule_parts_out_of_range() {
+        let rule = Rule {
+            freq: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            week_start: None,
+            by_set_pos: vec![],
+            by_month: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_week_no: vec![],
+            by_weekday: vec![],
+            by_hour: vec![300],
+            by_minute: vec![],
+            by_second: vec![],
+        };
+        let err = rule_to_string(rule).unwrap_err();
+        assert!(matches!(
+            err,
+            RecurrenceError::Parse { ref code, .. } if code == "parse.invalid_by_hour"
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Runs the Kotlin and Swift scripts in `tests/bindings` against the generated
+//! bindings. They need `kotlinc` with JNA on the `CLASSPATH`, and `swiftc`.
+//!
+//! A script whose compiler isn't installed is skipped, unless `CI` is set. Setting
+//! `UNIFFI_TESTS_DISABLE_EXTENSIONS` to `kts` or `swift` always skips it.
+use std::{env, io::ErrorKind, process::Command};
+
+use uniffi::{kotlin_test, swift_test, Result};
+
+type RunTest = fn(&str, &str, &str) -> Result<()>;
+
+/// Runs `script` with `run_test`, or skips it if `compiler` isn't installed.
+fn run_script(compiler: &str, version_arg: &str, run_test: RunTest, script: &str) -> Result<()> {
+    let extension = script.rsplit('.').next().unwrap_or_default();
+    let disabled = env::var("UNIFFI_TESTS_DISABLE_EXTENSIONS")
+        .is_ok_and(|extensions| extensions.split(',').any(|disabled| disabled == extension));
+    if disabled {
+        println!("skipping `{script}`, `{extension}` scripts are disabled");
+        return Ok(());
+    }
+    let missing = matches!(
+        Command::new(compiler).arg(version_arg).output(),
+        Err(err) if err.kind() == ErrorKind::NotFound
+    );
+    if missing && env::var_os("CI").is_none() {
+        println!("skipping `{script}`, `{compiler}` isn't installed");
+        return Ok(());
+    }
+    run_test(
+        env!("CARGO_TARGET_TMPDIR"),
+        env!("CARGO_PKG_NAME"),
+        &format!("{}/{script}", env!("CARGO_MANIFEST_DIR")),
+    )
+}
+
+#[test]
+fn kotlin_script_passes() -> Result<()> {
+    run_script(
+        "kotlinc",
+        "-version",
+        kotlin_test::run_test,
+        "tests/bindings/test_recurrence_set.kts",
+    )
+}
+
+#[test]
+fn swift_script_passes() -> Result<()> {
+    run_script(
+        "swiftc",
+        "--version",
+        swift_test::run_test,
+        "tests/bindings/test_recurrence_set.swift",
+    )
+}
@@ -0,0 +1,56 @@
+import java.time.Instant
+import uniffi.rust_recurrence_generator.*
+
+val rules = "DTSTART;TZID=Europe/Berlin:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3"
+
+RecurrenceSet(rules).use { set ->
+    assert(set.timeZone() == "Europe/Berlin")
+    assert(set.dtStart() == Instant.parse("2021-01-01T08:00:00Z"))
+
+    val recurrences = set.between(
+        Instant.parse("2021-01-01T00:00:00Z"),
+        Instant.parse("2021-12-31T00:00:00Z"),
+        Limits(maxResults = 2u),
+    )
+    assert(recurrences.dates == listOf(
+        Instant.parse("2021-01-01T08:00:00Z"),
+        Instant.parse("2021-01-02T08:00:00Z"),
+    ))
+    assert(recurrences.limited)
+    assert(recurrences.reason == LimitReason.MAX_RESULTS)
+
+    set.iter().use { iter ->
+        assert(iter.next() == Instant.parse("2021-01-01T08:00:00Z"))
+        assert(iter.take(5u).size == 2)
+        assert(iter.next() == null)
+    }
+}
+
+val rule = Rule(
+    freq = Frequency.MONTHLY,
+    interval = 2u,
+    count = 2u,
+    byWeekday = listOf(NWeekday(Weekday.FRIDAY, -1)),
+)
+assert(ruleToString(rule) == "FREQ=MONTHLY;COUNT=2;INTERVAL=2;BYDAY=-1FR")
+
+RecurrenceSet.build(
+    Instant.parse("2021-01-01T08:00:00Z"),
+    "Europe/Berlin",
+    listOf(rule),
+    listOf(),
+    listOf(),
+).use { set ->
+    assert(set.iter().take(3u) == listOf(
+        Instant.parse("2021-01-29T08:00:00Z"),
+        Instant.parse("2021-03-26T08:00:00Z"),
+    ))
+    assert(set.rules()[0].byHour == listOf(9))
+}
+
+try {
+    RecurrenceSet("DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24")
+    throw RuntimeException("Should have thrown")
+} catch (e: RecurrenceException.Parse) {
+    assert(e.code == "parse.invalid_by_hour")
+}
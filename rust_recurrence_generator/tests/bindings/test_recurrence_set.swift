@@ -0,0 +1,57 @@
+import Foundation
+import rust_recurrence_generator
+
+func date(_ iso: String) -> Date {
+    return ISO8601DateFormatter().date(from: iso)!
+}
+
+let rules = "DTSTART;TZID=Europe/Berlin:20210101T090000\nRRULE:FREQ=DAILY;COUNT=3"
+
+do {
+    let set = try! RecurrenceSet(rules: rules)
+    assert(set.timeZone() == "Europe/Berlin")
+    assert(set.dtStart() == date("2021-01-01T08:00:00Z"))
+
+    let recurrences = set.between(
+        after: date("2021-01-01T00:00:00Z"),
+        before: date("2021-12-31T00:00:00Z"),
+        limits: Limits(maxResults: 2)
+    )
+    assert(recurrences.dates == [date("2021-01-01T08:00:00Z"), date("2021-01-02T08:00:00Z")])
+    assert(recurrences.limited)
+    assert(recurrences.reason == .maxResults)
+
+    let iter = set.iter()
+    assert(iter.next() == date("2021-01-01T08:00:00Z"))
+    assert(iter.take(n: 5).count == 2)
+    assert(iter.next() == nil)
+}
+
+do {
+    let rule = Rule(
+        freq: .monthly,
+        interval: 2,
+        count: 2,
+        byWeekday: [NWeekday(weekday: .friday, n: -1)]
+    )
+    assert(try! ruleToString(rule: rule) == "FREQ=MONTHLY;COUNT=2;INTERVAL=2;BYDAY=-1FR")
+
+    let set = try! RecurrenceSet.build(
+        dtStart: date("2021-01-01T08:00:00Z"),
+        timeZone: "Europe/Berlin",
+        rules: [rule],
+        rdates: [],
+        exdates: []
+    )
+    assert(set.iter().take(n: 3) == [date("2021-01-29T08:00:00Z"), date("2021-03-26T08:00:00Z")])
+    assert(set.rules()[0].byHour == [9])
+}
+
+do {
+    _ = try RecurrenceSet(rules: "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24")
+    fatalError("Should have thrown")
+} catch let RecurrenceError.Parse(code, _) {
+    assert(code == "parse.invalid_by_hour")
+} catch {
+    fatalError("Unexpected error: \(error)")
+}
@@ -0,0 +1,178 @@
+// Exercises the C API through the generated header. Compiled and run by
+// `tests/c_program.rs`.
+#include <stdio.h>
+#include <string.h>
+
+#include "../src/headers/c_api.h"
+
+#define CHECK(condition)                                                       \
+  do {                                                                         \
+    if (!(condition)) {                                                        \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,        \
+              #condition);                                                     \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+// 2021-01-01T09:00:00Z
+static const int64_t JAN_1 = 1609491600;
+static const int64_t DAY = 86400;
+
+static const char *RULES = "DTSTART:20210101T090000Z\n"
+                           "RRULE:FREQ=DAILY\n"
+                           "RDATE:20210102T090000Z";
+
+static int test_next(void) {
+  RecurrenceGeneratorError error = {0};
+  RecurrenceSet *set = recurrence_generator_parse(RULES, &error);
+  CHECK(set != NULL);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(error.message == NULL);
+
+  int64_t seconds = 0;
+  for (int64_t day = 0; day < 3; day++) {
+    CHECK(recurrence_generator_next(set, &seconds, &error) ==
+          RECURRENCE_GENERATOR_STATUS_OK);
+    CHECK(seconds == JAN_1 + day * DAY);
+    CHECK(error.message == NULL);
+  }
+  CHECK(recurrence_generator_next(NULL, &seconds, &error) ==
+        RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+  CHECK(strcmp(error.message, "`set` or `seconds` is null") == 0);
+  recurrence_generator_error_free(&error);
+  CHECK(recurrence_generator_next(set, NULL, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+
+  recurrence_generator_free(set);
+  return 0;
+}
+
+static int test_next_until_exhausted(void) {
+  RecurrenceSet *set = recurrence_generator_parse(
+      "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;COUNT=2", NULL);
+  CHECK(set != NULL);
+
+  int64_t seconds = 0;
+  CHECK(recurrence_generator_next(set, &seconds, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(recurrence_generator_next(set, &seconds, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(seconds == JAN_1 + DAY);
+  // The last date is kept once there are no more.
+  CHECK(recurrence_generator_next(set, &seconds, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_DONE);
+  CHECK(recurrence_generator_next(set, &seconds, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_DONE);
+  CHECK(seconds == JAN_1 + DAY);
+
+  recurrence_generator_free(set);
+  return 0;
+}
+
+static int test_between(void) {
+  RecurrenceSet *set = recurrence_generator_parse(RULES, NULL);
+  CHECK(set != NULL);
+
+  RecurrenceGeneratorLimits limits = {0};
+  RecurrenceGeneratorTimestamps timestamps = {0};
+  RecurrenceGeneratorError error = {0};
+  RecurrenceGeneratorStatus status = recurrence_generator_between(
+      set, JAN_1 + DAY, JAN_1 + 3 * DAY, limits, &timestamps, &error);
+  CHECK(status == RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(timestamps.len == 3);
+  CHECK(!timestamps.limited);
+  for (size_t i = 0; i < timestamps.len; i++) {
+    CHECK(timestamps.seconds[i] == JAN_1 + (int64_t)(i + 1) * DAY);
+  }
+  recurrence_generator_timestamps_free(&timestamps);
+  CHECK(timestamps.seconds == NULL);
+  CHECK(timestamps.len == 0);
+
+  limits.max_results = 2;
+  status = recurrence_generator_between(set, JAN_1, JAN_1 + 30 * DAY, limits,
+                                        &timestamps, &error);
+  CHECK(status == RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(timestamps.len == 2);
+  CHECK(timestamps.limited);
+  recurrence_generator_timestamps_free(&timestamps);
+
+  // `between` doesn't move the position of `next`.
+  int64_t seconds = 0;
+  CHECK(recurrence_generator_next(set, &seconds, NULL) ==
+        RECURRENCE_GENERATOR_STATUS_OK);
+  CHECK(seconds == JAN_1);
+
+  status = recurrence_generator_between(set, INT64_MAX, INT64_MAX, limits,
+                                        &timestamps, &error);
+  CHECK(status == RECURRENCE_GENERATOR_STATUS_INVALID_DATE);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_INVALID_DATE);
+  CHECK(strcmp(error.message, "`after` is out of range") == 0);
+  CHECK(timestamps.seconds == NULL);
+  recurrence_generator_error_free(&error);
+  CHECK(error.message == NULL);
+
+  status = recurrence_generator_between(NULL, JAN_1, JAN_1, limits,
+                                        &timestamps, NULL);
+  CHECK(status == RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+  status = recurrence_generator_between(set, JAN_1, JAN_1, limits, NULL, NULL);
+  CHECK(status == RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+
+  recurrence_generator_free(set);
+  return 0;
+}
+
+static int test_parse_errors(void) {
+  RecurrenceGeneratorError error = {0};
+  CHECK(recurrence_generator_parse(
+            "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY;BYHOUR=24", &error) ==
+        NULL);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_INVALID_RULES);
+  CHECK(strstr(error.message, "BYHOUR") != NULL);
+  recurrence_generator_error_free(&error);
+
+  CHECK(recurrence_generator_parse(NULL, &error) == NULL);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_NULL_ARGUMENT);
+  recurrence_generator_error_free(&error);
+
+  CHECK(recurrence_generator_parse("DTSTART:\xff", &error) == NULL);
+  CHECK(error.code == RECURRENCE_GENERATOR_STATUS_INVALID_UTF8);
+  recurrence_generator_error_free(&error);
+
+  recurrence_generator_free(NULL);
+  recurrence_generator_timestamps_free(NULL);
+  recurrence_generator_error_free(NULL);
+  return 0;
+}
+
+static int test_generate(void) {
+  StringArray *array = recurrence_generator_generate(
+      RULES, "2021-01-01T00:00:00Z", "2021-01-02T23:00:00Z");
+  CHECK(array != NULL);
+  CHECK(array->error == NULL);
+  CHECK(array->len == 2);
+  CHECK(strcmp(array->strings[0], "2021-01-01T09:00:00+00:00") == 0);
+  free_string_array(array);
+
+  array = recurrence_generator_generate(RULES, NULL, "2021-01-02T23:00:00Z");
+  CHECK(array != NULL);
+  CHECK(array->len == 0);
+  CHECK(strcmp(array->error, "`after` is null") == 0);
+  free_string_array(array);
+
+  array = recurrence_generator_generate(RULES, "yesterday", "today");
+  CHECK(array != NULL);
+  CHECK(strcmp(array->error, "error parsing before/after dates") == 0);
+  free_string_array(array);
+  free_string_array(NULL);
+  return 0;
+}
+
+int main(void) {
+  int failed = test_next() || test_next_until_exhausted() || test_between() ||
+               test_parse_errors() || test_generate();
+  if (!failed) {
+    printf("all C API checks passed\n");
+  }
+  return failed;
+}
@@ -0,0 +1,78 @@
+//! Checks that the checked-in header is up to date, then compiles
+//! `tests/c_api_test.c` with it, links it against the static library and runs it.
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[cfg(target_os = "macos")]
+const NATIVE_LIBS: &[&str] = &["-framework", "CoreFoundation", "-lSystem"];
+#[cfg(not(target_os = "macos"))]
+const NATIVE_LIBS: &[&str] = &["-lpthread", "-ldl", "-lm"];
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/c_api.h"));
+    let checked_in = include_str!("../src/headers/c_api.h");
+    assert!(
+        generated == checked_in,
+        "`src/headers/c_api.h` is out of date, run `make update_header`"
+    );
+}
+
+#[test]
+fn c_program_passes() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // The test binary is in `target/<profile>/deps`.
+    let exe = env::current_exe().expect("the path of the test binary");
+    let profile_dir = exe
+        .parent()
+        .and_then(|deps| deps.parent())
+        .expect("the profile directory");
+    let release = profile_dir.ends_with("release");
+    // Cargo doesn't build the static library for the tests, so it's built here. Its
+    // own target directory keeps the dependencies, which may be built with other
+    // features than for the workspace, apart from those of the other tests.
+    let target_dir = profile_dir
+        .parent()
+        .expect("the target directory")
+        .join("c_api_test");
+
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo
+        .args([
+            "build",
+            "--package",
+            "rust_recurrence_generator",
+            "--target-dir",
+        ])
+        .arg(&target_dir);
+    if release {
+        cargo.arg("--release");
+    }
+    let built = cargo.status().expect("cargo");
+    assert!(built.success(), "building the static library failed");
+
+    let output_dir = target_dir.join(if release { "release" } else { "debug" });
+    let library = output_dir.join("librust_recurrence_generator.a");
+    let program = output_dir.join("c_api_test");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".into());
+    let status = Command::new(compiler)
+        .args(["-std=c99", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .arg(manifest_dir.join("tests/c_api_test.c"))
+        .arg(&library)
+        .args(NATIVE_LIBS)
+        .status()
+        .expect("a C compiler");
+    assert!(status.success(), "compiling the C program failed");
+
+    let output = Command::new(&program)
+        .output()
+        .expect("the compiled C program");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}